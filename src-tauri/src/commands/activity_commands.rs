@@ -1,5 +1,5 @@
-use crate::database::{Activity, add_activity, get_recent_activities, clear_activities, get_database_path};
-use rusqlite::Connection;
+use crate::database::{Activity, add_activity, get_recent_activities, clear_activities, get_connection};
+use crate::error::TrackerError;
 use tauri::AppHandle;
 use chrono::Utc;
 
@@ -11,9 +11,8 @@ pub async fn add_activity_entry(
     mod_name: Option<String>,
     description: String,
     metadata: Option<String>,
-) -> Result<i64, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<i64, TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     let activity = Activity {
         id: None,
@@ -25,26 +24,24 @@ pub async fn add_activity_entry(
         metadata,
     };
 
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())
+    Ok(add_activity(Some(&app_handle), &conn, &activity)?)
 }
 
 #[tauri::command]
 pub async fn get_activities(
     app_handle: AppHandle,
     limit: Option<i64>,
-) -> Result<Vec<Activity>, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<Vec<Activity>, TrackerError> {
+    let conn = get_connection(&app_handle)?;
     
-    get_recent_activities(&conn, limit).map_err(|e| e.to_string())
+    Ok(get_recent_activities(&conn, limit)?)
 }
 
 #[tauri::command]
 pub async fn clear_activity_history(
     app_handle: AppHandle,
-) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
     
-    clear_activities(&conn).map_err(|e| e.to_string())
+    Ok(clear_activities(&conn)?)
 }
\ No newline at end of file
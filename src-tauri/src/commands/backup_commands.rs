@@ -0,0 +1,108 @@
+use crate::database::{
+    activities::{add_activity, Activity},
+    backup::{self, Backup, ImportMode, ImportSummary},
+    get_connection,
+};
+use crate::error::TrackerError;
+use crate::update_service;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+use std::fs;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub mods: usize,
+    pub webhooks: usize,
+    pub includes_api_key: bool,
+}
+
+/// Writes the tracker's mods, webhooks, assignments, templates, settings and
+/// activity history to `path` as a versioned JSON backup.
+#[tauri::command]
+pub fn export_backup(
+    app_handle: AppHandle,
+    path: String,
+    include_api_key: bool,
+) -> Result<ExportSummary, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    let backup = backup::export_backup(&conn, include_api_key)?;
+
+    fs::write(&path, serde_json::to_string_pretty(&backup)?)?;
+    println!("Exported backup to {}", path);
+
+    let summary = ExportSummary {
+        path,
+        mods: backup.mods.len(),
+        webhooks: backup.webhooks.len(),
+        includes_api_key: include_api_key,
+    };
+
+    let activity = Activity {
+        id: None,
+        activity_type: "backup_exported".to_string(),
+        mod_id: None,
+        mod_name: None,
+        description: format!(
+            "Exported a backup of {} mods and {} webhooks",
+            summary.mods, summary.webhooks
+        ),
+        timestamp: Utc::now(),
+        metadata: Some(json!(summary).to_string()),
+    };
+    add_activity(Some(&app_handle), &conn, &activity)?;
+
+    Ok(summary)
+}
+
+/// Restores a backup written by `export_backup`. The file is fully validated
+/// before anything is changed, and the import runs in a single transaction.
+#[tauri::command]
+pub fn import_backup(
+    app_handle: AppHandle,
+    path: String,
+    mode: ImportMode,
+) -> Result<ImportSummary, TrackerError> {
+    let contents = fs::read_to_string(&path)?;
+    let backup: Backup = serde_json::from_str(&contents)
+        .map_err(|e| TrackerError::InvalidInput(format!("Invalid backup file: {}", e)))?;
+    backup::validate_backup(&backup).map_err(TrackerError::InvalidInput)?;
+
+    let mut conn = get_connection(&app_handle)?;
+    let summary = backup::import_backup(&mut conn, &backup, mode)?;
+    println!("Imported backup from {} ({:?} mode)", path, mode);
+
+    let activity = Activity {
+        id: None,
+        activity_type: "backup_imported".to_string(),
+        mod_id: None,
+        mod_name: None,
+        description: format!(
+            "Restored {} mods and {} webhooks from a backup",
+            summary.mods_imported, summary.webhooks_imported
+        ),
+        timestamp: Utc::now(),
+        metadata: Some(
+            json!({
+                "path": path,
+                "mode": mode,
+                "exported_at": backup.exported_at,
+                "summary": summary,
+            })
+            .to_string(),
+        ),
+    };
+    add_activity(Some(&app_handle), &conn, &activity)?;
+
+    // The restored settings may include a different update interval
+    update_service::reschedule(&app_handle);
+    app_handle
+        .emit_all("backup_imported", &summary)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to emit backup imported event: {}", e);
+        });
+
+    Ok(summary)
+}
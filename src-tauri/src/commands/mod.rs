@@ -2,4 +2,7 @@ pub mod mod_commands;
 pub mod webhook_commands;
 pub mod webhook_template_commands;
 pub mod settings_commands;
-pub mod activity_commands;
\ No newline at end of file
+pub mod activity_commands;
+pub mod update_service_commands;
+pub mod backup_commands;
+pub mod outbox_commands;
\ No newline at end of file
@@ -1,79 +1,24 @@
+use crate::curseforge::{CurseForgeClient, CurseForgeModData, ModFile};
 use crate::database::{
     activities::{add_activity, Activity},
-    ensure_database_exists, get_database_path,
-    mods::{self, Mod, ModWithWebhooks},
+    get_connection,
+    mods::{self, Mod, ModFileFilters, ModWithWebhooks},
+    outbox, webhooks,
 };
+use crate::error::TrackerError;
+use crate::notifications::changelog::{self, ChangelogFormat};
+use crate::update_service;
 use chrono::Utc;
-use html_escape;
-use reqwest::header::HeaderMap;
-use rusqlite::{params, Connection, OptionalExtension, Result};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tauri::AppHandle;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CurseForgeResponse {
-    data: CurseForgeModData,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CurseForgeModData {
-    pub id: i64,
-    pub name: String,
-    #[serde(rename = "dateModified")]
-    pub date_modified: String,
-    #[serde(rename = "dateReleased")]
-    pub date_released: String,
-    #[serde(rename = "dateCreated")]
-    pub date_created: String,
-    pub authors: Vec<ModAuthor>,
-    #[serde(rename = "latestFiles")]
-    pub latest_files: Vec<ModFile>,
-    #[serde(rename = "gameId")]
-    pub game_id: i64,
-    pub logo: Option<ModLogo>,
-    pub links: ModLinks,
-    #[serde(rename = "mainFileId")]
-    pub main_file_id: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ModLinks {
-    #[serde(rename = "websiteUrl")]
-    pub website_url: Option<String>,
-    #[serde(rename = "wikiUrl")]
-    pub wiki_url: Option<String>,
-    #[serde(rename = "issuesUrl")]
-    pub issues_url: Option<String>,
-    #[serde(rename = "sourceUrl")]
-    pub source_url: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ModAuthor {
-    pub name: String,
-    pub url: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ModFile {
-    #[serde(rename = "fileName")]
-    pub file_name: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ModLogo {
-    pub id: i64,
-    #[serde(rename = "modId")]
-    pub mod_id: i64,
-    pub title: String,
-    pub description: String,
-    #[serde(rename = "thumbnailUrl")]
-    pub thumbnail_url: String,
-    pub url: String,
-}
+// Number of mod IDs sent per bulk `POST /v1/mods` request
+const BULK_MODS_CHUNK_SIZE: usize = 50;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModUpdateInfo {
     pub mod_id: i64,
     pub curseforge_id: i64,
@@ -84,52 +29,49 @@ pub struct ModUpdateInfo {
     pub latest_file_name: String,
     pub logo_url: Option<String>,
     pub changelog: Option<String>,
+    /// The changelog as CurseForge returned it, kept to convert for each service
+    #[serde(default)]
+    pub changelog_html: Option<String>,
+    #[serde(default)]
+    pub file_id: Option<i64>,
+    #[serde(default)]
+    pub file_display_name: Option<String>,
+    #[serde(default)]
+    pub file_date: Option<String>,
+    #[serde(default)]
+    pub release_type: Option<String>,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    /// Every author CurseForge lists; `mod_author` is the first of them
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// The mod's latest files across game versions, newest first
+    #[serde(default)]
+    pub files: Vec<UpdateFile>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CurseForgeGameResponse {
-    data: CurseForgeGameData,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CurseForgeGameData {
-    id: i64,
-    name: String,
+/// One of the files CurseForge lists as a mod's latest, as templates see it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateFile {
+    pub id: i64,
+    pub display_name: String,
+    pub file_name: String,
+    pub file_date: String,
+    pub release_type: String,
+    pub game_versions: Vec<String>,
 }
 
-async fn get_game_name(
-    client: &reqwest::Client,
-    game_id: i64,
-    api_key: &str,
-) -> Result<String, String> {
-    // Hardcoded game ID for ARK: Survival Ascended due to CurseForge API bug
-    // See: https://github.com/CurseForge/APIFeedback/issues/XXX
-    if game_id == 83374 {
-        return Ok("ARK: Survival Ascended".to_string());
-    }
-
-    let mut headers = HeaderMap::new();
-    headers.insert("x-api-key", api_key.parse().unwrap());
-
-    let url = format!("https://api.curseforge.com/v1/games/{}", game_id);
-
-    let response = client
-        .get(url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to fetch game details: {}",
-            response.status()
-        ));
+impl From<&ModFile> for UpdateFile {
+    fn from(file: &ModFile) -> Self {
+        UpdateFile {
+            id: file.id,
+            display_name: file.display_name.clone(),
+            file_name: file.file_name.clone(),
+            file_date: file.file_date.clone(),
+            release_type: file.release_type_name().to_string(),
+            game_versions: file.game_versions.clone(),
+        }
     }
-
-    let game_data: CurseForgeGameResponse = response.json().await.map_err(|e| e.to_string())?;
-
-    Ok(game_data.data.name)
 }
 
 #[tauri::command]
@@ -137,90 +79,67 @@ pub async fn add_mod(
     app_handle: AppHandle,
     curseforge_id: i64,
     api_key: String,
-) -> Result<ModWithWebhooks, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<ModWithWebhooks, TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     // Check for existing mod
-    let existing_mod = conn.query_row(
-        "SELECT id FROM mods WHERE curseforge_id = ?1",
-        [curseforge_id],
-        |_| Ok(()),
-    );
-
-    if let Ok(_) = existing_mod {
-        return Err("A mod with this CurseForge ID already exists.".to_string());
+    let existing_mod = conn
+        .query_row(
+            "SELECT id FROM mods WHERE curseforge_id = ?1",
+            [curseforge_id],
+            |_| Ok(()),
+        )
+        .optional()?;
+
+    if existing_mod.is_some() {
+        return Err(TrackerError::ModAlreadyTracked { curseforge_id });
     }
 
-    // Create HTTP client for reuse
-    let client = reqwest::Client::new();
-    let mut headers = HeaderMap::new();
-    headers.insert("x-api-key", api_key.parse().unwrap());
+    let client = app_handle.state::<CurseForgeClient>().with_api_key(&api_key);
 
     // Fetch mod data
-    let url = format!("https://api.curseforge.com/v1/mods/{}", curseforge_id);
-
-    let response = client
-        .get(url)
-        .headers(headers.clone())
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !response.status().is_success() {
-        if response.status().as_u16() == 404 {
-            return Err("Mod not found on CurseForge.".to_string());
-        }
-        return Err(format!(
-            "Failed to fetch mod from CurseForge: {}",
-            response.status()
-        ));
-    }
-
-    let curse_data: CurseForgeResponse = response.json().await.map_err(|e| e.to_string())?;
+    let curse_data = client.get_mod(curseforge_id).await?;
 
     // Get page URL from the response
-    let page_url = curse_data.data.links.website_url.unwrap_or_default();
+    let page_url = curse_data.links.website_url.clone().unwrap_or_default();
 
     // Fetch game name
-    let game_name = get_game_name(&client, curse_data.data.game_id, &api_key).await?;
-    println!(
-        "Found game: {} (ID: {})",
-        game_name, curse_data.data.game_id
-    );
+    let game_name = client.get_game_name(curse_data.game_id).await?;
+    println!("Found game: {} (ID: {})", game_name, curse_data.game_id);
 
     let mod_data = Mod {
         id: None,
         curseforge_id,
-        name: curse_data.data.name.clone(),
+        name: curse_data.name.clone(),
         game_name: game_name.clone(),
-        last_updated: curse_data.data.date_modified.clone(),
+        last_updated: curse_data.date_modified.clone(),
         page_url: Some(page_url.clone()),
+        main_file_id: Some(curse_data.main_file_id),
+        latest_file_ids: curse_data.file_ids(),
+        filters: ModFileFilters::default(),
     };
 
-    ensure_database_exists(&db_path).map_err(|e| e.to_string())?;
-
-    let mod_id = mods::insert_mod(&conn, &mod_data).map_err(|e| e.to_string())?;
+    let mod_id = mods::insert_mod(&conn, &mod_data)?;
 
     // Log activity for mod addition
     let activity = Activity {
         id: None,
         activity_type: "mod_added".to_string(),
         mod_id: Some(mod_id),
-        mod_name: Some(curse_data.data.name.clone()),
-        description: format!("Added mod \"{}\"", curse_data.data.name),
+        mod_name: Some(curse_data.name.clone()),
+        description: format!("Added mod \"{}\"", curse_data.name),
         timestamp: Utc::now(),
         metadata: Some(
             json!({
                 "game": game_name,
                 "curseforge_id": curseforge_id,
-                "initial_version_date": curse_data.data.date_modified,
+                "initial_version_date": curse_data.date_modified,
                 "page_url": page_url,
             })
             .to_string(),
         ),
     };
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
+    add_activity(Some(&app_handle), &conn, &activity)?;
 
     let mut mod_with_webhooks = ModWithWebhooks {
         mod_info: mod_data,
@@ -235,148 +154,436 @@ pub async fn add_mod(
 pub async fn check_mod_update(
     app_handle: AppHandle,
     mod_id: i64,
-    curseforge_id: i64,
-    current_last_updated: String,
     api_key: String,
-) -> Result<Option<ModUpdateInfo>, String> {
-    let client = reqwest::Client::new();
-    let mut headers = HeaderMap::new();
-    headers.insert("x-api-key", api_key.parse().unwrap());
-
-    let url = format!("https://api.curseforge.com/v1/mods/{}", curseforge_id);
-
-    let response = client
-        .get(&url)
-        .headers(headers.clone())
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to fetch mod from CurseForge: {}",
-            response.status()
-        ));
+) -> Result<Option<ModUpdateInfo>, TrackerError> {
+    let tracked = {
+        let conn = get_connection(&app_handle)?;
+        mods::get_mod(&conn, mod_id)
+            .optional()?
+            .ok_or(TrackerError::NotFound {
+                entity: "Mod",
+                id: mod_id,
+            })?
+    };
+
+    let client = app_handle.state::<CurseForgeClient>().with_api_key(&api_key);
+    check_for_mod_update(&app_handle, &client, &tracked).await
+}
+
+/// What changed on CurseForge since a tracked mod was last checked.
+enum ModChange {
+    Unchanged,
+    /// No file IDs were recorded yet, e.g. for mods tracked before file-based
+    /// detection. The current files become the baseline without a notification.
+    Baseline,
+    /// Only the mod's metadata (description, logo, categories, ...) was edited.
+    MetadataOnly,
+    /// New files were published, but none of them match the mod's filters.
+    FilteredOut,
+    /// A file newer than any seen before was published.
+    NewFile(i64),
+}
+
+fn release_type_rank(release_type: &str) -> Option<i64> {
+    match release_type.to_lowercase().as_str() {
+        "release" => Some(1),
+        "beta" => Some(2),
+        "alpha" => Some(3),
+        _ => None,
     }
+}
 
-    let curse_data: CurseForgeResponse = response.json().await.map_err(|e| e.to_string())?;
-
-    let new_date = curse_data.data.date_modified.clone();
-
-    if new_date != current_last_updated {
-        let db_path = get_database_path(&app_handle);
-        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-
-        // Get changelog for the latest file
-        let changelog_url = format!(
-            "https://api.curseforge.com/v1/mods/{}/files/{}/changelog",
-            curseforge_id, curse_data.data.main_file_id
-        );
-
-        let changelog_response = client
-            .get(&changelog_url)
-            .headers(headers.clone())
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let changelog_text = if changelog_response.status().is_success() {
-            let changelog: serde_json::Value =
-                changelog_response.json().await.map_err(|e| e.to_string())?;
-
-            changelog["data"].as_str().map(|html| {
-                html_escape::decode_html_entities(html)
-                    .to_string()
-                    .replace(|c: char| !c.is_ascii() && !c.is_whitespace(), "")
-                    .replace("<p>", "") // Remove opening p tags
-                    .replace("</p>", "\n") // Replace closing p tags with newlines
-                    .replace("<br>", "\n") // Replace br tags with newlines
-                    .replace("<br/>", "\n") // Replace self-closing br tags
-                    .replace("<br />", "\n") // Replace self-closing br tags with space
-                    .trim() // Remove any leading/trailing whitespace
-                    .to_string()
-            })
-        } else {
-            None
-        };
+/// Returns whether a file satisfies all of the mod's filters. Game versions and
+/// mod loaders are both listed in the file's `gameVersions`.
+fn file_matches_filters(file: &ModFile, filters: &ModFileFilters) -> bool {
+    let targets = |wanted: &Option<String>| {
+        wanted.as_ref().map_or(true, |wanted| {
+            file.game_versions
+                .iter()
+                .any(|version| version.eq_ignore_ascii_case(wanted.trim()))
+        })
+    };
+
+    let stable_enough = filters
+        .release_type
+        .as_deref()
+        .and_then(release_type_rank)
+        .map_or(true, |max_rank| file.release_type <= max_rank);
 
-        mods::update_mod_last_updated(&conn, mod_id, &new_date).map_err(|e| e.to_string())?;
+    targets(&filters.game_version) && targets(&filters.mod_loader) && stable_enough
+}
 
-        // Extract the latest file info
-        let latest_file = curse_data
-            .data
+fn detect_change(tracked: &Mod, curse_data: &CurseForgeModData) -> ModChange {
+    if tracked.main_file_id.is_none() {
+        return ModChange::Baseline;
+    }
+
+    // CurseForge file IDs only ever increase, so any ID above the highest one we
+    // have seen is a newly uploaded file rather than a re-listed older one.
+    let newest_known = tracked
+        .latest_file_ids
+        .iter()
+        .copied()
+        .chain(tracked.main_file_id)
+        .max()
+        .unwrap_or_default();
+
+    let new_files: Vec<i64> = curse_data
+        .file_ids()
+        .into_iter()
+        .filter(|id| *id > newest_known)
+        .collect();
+
+    // Without filters the main file counts even if it isn't in `latestFiles`;
+    // with filters only files whose details we can inspect are considered.
+    let matching: Vec<i64> = if tracked.filters.is_empty() {
+        new_files.clone()
+    } else {
+        curse_data
             .latest_files
-            .first()
-            .ok_or_else(|| "No files found for mod".to_string())?;
+            .iter()
+            .filter(|file| new_files.contains(&file.id))
+            .filter(|file| file_matches_filters(file, &tracked.filters))
+            .map(|file| file.id)
+            .collect()
+    };
+
+    if matching.contains(&curse_data.main_file_id) {
+        ModChange::NewFile(curse_data.main_file_id)
+    } else if let Some(newest) = matching.into_iter().max() {
+        ModChange::NewFile(newest)
+    } else if !new_files.is_empty() {
+        ModChange::FilteredOut
+    } else if curse_data.date_modified != tracked.last_updated {
+        ModChange::MetadataOnly
+    } else {
+        ModChange::Unchanged
+    }
+}
+
+/// Fetches the latest mod data from CurseForge and compares its files with the
+/// ones recorded for `tracked`. Used by the `check_mod_update` command for
+/// one-off checks of a single mod.
+pub async fn check_for_mod_update(
+    app_handle: &AppHandle,
+    client: &CurseForgeClient,
+    tracked: &Mod,
+) -> Result<Option<ModUpdateInfo>, TrackerError> {
+    let curse_data = client.get_mod(tracked.curseforge_id).await?;
+    apply_mod_check(app_handle, client, tracked, curse_data).await
+}
+
+/// Outcome of checking a single tracked mod as part of a batched update check.
+#[derive(Debug)]
+pub struct ModCheckResult {
+    pub mod_id: i64,
+    pub mod_name: String,
+    pub result: Result<Option<ModUpdateInfo>, TrackerError>,
+}
+
+/// Checks all given mods for updates using CurseForge's bulk `POST /v1/mods`
+/// endpoint, requesting `BULK_MODS_CHUNK_SIZE` mods at a time. Changelogs are only
+/// fetched for mods that gained a new file since the last check.
+pub async fn check_mods_for_updates(
+    app_handle: &AppHandle,
+    client: &CurseForgeClient,
+    tracked_mods: Vec<Mod>,
+) -> Vec<ModCheckResult> {
+    let mut results = Vec::with_capacity(tracked_mods.len());
+
+    for chunk in tracked_mods.chunks(BULK_MODS_CHUNK_SIZE) {
+        let curseforge_ids: Vec<i64> = chunk.iter().map(|m| m.curseforge_id).collect();
+
+        let mut fetched: HashMap<i64, CurseForgeModData> =
+            match client.get_mods(&curseforge_ids).await {
+                Ok(fetched) => fetched.into_iter().map(|data| (data.id, data)).collect(),
+                Err(e) => {
+                    println!("Bulk mod request failed: {}", e);
+                    for tracked in chunk {
+                        results.push(ModCheckResult {
+                            mod_id: tracked.id.unwrap_or_default(),
+                            mod_name: tracked.name.clone(),
+                            result: Err(e.clone()),
+                        });
+                    }
+                    continue;
+                }
+            };
+
+        for tracked in chunk {
+            let mod_id = match tracked.id {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let result = match fetched.remove(&tracked.curseforge_id) {
+                None => Err(TrackerError::ModNotFound {
+                    curseforge_id: tracked.curseforge_id,
+                }),
+                Some(data) => apply_mod_check(app_handle, client, tracked, data).await,
+            };
+
+            results.push(ModCheckResult {
+                mod_id,
+                mod_name: tracked.name.clone(),
+                result,
+            });
+        }
+    }
 
-        let author_name = curse_data
-            .data
+    results
+}
+
+/// Records the outcome of comparing `curse_data` against `tracked`. Returns update
+/// info only when a new file was published; metadata-only edits are logged as a
+/// quieter `mod_metadata_updated` activity without triggering notifications.
+async fn apply_mod_check(
+    app_handle: &AppHandle,
+    client: &CurseForgeClient,
+    tracked: &Mod,
+    curse_data: CurseForgeModData,
+) -> Result<Option<ModUpdateInfo>, TrackerError> {
+    let mod_id = tracked
+        .id
+        .ok_or_else(|| TrackerError::Internal("Mod has no database ID".to_string()))?;
+
+    match detect_change(tracked, &curse_data) {
+        ModChange::Unchanged => Ok(None),
+        ModChange::Baseline => {
+            let conn = get_connection(app_handle)?;
+            mods::update_mod_files(
+                &conn,
+                mod_id,
+                &curse_data.date_modified,
+                curse_data.main_file_id,
+                &curse_data.file_ids(),
+            )?;
+            Ok(None)
+        }
+        ModChange::MetadataOnly => {
+            record_metadata_update(app_handle, tracked, mod_id, &curse_data)?;
+            Ok(None)
+        }
+        ModChange::FilteredOut => {
+            record_filtered_update(app_handle, mod_id, &curse_data)?;
+            Ok(None)
+        }
+        ModChange::NewFile(file_id) => {
+            record_mod_update(app_handle, client, tracked, mod_id, file_id, curse_data)
+                .await
+                .map(Some)
+        }
+    }
+}
+
+fn record_metadata_update(
+    app_handle: &AppHandle,
+    tracked: &Mod,
+    mod_id: i64,
+    curse_data: &CurseForgeModData,
+) -> Result<(), TrackerError> {
+    let conn = get_connection(app_handle)?;
+
+    mods::update_mod_last_updated(&conn, mod_id, &curse_data.date_modified)?;
+
+    let activity = Activity {
+        id: None,
+        activity_type: "mod_metadata_updated".to_string(),
+        mod_id: Some(mod_id),
+        mod_name: Some(curse_data.name.clone()),
+        description: format!("\"{}\" page details were edited", curse_data.name),
+        timestamp: Utc::now(),
+        metadata: Some(
+            json!({
+                "old_version_date": tracked.last_updated,
+                "new_version_date": curse_data.date_modified,
+                "main_file_id": curse_data.main_file_id,
+            })
+            .to_string(),
+        ),
+    };
+    add_activity(Some(app_handle), &conn, &activity)?;
+
+    Ok(())
+}
+
+fn record_filtered_update(
+    app_handle: &AppHandle,
+    mod_id: i64,
+    curse_data: &CurseForgeModData,
+) -> Result<(), TrackerError> {
+    let conn = get_connection(app_handle)?;
+
+    mods::update_mod_files(
+        &conn,
+        mod_id,
+        &curse_data.date_modified,
+        curse_data.main_file_id,
+        &curse_data.file_ids(),
+    )?;
+
+    let activity = Activity {
+        id: None,
+        activity_type: "mod_update_filtered".to_string(),
+        mod_id: Some(mod_id),
+        mod_name: Some(curse_data.name.clone()),
+        description: format!(
+            "New file for \"{}\" doesn't match its filters",
+            curse_data.name
+        ),
+        timestamp: Utc::now(),
+        metadata: Some(
+            json!({
+                "main_file_id": curse_data.main_file_id,
+                "new_version_date": curse_data.date_modified,
+            })
+            .to_string(),
+        ),
+    };
+    add_activity(Some(app_handle), &conn, &activity)?;
+
+    Ok(())
+}
+
+/// Fetches the changelog for a newly published file, stores the mod's new file
+/// IDs, queues a notification for every enabled assigned webhook in the outbox
+/// and logs the `mod_updated` activity.
+async fn record_mod_update(
+    app_handle: &AppHandle,
+    client: &CurseForgeClient,
+    tracked: &Mod,
+    mod_id: i64,
+    file_id: i64,
+    curse_data: CurseForgeModData,
+) -> Result<ModUpdateInfo, TrackerError> {
+    let curseforge_id = curse_data.id;
+    let new_date = curse_data.date_modified.clone();
+    let current_last_updated = tracked.last_updated.clone();
+
+    // Get changelog for the new file
+    let changelog_html = client.get_mod_file_changelog(curseforge_id, file_id).await?;
+
+    let changelog_text = changelog_html
+        .as_deref()
+        .map(|html| changelog::convert(html, ChangelogFormat::PlainText))
+        .filter(|text| !text.is_empty());
+
+    // Extract the new file's info, falling back to the first listed file
+    let latest_file = curse_data
+        .latest_files
+        .iter()
+        .find(|file| file.id == file_id)
+        .or_else(|| curse_data.latest_files.first())
+        .ok_or_else(|| TrackerError::Internal("No files found for mod".to_string()))?;
+
+    let mut files: Vec<UpdateFile> = curse_data
+        .latest_files
+        .iter()
+        .map(UpdateFile::from)
+        .collect();
+    files.sort_by(|a, b| b.file_date.cmp(&a.file_date));
+
+    let author_name = curse_data
+        .authors
+        .first()
+        .map(|author| author.name.clone())
+        .unwrap_or_else(|| "Unknown Author".to_string());
+
+    // Get logo URL if available
+    let logo_url = curse_data.logo.as_ref().map(|logo| logo.url.clone());
+
+    let update = ModUpdateInfo {
+        mod_id,
+        curseforge_id,
+        name: curse_data.name.clone(),
+        old_update_time: current_last_updated.clone(),
+        new_update_time: new_date.clone(),
+        mod_author: author_name.clone(),
+        latest_file_name: latest_file.file_name.clone(),
+        logo_url: logo_url.clone(),
+        changelog: changelog_text.clone(),
+        changelog_html: changelog_html.clone(),
+        file_id: Some(latest_file.id),
+        file_display_name: Some(latest_file.display_name.clone()),
+        file_date: Some(latest_file.file_date.clone()),
+        release_type: Some(latest_file.release_type_name().to_string()),
+        game_versions: latest_file.game_versions.clone(),
+        authors: curse_data
             .authors
-            .first()
+            .iter()
             .map(|author| author.name.clone())
-            .unwrap_or_else(|| "Unknown Author".to_string());
-
-        // Get logo URL if available
-        let logo_url = curse_data.data.logo.map(|logo| logo.url);
-
-        // Always log the mod update activity
-        let activity = Activity {
-            id: None,
-            activity_type: "mod_updated".to_string(),
-            mod_id: Some(mod_id),
-            mod_name: Some(curse_data.data.name.clone()),
-            description: format!("\"{}\" has been updated", curse_data.data.name),
-            timestamp: Utc::now(),
-            metadata: Some(
-                json!({
-                    "old_version_date": current_last_updated,
-                    "new_version_date": new_date,
-                    "author": author_name.clone(),
-                    "latest_file": latest_file.file_name.clone(),
-                    "logo_url": logo_url,
-                    "page_url": curse_data.data.links.website_url,
-                    "changelog": changelog_text,
-                })
-                .to_string(),
-            ),
-        };
-        add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
-
-        Ok(Some(ModUpdateInfo {
-            mod_id,
-            curseforge_id,
-            name: curse_data.data.name,
-            old_update_time: current_last_updated,
-            new_update_time: new_date,
-            mod_author: author_name,
-            latest_file_name: latest_file.file_name.clone(),
-            logo_url,
-            changelog: changelog_text,
-        }))
-    } else {
-        Ok(None)
+            .collect(),
+        files,
+    };
+
+    let mut conn = get_connection(app_handle)?;
+
+    // Storing the new files and queueing the notifications in one transaction means
+    // an update is never marked as seen without its notifications being saved
+    let tx = conn.transaction()?;
+    mods::update_mod_files(
+        &tx,
+        mod_id,
+        &new_date,
+        curse_data.main_file_id,
+        &curse_data.file_ids(),
+    )?;
+    let update_info = serde_json::to_string(&update)?;
+    for webhook in webhooks::get_mod_webhooks(&tx, mod_id)?
+        .iter()
+        .filter(|w| w.enabled)
+    {
+        if let Some(webhook_id) = webhook.id {
+            outbox::enqueue(&tx, webhook_id, mod_id, &update.name, &update_info)?;
+        }
     }
+    tx.commit()?;
+
+    // Always log the mod update activity
+    let activity = Activity {
+        id: None,
+        activity_type: "mod_updated".to_string(),
+        mod_id: Some(mod_id),
+        mod_name: Some(curse_data.name.clone()),
+        description: format!("\"{}\" has been updated", curse_data.name),
+        timestamp: Utc::now(),
+        metadata: Some(
+            json!({
+                "old_version_date": current_last_updated,
+                "new_version_date": new_date,
+                "author": author_name.clone(),
+                "latest_file": latest_file.file_name.clone(),
+                "file_id": file_id,
+                "release_type": latest_file.release_type_name(),
+                "game_versions": latest_file.game_versions,
+                "logo_url": logo_url,
+                "page_url": curse_data.links.website_url,
+                "changelog": changelog_text,
+                "changelog_html": changelog_html,
+            })
+            .to_string(),
+        ),
+    };
+    add_activity(Some(app_handle), &conn, &activity)?;
+
+    update_service::wake_outbox(app_handle);
+
+    Ok(update)
 }
 
 #[tauri::command]
-pub fn get_mods(app_handle: AppHandle) -> Result<Vec<ModWithWebhooks>, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    let mods = mods::get_all_mods(&conn).map_err(|e| e.to_string())?;
+pub fn get_mods(app_handle: AppHandle) -> Result<Vec<ModWithWebhooks>, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    let mods = mods::get_all_mods(&conn)?;
     Ok(mods)
 }
 
 #[tauri::command]
-pub fn delete_mod(app_handle: AppHandle, mod_id: i64) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-
-    // Enable foreign key support
-    conn.execute("PRAGMA foreign_keys = ON", [])
-        .map_err(|e| e.to_string())?;
+pub fn delete_mod(app_handle: AppHandle, mod_id: i64) -> Result<(), TrackerError> {
+    let mut conn = get_connection(&app_handle)?;
 
     // Start a transaction
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let tx = conn.transaction()?;
 
     println!("Starting mod deletion process for mod_id: {}", mod_id);
 
@@ -387,8 +594,7 @@ pub fn delete_mod(app_handle: AppHandle, mod_id: i64) -> Result<(), String> {
             params![mod_id],
             |row| Ok((row.get(0)?, row.get(1)?)),
         )
-        .optional()
-        .map_err(|e| e.to_string())?;
+        .optional()?;
 
     if let Some((name, game_name)) = mod_info {
         // Clear relations first
@@ -396,20 +602,17 @@ pub fn delete_mod(app_handle: AppHandle, mod_id: i64) -> Result<(), String> {
         tx.execute(
             "DELETE FROM mod_webhook_assignments WHERE mod_id = ?1",
             params![mod_id],
-        )
-        .map_err(|e| format!("Failed to delete webhook assignments: {}", e))?;
+        )?;
 
         println!("Updating activities...");
         tx.execute(
             "UPDATE activities SET mod_id = NULL WHERE mod_id = ?1",
             params![mod_id],
-        )
-        .map_err(|e| format!("Failed to update activities: {}", e))?;
+        )?;
 
         // Delete the mod
         println!("Deleting mod {}...", name);
-        tx.execute("DELETE FROM mods WHERE id = ?1", params![mod_id])
-            .map_err(|e| format!("Failed to delete mod: {}", e))?;
+        tx.execute("DELETE FROM mods WHERE id = ?1", params![mod_id])?;
 
         // Add deletion activity
         println!("Logging deletion activity...");
@@ -429,42 +632,109 @@ pub fn delete_mod(app_handle: AppHandle, mod_id: i64) -> Result<(), String> {
                 })
                 .to_string(),
             ],
-        )
-        .map_err(|e| format!("Failed to log activity: {}", e))?;
+        )?;
 
         println!("Committing transaction...");
-        tx.commit()
-            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        tx.commit()?;
 
         println!("Mod deletion completed successfully");
         Ok(())
     } else {
-        Err("Mod not found".to_string())
+        Err(TrackerError::NotFound {
+            entity: "Mod",
+            id: mod_id,
+        })
+    }
+}
+
+fn get_mod_name(conn: &Connection, mod_id: i64) -> Result<String, TrackerError> {
+    conn.query_row("SELECT name FROM mods WHERE id = ?1", [mod_id], |row| {
+        row.get(0)
+    })
+    .optional()?
+    .ok_or(TrackerError::NotFound {
+        entity: "Mod",
+        id: mod_id,
+    })
+}
+
+fn get_webhook_name(conn: &Connection, webhook_id: i64) -> Result<String, TrackerError> {
+    conn.query_row(
+        "SELECT name FROM webhooks WHERE id = ?1",
+        [webhook_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or(TrackerError::NotFound {
+        entity: "Webhook",
+        id: webhook_id,
+    })
+}
+
+/// Sets the game version, mod loader and release type filters for a mod. Empty
+/// values clear the corresponding filter.
+#[tauri::command]
+pub fn set_mod_filters(
+    app_handle: AppHandle,
+    mod_id: i64,
+    filters: ModFileFilters,
+) -> Result<ModFileFilters, TrackerError> {
+    let normalize = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let filters = ModFileFilters {
+        game_version: normalize(filters.game_version),
+        mod_loader: normalize(filters.mod_loader),
+        release_type: normalize(filters.release_type).map(|v| v.to_lowercase()),
+    };
+
+    if let Some(release_type) = &filters.release_type {
+        if release_type_rank(release_type).is_none() {
+            return Err(TrackerError::InvalidInput(format!(
+                "Invalid release type \"{}\": expected release, beta or alpha",
+                release_type
+            )));
+        }
     }
+
+    let conn = get_connection(&app_handle)?;
+
+    let mod_name = get_mod_name(&conn, mod_id)?;
+
+    mods::update_mod_filters(&conn, mod_id, &filters)?;
+
+    let activity = Activity {
+        id: None,
+        activity_type: "mod_filters_updated".to_string(),
+        mod_id: Some(mod_id),
+        mod_name: Some(mod_name.clone()),
+        description: format!("Updated file filters for mod \"{}\"", mod_name),
+        timestamp: Utc::now(),
+        metadata: Some(json!(filters).to_string()),
+    };
+    add_activity(Some(&app_handle), &conn, &activity)?;
+
+    Ok(filters)
 }
 
 #[tauri::command]
-pub fn assign_webhook(app_handle: AppHandle, mod_id: i64, webhook_id: i64) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+pub fn assign_webhook(
+    app_handle: AppHandle,
+    mod_id: i64,
+    webhook_id: i64,
+) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     // Get mod and webhook info for activity log
-    let mod_name: String = conn
-        .query_row("SELECT name FROM mods WHERE id = ?1", [mod_id], |row| {
-            row.get(0)
-        })
-        .map_err(|e| e.to_string())?;
+    let mod_name = get_mod_name(&conn, mod_id)?;
 
-    let webhook_name: String = conn
-        .query_row(
-            "SELECT name FROM webhooks WHERE id = ?1",
-            [webhook_id],
-            |row| row.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+    let webhook_name = get_webhook_name(&conn, webhook_id)?;
 
     // Assign webhook
-    mods::assign_webhook_to_mod(&conn, mod_id, webhook_id).map_err(|e| e.to_string())?;
+    mods::assign_webhook_to_mod(&conn, mod_id, webhook_id)?;
 
     // Log activity for webhook assignment
     let activity = Activity {
@@ -485,7 +755,7 @@ pub fn assign_webhook(app_handle: AppHandle, mod_id: i64, webhook_id: i64) -> Re
             .to_string(),
         ),
     };
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
+    add_activity(Some(&app_handle), &conn, &activity)?;
 
     Ok(())
 }
@@ -495,27 +765,16 @@ pub fn remove_webhook_assignment(
     app_handle: AppHandle,
     mod_id: i64,
     webhook_id: i64,
-) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     // Get mod and webhook info for activity log
-    let mod_name: String = conn
-        .query_row("SELECT name FROM mods WHERE id = ?1", [mod_id], |row| {
-            row.get(0)
-        })
-        .map_err(|e| e.to_string())?;
+    let mod_name = get_mod_name(&conn, mod_id)?;
 
-    let webhook_name: String = conn
-        .query_row(
-            "SELECT name FROM webhooks WHERE id = ?1",
-            [webhook_id],
-            |row| row.get(0),
-        )
-        .map_err(|e| e.to_string())?;
+    let webhook_name = get_webhook_name(&conn, webhook_id)?;
 
     // Remove webhook assignment
-    mods::remove_webhook_from_mod(&conn, mod_id, webhook_id).map_err(|e| e.to_string())?;
+    mods::remove_webhook_from_mod(&conn, mod_id, webhook_id)?;
 
     // Log activity for webhook removal
     let activity = Activity {
@@ -536,7 +795,7 @@ pub fn remove_webhook_assignment(
             .to_string(),
         ),
     };
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
+    add_activity(Some(&app_handle), &conn, &activity)?;
 
     Ok(())
 }
@@ -545,8 +804,7 @@ pub fn remove_webhook_assignment(
 pub fn get_mod_assigned_webhooks(
     app_handle: AppHandle,
     mod_id: i64,
-) -> Result<Vec<crate::database::Webhook>, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    crate::database::webhooks::get_mod_webhooks(&conn, mod_id).map_err(|e| e.to_string())
+) -> Result<Vec<crate::database::Webhook>, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(crate::database::webhooks::get_mod_webhooks(&conn, mod_id)?)
 }
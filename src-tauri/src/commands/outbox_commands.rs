@@ -0,0 +1,53 @@
+use crate::database::{
+    get_connection,
+    outbox::{self, OutboxEntry},
+};
+use crate::error::TrackerError;
+use crate::update_service;
+use tauri::AppHandle;
+
+/// Lists the notifications still waiting for delivery, including the failed ones.
+#[tauri::command]
+pub fn get_outbox_entries(app_handle: AppHandle) -> Result<Vec<OutboxEntry>, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(outbox::get_all_entries(&conn)?)
+}
+
+#[tauri::command]
+pub fn resend_failed_delivery(app_handle: AppHandle, entry_id: i64) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
+
+    if !outbox::requeue_entry(&conn, entry_id)? {
+        return Err(TrackerError::NotFound {
+            entity: "Delivery",
+            id: entry_id,
+        });
+    }
+
+    update_service::wake_outbox(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn resend_all_failed_deliveries(app_handle: AppHandle) -> Result<usize, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    let requeued = outbox::requeue_failed_entries(&conn)?;
+
+    update_service::wake_outbox(&app_handle);
+    Ok(requeued)
+}
+
+#[tauri::command]
+pub fn discard_failed_delivery(app_handle: AppHandle, entry_id: i64) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
+
+    if !outbox::delete_entry(&conn, entry_id)? {
+        return Err(TrackerError::NotFound {
+            entity: "Delivery",
+            id: entry_id,
+        });
+    }
+
+    update_service::emit_outbox_updated(&app_handle);
+    Ok(())
+}
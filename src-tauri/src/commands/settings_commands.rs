@@ -1,21 +1,18 @@
 use crate::database::{
-    get_api_key as get_api_key_db, get_close_to_tray as get_close_to_tray_db, get_database_path,
+    get_api_key as get_api_key_db, get_close_to_tray as get_close_to_tray_db, get_connection,
     get_minimize_to_tray as get_minimize_to_tray_db,
     get_show_quick_start as get_show_quick_start_db, get_update_interval as get_update_interval_db,
     set_api_key as set_api_key_db, set_close_to_tray as set_close_to_tray_db,
     set_minimize_to_tray as set_minimize_to_tray_db,
     set_show_quick_start as set_show_quick_start_db, set_update_interval as set_update_interval_db,
 };
-use rusqlite::Connection;
+use crate::error::TrackerError;
+use crate::update_service;
 use tauri::AppHandle;
 
 #[tauri::command]
-pub async fn get_api_key(app_handle: AppHandle) -> Result<Option<String>, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| {
-        println!("Failed to open database: {}", e);
-        e.to_string()
-    })?;
+pub async fn get_api_key(app_handle: AppHandle) -> Result<Option<String>, TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     match get_api_key_db(&conn) {
         Ok(maybe_key) => {
@@ -27,18 +24,14 @@ pub async fn get_api_key(app_handle: AppHandle) -> Result<Option<String>, String
         }
         Err(e) => {
             println!("Error retrieving API key: {}", e);
-            Err(format!("Failed to retrieve API key: {}", e))
+            Err(e.into())
         }
     }
 }
 
 #[tauri::command]
-pub async fn get_update_interval(app_handle: AppHandle) -> Result<i64, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| {
-        println!("Failed to open database: {}", e);
-        e.to_string()
-    })?;
+pub async fn get_update_interval(app_handle: AppHandle) -> Result<i64, TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     match get_update_interval_db(&conn) {
         Ok(interval) => {
@@ -47,18 +40,14 @@ pub async fn get_update_interval(app_handle: AppHandle) -> Result<i64, String> {
         }
         Err(e) => {
             println!("Error retrieving update interval: {}", e);
-            Err(format!("Failed to retrieve update interval: {}", e))
+            Err(e.into())
         }
     }
 }
 
 #[tauri::command]
-pub async fn set_api_key(app_handle: AppHandle, api_key: String) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| {
-        println!("Failed to open database: {}", e);
-        e.to_string()
-    })?;
+pub async fn set_api_key(app_handle: AppHandle, api_key: String) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     match set_api_key_db(&conn, &api_key) {
         Ok(_) => {
@@ -67,38 +56,31 @@ pub async fn set_api_key(app_handle: AppHandle, api_key: String) -> Result<(), S
         }
         Err(e) => {
             println!("Error setting API key: {}", e);
-            Err(format!("Failed to set API key: {}", e))
+            Err(e.into())
         }
     }
 }
 
 #[tauri::command]
-pub async fn set_update_interval(app_handle: AppHandle, interval: i64) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| {
-        println!("Failed to open database: {}", e);
-        e.to_string()
-    })?;
+pub async fn set_update_interval(app_handle: AppHandle, interval: i64) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     match set_update_interval_db(&conn, interval) {
         Ok(_) => {
             println!("Successfully set update interval to {}", interval);
+            update_service::reschedule(&app_handle);
             Ok(())
         }
         Err(e) => {
             println!("Error setting update interval: {}", e);
-            Err(format!("Failed to set update interval: {}", e))
+            Err(e.into())
         }
     }
 }
 
 #[tauri::command]
-pub async fn get_show_quick_start(app_handle: AppHandle) -> Result<bool, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| {
-        println!("Failed to open database: {}", e);
-        e.to_string()
-    })?;
+pub async fn get_show_quick_start(app_handle: AppHandle) -> Result<bool, TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     match get_show_quick_start_db(&conn) {
         Ok(show) => {
@@ -107,18 +89,14 @@ pub async fn get_show_quick_start(app_handle: AppHandle) -> Result<bool, String>
         }
         Err(e) => {
             println!("Error retrieving quick start setting: {}", e);
-            Err(format!("Failed to retrieve quick start setting: {}", e))
+            Err(e.into())
         }
     }
 }
 
 #[tauri::command]
-pub async fn set_show_quick_start(app_handle: AppHandle, show: bool) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| {
-        println!("Failed to open database: {}", e);
-        e.to_string()
-    })?;
+pub async fn set_show_quick_start(app_handle: AppHandle, show: bool) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
 
     match set_show_quick_start_db(&conn, show) {
         Ok(_) => {
@@ -127,42 +105,40 @@ pub async fn set_show_quick_start(app_handle: AppHandle, show: bool) -> Result<(
         }
         Err(e) => {
             println!("Error setting quick start setting: {}", e);
-            Err(format!("Failed to set quick start setting: {}", e))
+            Err(e.into())
         }
     }
 }
 
 #[tauri::command]
-pub async fn get_minimize_to_tray(app_handle: AppHandle) -> Result<bool, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    get_minimize_to_tray_db(&conn).map_err(|e| e.to_string())
+pub async fn get_minimize_to_tray(app_handle: AppHandle) -> Result<bool, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(get_minimize_to_tray_db(&conn)?)
 }
 
 #[tauri::command]
-pub async fn set_minimize_to_tray(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    set_minimize_to_tray_db(&conn, enabled).map_err(|e| e.to_string())
+pub async fn set_minimize_to_tray(
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(set_minimize_to_tray_db(&conn, enabled)?)
 }
 
 #[tauri::command]
-pub async fn get_close_to_tray(app_handle: AppHandle) -> Result<bool, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    get_close_to_tray_db(&conn).map_err(|e| e.to_string())
+pub async fn get_close_to_tray(app_handle: AppHandle) -> Result<bool, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(get_close_to_tray_db(&conn)?)
 }
 
 #[tauri::command]
-pub async fn set_close_to_tray(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    set_close_to_tray_db(&conn, enabled).map_err(|e| e.to_string())
+pub async fn set_close_to_tray(app_handle: AppHandle, enabled: bool) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(set_close_to_tray_db(&conn, enabled)?)
 }
 
 #[tauri::command]
-pub async fn handle_close_requested(app_handle: AppHandle) -> Result<bool, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    get_close_to_tray_db(&conn).map_err(|e| e.to_string())
+pub async fn handle_close_requested(app_handle: AppHandle) -> Result<bool, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(get_close_to_tray_db(&conn)?)
 }
@@ -0,0 +1,47 @@
+use crate::error::TrackerError;
+use crate::update_service::{self, UpdateService, UpdateServiceStatus};
+use tauri::{AppHandle, State};
+
+/// Starts the background update scheduler if it isn't already running and
+/// returns its current status.
+#[tauri::command]
+pub fn start_update_service(
+    app_handle: AppHandle,
+    service: State<'_, UpdateService>,
+) -> Result<UpdateServiceStatus, TrackerError> {
+    update_service::start(&app_handle);
+    Ok(service.status())
+}
+
+/// Stops scheduled checks until resumed. Manual "check now" requests still run.
+#[tauri::command]
+pub fn pause_update_service(
+    app_handle: AppHandle,
+    service: State<'_, UpdateService>,
+) -> Result<UpdateServiceStatus, TrackerError> {
+    update_service::pause(&app_handle);
+    Ok(service.status())
+}
+
+#[tauri::command]
+pub fn resume_update_service(
+    app_handle: AppHandle,
+    service: State<'_, UpdateService>,
+) -> Result<UpdateServiceStatus, TrackerError> {
+    update_service::resume(&app_handle);
+    Ok(service.status())
+}
+
+/// Triggers an immediate update check. Progress is reported through the
+/// `update_check_started`, `update_check_progress` and `update_check_completed` events.
+#[tauri::command]
+pub fn check_now(app_handle: AppHandle) -> Result<(), TrackerError> {
+    update_service::check_now(&app_handle)
+}
+
+#[tauri::command]
+pub fn get_update_service_status(
+    service: State<'_, UpdateService>,
+) -> Result<UpdateServiceStatus, TrackerError> {
+    Ok(service.status())
+}
@@ -1,141 +1,42 @@
+use crate::commands::mod_commands::ModUpdateInfo;
 use crate::database::{
     activities::{add_activity, Activity},
-    ensure_database_exists, get_database_path,
-    webhook_templates::get_webhook_template,
-    webhooks, Webhook,
+    digests, discord_messages, get_connection,
+    outbox::{self, OutboxEntry},
+    webhook_templates::{self, get_webhook_template},
+    webhooks, Webhook, WebhookKind,
 };
-use chrono::{DateTime, Datelike, Utc};
+use crate::error::TrackerError;
+use crate::notifications::{
+    self, discord, email, rate_limit::DiscordRateLimiter, DeliveryResponse, ModUpdateData,
+};
+use crate::update_service;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use serde_json::json;
-use tauri::AppHandle;
-
-const DISCORD_FIELD_CHAR_LIMIT: usize = 1024; // Discord's field value character limit
-
-#[derive(Debug)]
-struct ModUpdateData {
-    mod_id: i64,
-    curseforge_id: i64,
-    mod_name: String,
-    mod_author: String,
-    new_release_date: String,
-    old_release_date: String,
-    latest_file_name: String,
-    logo_url: Option<String>,
-    page_url: Option<String>,
-    changelog: Option<String>,
-}
+use tauri::{AppHandle, Manager};
 
-fn get_ordinal_suffix(day: u32) -> &'static str {
-    if (11..=13).contains(&(day % 100)) {
-        return "th";
-    }
-    match day % 10 {
-        1 => "st",
-        2 => "nd",
-        3 => "rd",
-        _ => "th",
-    }
-}
+// Keeps activity metadata small when an endpoint answers with a large body
+const RESPONSE_BODY_LOG_LIMIT: usize = 1000;
 
-fn format_date(date_str: &str) -> String {
-    if let Ok(date) = DateTime::parse_from_rfc3339(date_str) {
-        let utc_date: DateTime<Utc> = date.into();
-        let day = utc_date.day();
-        let suffix = get_ordinal_suffix(day);
-
-        format!(
-            "{}{} {} {} at {:02}:{:02} UTC",
-            day,
-            suffix,
-            utc_date.format("%B"),
-            utc_date.format("%Y"),
-            utc_date.format("%H"),
-            utc_date.format("%M")
-        )
-    } else {
-        date_str.to_string()
-    }
-}
+// Failed deliveries are retried after 1, 2, 4, ... minutes, at most two hours apart,
+// and moved to the failed deliveries after the last attempt
+const RETRY_BASE_DELAY_SECS: i64 = 60;
+const RETRY_MAX_DELAY_SECS: i64 = 2 * 60 * 60;
+const MAX_DELIVERY_ATTEMPTS: i64 = 10;
 
-fn format_changelog(changelog: Option<String>) -> String {
-    match changelog {
-        Some(text) if !text.trim().is_empty() => {
-            let mut formatted = text.trim().to_string();
-            if formatted.len() > DISCORD_FIELD_CHAR_LIMIT {
-                // Find the last space before the limit to avoid cutting words
-                if let Some(last_space) = formatted[..DISCORD_FIELD_CHAR_LIMIT - 3].rfind(' ') {
-                    formatted.truncate(last_space);
-                    formatted.push_str("...");
-                } else {
-                    // If no space found, just cut at the limit
-                    formatted.truncate(DISCORD_FIELD_CHAR_LIMIT - 3);
-                    formatted.push_str("...");
-                }
-            }
-            formatted
-        }
-        _ => "No changelog available".to_string(),
-    }
-}
-
-fn replace_template_variables(text: &str, data: &ModUpdateData) -> String {
-    let mut result = text.to_string();
-
-    // Basic replacements
-    let replacements = vec![
-        ("{modID}", data.curseforge_id.to_string()),
-        ("{modDatabaseID}", data.mod_id.to_string()),
-        ("{modName}", data.mod_name.clone()),
-        ("{newReleaseDate}", data.new_release_date.clone()),
-        ("{oldPreviousDate}", data.old_release_date.clone()),
-        ("{everyone}", "@everyone".to_string()),
-        ("{here}", "@here".to_string()),
-        ("{lastestModFileName}", data.latest_file_name.clone()),
-        ("{modAuthorName}", data.mod_author.clone()),
-        ("{logoUrl}", data.logo_url.clone().unwrap_or_default()),
-        ("{modURL}", data.page_url.clone().unwrap_or_default()),
-        ("{changelog}", format_changelog(data.changelog.clone())),
-    ];
-
-    for (key, value) in replacements {
-        result = result.replace(key, &value);
-    }
-
-    // Role mentions
-    while let Some(start) = result.find("{&") {
-        if let Some(end) = result[start..].find("}") {
-            let role_id = &result[start + 2..start + end];
-            if let Ok(id) = role_id.parse::<u64>() {
-                result = result.replace(&format!("{{&{}}}", role_id), &format!("<@&{}>", id));
-            }
-        } else {
-            break;
-        }
-    }
+#[tauri::command]
+pub fn add_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<Webhook, TrackerError> {
+    notifications::validate_webhook(&webhook)?;
+    let mut conn = get_connection(&app_handle)?;
 
-    // Channel mentions
-    while let Some(start) = result.find("{#") {
-        if let Some(end) = result[start..].find("}") {
-            let channel_id = &result[start + 2..start + end];
-            if let Ok(id) = channel_id.parse::<u64>() {
-                result = result.replace(&format!("{{#{}}}", channel_id), &format!("<#{}>", id));
-            }
-        } else {
-            break;
-        }
+    if webhooks::webhook_name_exists(&conn, &webhook.name, None)? {
+        return Err(TrackerError::WebhookNameTaken { name: webhook.name });
     }
+    check_template_exists(&conn, webhook.template_id)?;
 
-    result
-}
-
-#[tauri::command]
-pub fn add_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<Webhook, String> {
-    let db_path = get_database_path(&app_handle);
-    ensure_database_exists(&db_path).map_err(|e| e.to_string())?;
-
-    let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    let webhook_id = webhooks::insert_webhook(&mut conn, &webhook).map_err(|e| e.to_string())?;
+    let webhook_id = webhooks::insert_webhook(&mut conn, &webhook)?;
 
     // Log activity for webhook addition
     let activity = Activity {
@@ -153,7 +54,7 @@ pub fn add_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<Webhook, S
             .to_string(),
         ),
     };
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
+    add_activity(Some(&app_handle), &conn, &activity)?;
 
     let mut new_webhook = webhook;
     new_webhook.id = Some(webhook_id);
@@ -162,17 +63,22 @@ pub fn add_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<Webhook, S
 }
 
 #[tauri::command]
-pub fn get_webhooks(app_handle: AppHandle) -> Result<Vec<Webhook>, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    webhooks::get_all_webhooks(&conn).map_err(|e| e.to_string())
+pub fn get_webhooks(app_handle: AppHandle) -> Result<Vec<Webhook>, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(webhooks::get_all_webhooks(&conn)?)
 }
 
 #[tauri::command]
-pub fn update_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    webhooks::update_webhook(&mut conn, &webhook).map_err(|e| e.to_string())?;
+pub fn update_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<(), TrackerError> {
+    notifications::validate_webhook(&webhook)?;
+    let mut conn = get_connection(&app_handle)?;
+
+    if webhooks::webhook_name_exists(&conn, &webhook.name, webhook.id)? {
+        return Err(TrackerError::WebhookNameTaken { name: webhook.name });
+    }
+    check_template_exists(&conn, webhook.template_id)?;
+
+    webhooks::update_webhook(&mut conn, &webhook)?;
 
     // Log activity for webhook update
     let activity = Activity {
@@ -190,15 +96,28 @@ pub fn update_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<(), Str
             .to_string(),
         ),
     };
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
+    add_activity(Some(&app_handle), &conn, &activity)?;
+
+    Ok(())
+}
+
+// A webhook can only use a template that is in the library
+fn check_template_exists(conn: &Connection, template_id: Option<i64>) -> Result<(), TrackerError> {
+    if let Some(template_id) = template_id {
+        webhook_templates::get_template(conn, template_id)
+            .optional()?
+            .ok_or(TrackerError::NotFound {
+                entity: "Template",
+                id: template_id,
+            })?;
+    }
 
     Ok(())
 }
 
 #[tauri::command]
-pub fn delete_webhook(app_handle: AppHandle, webhook_id: i64) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+pub fn delete_webhook(app_handle: AppHandle, webhook_id: i64) -> Result<(), TrackerError> {
+    let mut conn = get_connection(&app_handle)?;
 
     // Get webhook info before deletion
     let webhook_name: String = conn
@@ -207,10 +126,14 @@ pub fn delete_webhook(app_handle: AppHandle, webhook_id: i64) -> Result<(), Stri
             [webhook_id],
             |row| row.get(0),
         )
-        .map_err(|e| e.to_string())?;
+        .optional()?
+        .ok_or(TrackerError::NotFound {
+            entity: "Webhook",
+            id: webhook_id,
+        })?;
 
     // Delete the webhook
-    webhooks::delete_webhook(&mut conn, webhook_id).map_err(|e| e.to_string())?;
+    webhooks::delete_webhook(&mut conn, webhook_id)?;
 
     // Log activity for webhook deletion
     let activity = Activity {
@@ -228,60 +151,18 @@ pub fn delete_webhook(app_handle: AppHandle, webhook_id: i64) -> Result<(), Stri
             .to_string(),
         ),
     };
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
+    add_activity(Some(&app_handle), &conn, &activity)?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn test_webhook(webhook: Webhook) -> Result<bool, String> {
+pub async fn test_webhook(app_handle: AppHandle, webhook: Webhook) -> Result<bool, TrackerError> {
     let client = Client::new();
-
-    let mut payload = json!({
-        "embeds": [{
-            "title": "🧪 Test Message",
-            "description": "This is a test message from Arti's CurseForge Mod Tracker!",
-            "color": 5814783,
-            "footer": {
-                "text": "Test completed successfully"
-            },
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        }]
-    });
-
-    payload["username"] = json!(webhook
-        .username
-        .and_then(|u| if u.trim().is_empty() { None } else { Some(u) })
-        .unwrap_or_else(|| "Mod Tracker".to_string()));
-
-    if let Some(avatar_url) = &webhook.avatar_url {
-        if !avatar_url.trim().is_empty() {
-            payload["avatar_url"] = json!(avatar_url);
-        }
-    }
-
-    println!(
-        "Sending test webhook payload: {}",
-        serde_json::to_string_pretty(&payload).unwrap()
-    );
-
-    let response = client
-        .post(&webhook.url)
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        println!("Discord API error: {}", error_text);
-        return Err(format!("Discord API error: {}", error_text));
-    }
-
-    Ok(response.status().is_success())
+    let rate_limiter = app_handle.state::<DiscordRateLimiter>();
+    let payload = notifications::build_test_payload(&webhook)?;
+    notifications::post_payload(&client, &rate_limiter, &webhook, &payload).await?;
+    Ok(true)
 }
 
 #[tauri::command]
@@ -297,163 +178,303 @@ pub async fn send_update_notification(
     curseforge_id: i64,
     logo_url: Option<String>,
     changelog: Option<String>,
-) -> Result<bool, String> {
-    let client = Client::new();
-
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    let template =
-        get_webhook_template(&conn, webhook.id.unwrap_or(-1)).map_err(|e| e.to_string())?;
-
-    let update_data = ModUpdateData {
+) -> Result<bool, TrackerError> {
+    let update = ModUpdateInfo {
         mod_id,
         curseforge_id,
-        mod_name: mod_name.clone(),
-        mod_author: mod_author.clone(),
-        new_release_date: format_date(&new_release_date),
-        old_release_date: format_date(&old_release_date),
-        latest_file_name: latest_file_name.clone(),
-        logo_url: logo_url.clone(),
-        page_url: conn
-            .query_row("SELECT page_url FROM mods WHERE id = ?1", [mod_id], |row| {
-                row.get(0)
-            })
-            .unwrap_or(None),
+        name: mod_name,
+        old_update_time: old_release_date,
+        new_update_time: new_release_date,
+        mod_author,
+        latest_file_name,
+        logo_url,
         changelog,
+        changelog_html: None,
+        file_id: None,
+        file_display_name: None,
+        file_date: None,
+        release_type: None,
+        game_versions: Vec::new(),
+        authors: Vec::new(),
+        files: Vec::new(),
     };
 
-    let mut embed = json!({
-        "title": replace_template_variables(&template.title, &update_data),
-        "color": template.color,
-        "fields": serde_json::from_str::<Vec<serde_json::Value>>(&template.embed_fields)
-            .map_err(|e| e.to_string())?
-            .iter()
-            .map(|field| {
-                let mut new_field = field.clone();
-                let name = field["name"].as_str().unwrap_or("");
-                let value = field["value"].as_str().unwrap_or("");
-                new_field["name"] = json!(replace_template_variables(name, &update_data));
-                new_field["value"] = json!(replace_template_variables(value, &update_data));
-                new_field
-            })
-            .collect::<Vec<_>>()
-    });
-
-    if let Some(author_name) = &template.author_name {
-        if !author_name.trim().is_empty() {
-            let mut author = json!({
-                "name": replace_template_variables(author_name, &update_data)
-            });
-            if let Some(icon_url) = &template.author_icon_url {
-                if !icon_url.trim().is_empty() {
-                    author["icon_url"] = json!(replace_template_variables(icon_url, &update_data));
-                }
-            }
-            embed["author"] = author;
-        }
+    deliver_update_notification(&app_handle, &webhook, &update).await
+}
+
+/// Renders the webhook's template for `update` in the webhook's format, posts it
+/// and logs the outcome as a `notification_sent` or `webhook_error` activity.
+/// Webhooks in digest mode queue the update for their next digest instead.
+pub async fn deliver_update_notification(
+    app_handle: &AppHandle,
+    webhook: &Webhook,
+    update: &ModUpdateInfo,
+) -> Result<bool, TrackerError> {
+    let client = Client::new();
+
+    let conn = get_connection(app_handle)?;
+
+    if let Some(webhook_id) = webhook.id.filter(|_| notifications::uses_digest(webhook)) {
+        digests::queue_update(
+            &conn,
+            webhook_id,
+            update.mod_id,
+            &serde_json::to_string(update)?,
+        )?;
+        println!(
+            "Queued update for \"{}\" for the next digest of webhook \"{}\"",
+            update.name, webhook.name
+        );
+        return Ok(true);
     }
 
-    // Add thumbnail if enabled and URL is available
-    if template.use_thumbnail {
-        if let Some(url) = logo_url {
-            embed["thumbnail"] = json!({
-                "url": url
-            });
+    let template = get_webhook_template(&conn, webhook.id.unwrap_or(-1))?;
+
+    let mod_id = update.mod_id;
+    let mod_name = update.name.clone();
+    let update_data = ModUpdateData::from_update(&conn, update);
+
+    let messages = notifications::build_update_messages(webhook, &template, &update_data)?;
+    let payload = &messages.payloads[0];
+
+    // A mod that updates again soon after its last message gets that message edited.
+    // Notifications that need follow-ups are posted anew, since their follow-ups
+    // couldn't be placed after the earlier message.
+    let edit_window = discord::edit_window(webhook);
+    let previous_message = match (webhook.id, edit_window) {
+        (Some(webhook_id), Some(window)) if messages.payloads.len() == 1 => {
+            discord_messages::get_message(&conn, webhook_id, mod_id)?
+                .filter(|message| message.posted_at + window > Utc::now())
         }
-    }
+        _ => None,
+    };
 
-    if template
-        .footer_text
-        .as_ref()
-        .map_or(false, |t| !t.trim().is_empty())
-        || template
-            .footer_icon_url
-            .as_ref()
-            .map_or(false, |u| !u.trim().is_empty())
-        || template.include_timestamp
-    {
-        let mut footer = json!({});
-        if let Some(text) = &template.footer_text {
-            if !text.trim().is_empty() {
-                footer["text"] = json!(replace_template_variables(text, &update_data));
+    // Waiting out a rate limit can take a while, so don't hold on to the connection
+    drop(conn);
+    let rate_limiter = app_handle.state::<DiscordRateLimiter>();
+    let mut edited_message_id = None;
+    let mut sent = None;
+    if let Some(message) = &previous_message {
+        let message_id = &message.message_id;
+        match notifications::edit_discord_message(
+            &client,
+            &rate_limiter,
+            webhook,
+            message_id,
+            payload,
+        )
+        .await
+        {
+            // The message was deleted in the meantime, so a new one is posted
+            Err(TrackerError::WebhookDelivery { status: 404, .. }) => {}
+            result => {
+                edited_message_id = Some(message_id.clone());
+                sent = Some(result);
             }
         }
-        if let Some(icon_url) = &template.footer_icon_url {
-            if !icon_url.trim().is_empty() {
-                footer["icon_url"] = json!(replace_template_variables(icon_url, &update_data));
+    }
+    let sent = match sent {
+        Some(result) => result,
+        None => notifications::post_payload(&client, &rate_limiter, webhook, payload).await,
+    };
+    let (response, error) = match sent {
+        Ok(response) => (Some(response), None),
+        Err(error) => (DeliveryResponse::from_error(&error), Some(error)),
+    };
+    let result = error.is_none();
+
+    // What didn't fit into the notification follows it. A failure there is only
+    // logged, as retrying the delivery would post the notification itself again.
+    let mut follow_up_error = None;
+    if result {
+        for follow_up in &messages.payloads[1..] {
+            if let Err(e) =
+                notifications::post_payload(&client, &rate_limiter, webhook, follow_up).await
+            {
+                println!(
+                    "Failed to send follow-up message for \"{}\" to webhook \"{}\": {}",
+                    mod_name, webhook.name, e
+                );
+                follow_up_error = Some(e.to_string());
+                break;
             }
         }
-        embed["footer"] = footer;
+    }
+    let rate_limit_delay = response.as_ref().and_then(|r| r.rate_limit_delay);
+
+    let conn = get_connection(app_handle)?;
+    // An edited message counts as freshly posted, so the edit window starts over
+    if result && edit_window.is_some() {
+        let message_id = edited_message_id
+            .clone()
+            .or_else(|| response.as_ref().and_then(|r| discord::message_id(&r.body)));
+        if let (Some(webhook_id), Some(message_id)) = (webhook.id, message_id) {
+            discord_messages::save_message(&conn, webhook_id, mod_id, &message_id)?;
+        }
     }
 
-    if template.include_timestamp {
-        embed["timestamp"] = json!(chrono::Utc::now().to_rfc3339());
+    // Log activity for notification result
+    let activity = Activity {
+        id: None,
+        activity_type: match &error {
+            None => "notification_sent".to_string(),
+            Some(TrackerError::RateLimited { .. }) => "notification_delayed".to_string(),
+            Some(_) => "webhook_error".to_string(),
+        },
+        mod_id: Some(mod_id),
+        mod_name: Some(mod_name.clone()),
+        description: match &error {
+            None => {
+                let sent = if edited_message_id.is_some() {
+                    format!(
+                        "Edited the earlier update notification for \"{}\" in webhook \"{}\"",
+                        mod_name, webhook.name
+                    )
+                } else {
+                    format!(
+                        "Sent update notification for \"{}\" to webhook \"{}\"",
+                        mod_name, webhook.name
+                    )
+                };
+                match rate_limit_delay {
+                    Some(delay) if delay >= 1.0 => {
+                        format!("{} (delayed {:.0}s due to rate limit)", sent, delay)
+                    }
+                    _ => sent,
+                }
+            }
+            Some(TrackerError::RateLimited { .. }) => format!(
+                "Update notification for \"{}\" to webhook \"{}\" was delayed due to rate limit",
+                mod_name, webhook.name
+            ),
+            Some(_) => format!(
+                "Failed to send update notification for \"{}\" to webhook \"{}\"",
+                mod_name, webhook.name
+            ),
+        },
+        timestamp: Utc::now(),
+        metadata: Some(
+            json!({
+                "webhook_name": webhook.name,
+                "webhook_id": webhook.id,
+                "webhook_kind": webhook.kind,
+                "rate_limit_delay": rate_limit_delay,
+                "edited_message_id": edited_message_id,
+                "follow_up_messages": messages.payloads.len() - 1,
+                "follow_up_error": follow_up_error,
+                "truncations": messages.truncations,
+                "retry_after": error.as_ref().and_then(|e| e.retry_after()),
+                "error": error.as_ref().map(|e| e.to_string()),
+                "error_code": error.as_ref().map(|e| e.code()),
+                "response": response.map(|r| DeliveryResponse {
+                    body: notifications::truncate_text(&r.body, RESPONSE_BODY_LOG_LIMIT),
+                    ..r
+                }),
+            })
+            .to_string(),
+        ),
+    };
+    add_activity(Some(app_handle), &conn, &activity)?;
+
+    if let Some(error) = error {
+        return Err(error);
     }
 
-    let mut payload = json!({
-        "username": webhook.username
-            .and_then(|u| if u.trim().is_empty() { None } else { Some(u) })
-            .unwrap_or_else(|| "Mod Tracker".to_string()),
-    });
+    Ok(result)
+}
 
-    if let Some(avatar_url) = &webhook.avatar_url {
-        if !avatar_url.trim().is_empty() {
-            payload["avatar_url"] = json!(avatar_url);
+/// Sends the updates queued for a webhook's digest once it is due: daily for email
+/// webhooks, after the collection window for Discord ones. Logs the outcome and
+/// returns whether anything was sent.
+pub async fn deliver_due_digest(
+    app_handle: &AppHandle,
+    webhook: &Webhook,
+) -> Result<bool, TrackerError> {
+    let webhook_id = match webhook.id {
+        Some(id) => id,
+        None => return Ok(false),
+    };
+
+    let mut conn = get_connection(app_handle)?;
+    let queued = digests::get_queued_updates(&conn, webhook_id)?;
+    let oldest_queued_at = match queued.first() {
+        Some(entry) => entry.queued_at,
+        None => return Ok(false),
+    };
+
+    // A digest that failed waits for its retry instead of going out on every check
+    let retry = digests::get_digest_retry(&conn, webhook_id)?;
+    let due = match webhook.kind {
+        WebhookKind::Email => email::digest_due(
+            webhook,
+            digests::get_last_digest_at(&conn, webhook_id)?,
+            retry.retry_at,
+        ),
+        WebhookKind::Discord => {
+            retry
+                .retry_at
+                .map_or(true, |retry_at| retry_at <= Utc::now())
+                && discord::digest_due(webhook, oldest_queued_at)
         }
+        _ => false,
+    };
+    if !due {
+        return Ok(false);
     }
 
-    if template.use_embed {
-        payload["embeds"] = json!([embed]);
-    } else {
-        let content = template
-            .content
-            .unwrap_or_else(|| "🔄 Mod Update Available!".to_string());
-        payload["content"] = json!(replace_template_variables(&content, &update_data));
+    let mut updates = Vec::new();
+    for entry in &queued {
+        let update: ModUpdateInfo = serde_json::from_str(&entry.update_info)?;
+        updates.push(ModUpdateData::from_update(&conn, &update));
     }
 
-    println!(
-        "Sending webhook payload: {}",
-        serde_json::to_string_pretty(&payload).unwrap()
-    );
-
-    let response = client
-        .post(&webhook.url)
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let result = response.status().is_success();
-    let error_text = if !result {
-        Some(
-            response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string()),
-        )
-    } else {
-        None
-    };
+    let client = Client::new();
+    let template = get_webhook_template(&conn, webhook_id)?;
+    let messages = notifications::build_digest_messages(webhook, &template, &updates)?;
+    let rate_limiter = app_handle.state::<DiscordRateLimiter>();
+
+    // Messages go out in order; each one's updates leave the queue as soon as it is
+    // delivered, so a failure part-way only leaves the rest for the next digest check
+    let mut sent_updates = 0;
+    let mut response = None;
+    let mut error = None;
+    for message in &messages {
+        match notifications::post_payload(&client, &rate_limiter, webhook, &message.payload).await {
+            Ok(delivered) => {
+                let ids: Vec<i64> = message.updates.iter().map(|&i| queued[i].id).collect();
+                digests::mark_digest_sent(&mut conn, webhook_id, &ids, Utc::now())?;
+                sent_updates += message.updates.len();
+                response = Some(delivered);
+            }
+            Err(e) => {
+                response = DeliveryResponse::from_error(&e);
+                error = Some(e);
+                break;
+            }
+        }
+    }
 
-    // Log activity for notification result
     let activity = Activity {
         id: None,
-        activity_type: if result {
+        activity_type: if error.is_none() {
             "notification_sent".to_string()
         } else {
             "webhook_error".to_string()
         },
-        mod_id: Some(mod_id),
-        mod_name: Some(mod_name.clone()),
-        description: if result {
+        mod_id: None,
+        mod_name: None,
+        description: if error.is_none() {
             format!(
-                "Sent update notification for \"{}\" to webhook \"{}\"",
-                mod_name, webhook.name
+                "Sent digest with {} updates to webhook \"{}\"",
+                updates.len(),
+                webhook.name
             )
         } else {
             format!(
-                "Failed to send update notification for \"{}\" to webhook \"{}\"",
-                mod_name, webhook.name
+                "Failed to send digest with {} updates to webhook \"{}\" ({} sent)",
+                updates.len(),
+                webhook.name,
+                sent_updates
             )
         },
         timestamp: Utc::now(),
@@ -461,19 +482,202 @@ pub async fn send_update_notification(
             json!({
                 "webhook_name": webhook.name,
                 "webhook_id": webhook.id,
-                "error": error_text,
+                "webhook_kind": webhook.kind,
+                "digest": true,
+                "messages": messages.len(),
+                "truncations": messages
+                    .iter()
+                    .flat_map(|message| message.truncations.iter())
+                    .collect::<Vec<_>>(),
+                "mod_ids": updates.iter().map(|update| update.mod_id).collect::<Vec<_>>(),
+                "error": error.as_ref().map(|e| e.to_string()),
+                "error_code": error.as_ref().map(|e| e.code()),
+                "response": response.map(|r| DeliveryResponse {
+                    body: notifications::truncate_text(&r.body, RESPONSE_BODY_LOG_LIMIT),
+                    ..r
+                }),
             })
             .to_string(),
         ),
     };
-    add_activity(Some(&app_handle), &conn, &activity).map_err(|e| e.to_string())?;
+    add_activity(Some(app_handle), &conn, &activity)?;
+
+    let error = match error {
+        Some(error) => error,
+        None => return Ok(true),
+    };
 
-    if !result {
-        if let Some(error_text) = error_text {
-            println!("Discord API error: {}", error_text);
-            return Err(format!("Discord API error: {}", error_text));
+    // Same rules as the outbox: back off between attempts and stop retrying
+    // errors that won't go away
+    let attempts = match error {
+        TrackerError::RateLimited { .. } => retry.attempts,
+        _ => retry.attempts + 1,
+    };
+    match next_attempt_at(webhook.kind, attempts, &error) {
+        Some(retry_at) => {
+            digests::record_digest_failure(&conn, webhook_id, attempts, retry_at)?;
+        }
+        None => {
+            // The updates that didn't go out become failed deliveries, which can be
+            // re-sent one by one once the webhook works again
+            let tx = conn.transaction()?;
+            let remaining = digests::get_queued_updates(&tx, webhook_id)?;
+            for entry in &remaining {
+                let update: ModUpdateInfo = serde_json::from_str(&entry.update_info)?;
+                let outbox_id = outbox::enqueue(
+                    &tx,
+                    webhook_id,
+                    update.mod_id,
+                    &update.name,
+                    &entry.update_info,
+                )?;
+                outbox::record_failure(&tx, outbox_id, attempts, None, &error.to_string())?;
+            }
+            digests::clear_queue(&tx, webhook_id)?;
+            tx.commit()?;
+
+            let activity = Activity {
+                id: None,
+                activity_type: "notification_failed".to_string(),
+                mod_id: None,
+                mod_name: None,
+                description: format!(
+                    "Gave up sending digest to webhook \"{}\" after {} {}, {} updates moved to failed deliveries",
+                    webhook.name,
+                    attempts,
+                    if attempts == 1 { "attempt" } else { "attempts" },
+                    remaining.len()
+                ),
+                timestamp: Utc::now(),
+                metadata: Some(
+                    json!({
+                        "webhook_name": webhook.name,
+                        "webhook_id": webhook.id,
+                        "digest": true,
+                        "attempts": attempts,
+                        "error": error.to_string(),
+                        "error_code": error.code(),
+                    })
+                    .to_string(),
+                ),
+            };
+            add_activity(Some(app_handle), &conn, &activity)?;
+            update_service::emit_outbox_updated(app_handle);
         }
     }
 
-    Ok(result)
+    Err(error)
+}
+
+// Errors that will fail the same way on every attempt aren't worth retrying
+fn is_retryable(kind: WebhookKind, error: &TrackerError) -> bool {
+    match error {
+        TrackerError::InvalidInput(_) | TrackerError::NotFound { .. } => false,
+        // SMTP marks permanent failures with 5xx codes and temporary ones with 4xx
+        TrackerError::WebhookDelivery { status, .. } if kind == WebhookKind::Email => {
+            !(500..600).contains(status)
+        }
+        // Other 4xx answers mean the message or webhook itself was rejected
+        TrackerError::WebhookDelivery { status, .. } => !(400..500).contains(status),
+        _ => true,
+    }
+}
+
+/// When to try a failed delivery again, or `None` to give up on it.
+fn next_attempt_at(
+    kind: WebhookKind,
+    attempts: i64,
+    error: &TrackerError,
+) -> Option<DateTime<Utc>> {
+    if attempts >= MAX_DELIVERY_ATTEMPTS || !is_retryable(kind, error) {
+        return None;
+    }
+
+    let backoff = RETRY_BASE_DELAY_SECS
+        .saturating_mul(1 << (attempts - 1).clamp(0, 20))
+        .min(RETRY_MAX_DELAY_SECS);
+    // A service that said how long to wait is trusted over the backoff
+    let delay = match error.retry_after() {
+        Some(retry_after) => backoff.max(retry_after.ceil() as i64),
+        None => backoff,
+    };
+    Some(Utc::now() + chrono::Duration::seconds(delay))
+}
+
+/// Attempts one delivery from the outbox. Delivered entries are removed; failed ones
+/// are scheduled for a retry with exponential backoff, or moved to the failed
+/// deliveries once they run out of attempts.
+pub async fn deliver_outbox_entry(
+    app_handle: &AppHandle,
+    entry: &OutboxEntry,
+) -> Result<bool, TrackerError> {
+    let webhook = {
+        let conn = get_connection(app_handle)?;
+        match webhooks::get_webhook(&conn, entry.webhook_id).optional()? {
+            Some(webhook) if webhook.enabled => webhook,
+            _ => {
+                // Disabling a webhook drops what was still waiting for it
+                println!(
+                    "Dropping notification for \"{}\": webhook \"{}\" is disabled",
+                    entry.mod_name, entry.webhook_name
+                );
+                outbox::delete_entry(&conn, entry.id)?;
+                update_service::emit_outbox_updated(app_handle);
+                return Ok(false);
+            }
+        }
+    };
+    let update: ModUpdateInfo = serde_json::from_str(&entry.update_info)?;
+
+    let result = deliver_update_notification(app_handle, &webhook, &update).await;
+
+    let conn = get_connection(app_handle)?;
+    let error = match result {
+        Ok(sent) => {
+            outbox::delete_entry(&conn, entry.id)?;
+            update_service::emit_outbox_updated(app_handle);
+            return Ok(sent);
+        }
+        Err(error) => error,
+    };
+
+    // Waiting out a rate limit isn't the delivery's fault, so it doesn't use up an attempt
+    let attempts = match error {
+        TrackerError::RateLimited { .. } => entry.attempts,
+        _ => entry.attempts + 1,
+    };
+    let retry_at = next_attempt_at(webhook.kind, attempts, &error);
+    outbox::record_failure(&conn, entry.id, attempts, retry_at, &error.to_string())?;
+
+    if retry_at.is_none() {
+        let activity = Activity {
+            id: None,
+            activity_type: "notification_failed".to_string(),
+            mod_id: Some(entry.mod_id),
+            mod_name: Some(entry.mod_name.clone()),
+            description: format!(
+                "Gave up sending update notification for \"{}\" to webhook \"{}\" after {} {}",
+                entry.mod_name,
+                webhook.name,
+                attempts,
+                if attempts == 1 { "attempt" } else { "attempts" }
+            ),
+            timestamp: Utc::now(),
+            metadata: Some(
+                json!({
+                    "webhook_name": webhook.name,
+                    "webhook_id": webhook.id,
+                    "outbox_id": entry.id,
+                    "attempts": attempts,
+                    "error": error.to_string(),
+                    "error_code": error.code(),
+                })
+                .to_string(),
+            ),
+        };
+        add_activity(Some(app_handle), &conn, &activity)?;
+    }
+    update_service::emit_outbox_updated(app_handle);
+
+    Err(error)
 }
@@ -1,48 +1,251 @@
+use crate::commands::mod_commands::UpdateFile;
 use crate::database::{
     webhook_templates::{
+        self,
         WebhookTemplate,
         get_webhook_template as db_get_webhook_template,
         update_webhook_template as db_update_webhook_template,
-        delete_custom_template as db_delete_custom_template
     },
-    get_database_path
+    activities::get_latest_mod_activity,
+    mods,
+    get_connection,
+    Webhook,
+    WebhookKind
 };
-use rusqlite::Connection;
+use crate::error::TrackerError;
+use crate::notifications::{self, format_date, ModUpdateData};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
 use tauri::AppHandle;
 use serde_json::Value;
 
-/// Gets the template for a webhook. If webhook_id is -1, returns the default template.
-/// Otherwise, returns the custom template for the specified webhook if it exists,
-/// or falls back to the default template.
+/// The messages a template renders to, as the backend would post them.
+#[derive(Debug, Serialize)]
+pub struct RenderedPayload {
+    /// The notification, followed by any messages carrying what didn't fit into it
+    pub payloads: Vec<Value>,
+    /// Placeholders nothing fills in and text that had to be shortened or split
+    pub warnings: Vec<String>,
+}
+
+/// Lists every template in the library, the default template first.
 #[tauri::command]
-pub fn get_webhook_template(app_handle: AppHandle, webhook_id: i64) -> Result<WebhookTemplate, String> {
-    let db_path = get_database_path(&app_handle);
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    db_get_webhook_template(&conn, webhook_id).map_err(|e| e.to_string())
+pub fn get_webhook_templates(app_handle: AppHandle) -> Result<Vec<WebhookTemplate>, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(webhook_templates::get_all_webhook_templates(&conn)?)
 }
 
-/// Updates a webhook template. If the template is marked as default (is_default = true),
-/// updates the default template. Otherwise, creates or updates a custom template for
-/// the specified webhook.
+/// Gets the template a webhook sends with. If webhook_id is -1, returns the default
+/// template. Otherwise, returns the template assigned to the webhook, or the default
+/// template when it has none.
 #[tauri::command]
-pub fn update_webhook_template(app_handle: AppHandle, template: WebhookTemplate) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
+pub fn get_webhook_template(app_handle: AppHandle, webhook_id: i64) -> Result<WebhookTemplate, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    Ok(db_get_webhook_template(&conn, webhook_id)?)
+}
+
+/// Adds a new template to the library and returns it with its ID.
+#[tauri::command]
+pub fn create_webhook_template(app_handle: AppHandle, mut template: WebhookTemplate) -> Result<WebhookTemplate, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+
+    template.name = check_template_name(&conn, &template.name, None)?;
+    template.is_default = false;
+    validate_template(&template)?;
+
+    template.id = Some(webhook_templates::insert_template(&conn, &template)?);
+    Ok(template)
+}
+
+/// Saves a template in the library. Every webhook using it picks up the change.
+#[tauri::command]
+pub fn update_webhook_template(app_handle: AppHandle, mut template: WebhookTemplate) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
+
+    let template_id = template
+        .id
+        .ok_or_else(|| TrackerError::InvalidInput("The template has no ID".to_string()))?;
+    find_template(&conn, template_id)?;
+    template.name = check_template_name(&conn, &template.name, Some(template_id))?;
+    validate_template(&template)?;
+
+    Ok(db_update_webhook_template(&conn, &template)?)
+}
+
+/// Copies a template under a new name, leaving the original and its webhooks as they are.
+#[tauri::command]
+pub fn duplicate_webhook_template(app_handle: AppHandle, template_id: i64, name: String) -> Result<WebhookTemplate, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+
+    let mut template = find_template(&conn, template_id)?;
+    template.name = check_template_name(&conn, &name, None)?;
+    template.is_default = false;
+
+    template.id = Some(webhook_templates::insert_template(&conn, &template)?);
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn rename_webhook_template(app_handle: AppHandle, template_id: i64, name: String) -> Result<(), TrackerError> {
+    let conn = get_connection(&app_handle)?;
+
+    find_template(&conn, template_id)?;
+    let name = check_template_name(&conn, &name, Some(template_id))?;
+    Ok(webhook_templates::rename_template(&conn, template_id, &name)?)
+}
+
+/// Deletes a template from the library. The webhooks that used it switch to the
+/// default template.
+#[tauri::command]
+pub fn delete_webhook_template(app_handle: AppHandle, template_id: i64) -> Result<(), TrackerError> {
+    let mut conn = get_connection(&app_handle)?;
+
+    if find_template(&conn, template_id)?.is_default {
+        return Err(TrackerError::InvalidInput("The default template can't be deleted".to_string()));
+    }
+
+    Ok(webhook_templates::delete_template(&mut conn, template_id)?)
+}
+
+// Trims the name and makes sure no other template uses it
+fn check_template_name(conn: &Connection, name: &str, exclude_id: Option<i64>) -> Result<String, TrackerError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(TrackerError::InvalidInput("Template name cannot be empty".to_string()));
+    }
+    if webhook_templates::template_name_exists(conn, name, exclude_id)? {
+        return Err(TrackerError::TemplateNameTaken { name: name.to_string() });
+    }
+
+    Ok(name.to_string())
+}
+
+fn validate_template(template: &WebhookTemplate) -> Result<(), TrackerError> {
     // Validate embed fields format
     validate_embed_fields(&template.embed_fields)
-        .map_err(|e| format!("Invalid embed fields format: {}", e))?;
-    
-    db_update_webhook_template(&mut conn, &template)
-        .map_err(|e| e.to_string())
+        .map_err(|e| TrackerError::InvalidInput(format!("Invalid embed fields format: {}", e)))?;
+
+    // Compile the template expressions so mistakes show up now, not on the next update
+    notifications::template::validate(template)
+}
+
+fn find_template(conn: &Connection, template_id: i64) -> Result<WebhookTemplate, TrackerError> {
+    webhook_templates::get_template(conn, template_id)
+        .optional()?
+        .ok_or(TrackerError::NotFound { entity: "Template", id: template_id })
 }
 
-/// Deletes a custom template for a webhook and resets it to use the default template.
+/// Renders a saved or unsaved template into the exact payloads `webhook` would be sent,
+/// using the last recorded update of `mod_id` or sample data when no mod is given.
+/// Without a webhook the payload is rendered for a Discord webhook with default settings.
 #[tauri::command]
-pub fn delete_custom_template(app_handle: AppHandle, webhook_id: i64) -> Result<(), String> {
-    let db_path = get_database_path(&app_handle);
-    let mut conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    db_delete_custom_template(&mut conn, webhook_id).map_err(|e| e.to_string())
+pub fn render_webhook_payload(
+    app_handle: AppHandle,
+    template: WebhookTemplate,
+    webhook: Option<Webhook>,
+    mod_id: Option<i64>,
+) -> Result<RenderedPayload, TrackerError> {
+    let conn = get_connection(&app_handle)?;
+    let mut warnings = Vec::new();
+
+    validate_embed_fields(&template.embed_fields)
+        .map_err(|e| TrackerError::InvalidInput(format!("Invalid embed fields format: {}", e)))?;
+
+    // Broken expressions are sent as written, so the preview shows them that way too
+    if let Err(e) = notifications::template::validate(&template) {
+        warnings.push(e.to_string());
+    }
+    warnings.extend(notifications::template::unknown_variables(&template)?);
+
+    let data = match mod_id {
+        Some(mod_id) => mod_preview_data(&conn, mod_id, &mut warnings)?,
+        None => ModUpdateData::sample(),
+    };
+
+    let webhook = webhook.unwrap_or_else(|| Webhook {
+        id: None,
+        name: "Preview".to_string(),
+        url: String::new(),
+        avatar_url: None,
+        username: None,
+        enabled: true,
+        template_id: None,
+        kind: WebhookKind::Discord,
+        config: Value::Null,
+    });
+    let messages = notifications::build_update_messages(&webhook, &template, &data)?;
+    warnings.extend(messages.truncations);
+
+    Ok(RenderedPayload { payloads: messages.payloads, warnings })
+}
+
+// Rebuilds the update data of a mod's last recorded update from its activity log
+// entry, or uses the tracked mod's details with sample file info when none is kept
+fn mod_preview_data(
+    conn: &Connection,
+    mod_id: i64,
+    warnings: &mut Vec<String>,
+) -> Result<ModUpdateData, TrackerError> {
+    let tracked = mods::get_mod(conn, mod_id)
+        .optional()?
+        .ok_or(TrackerError::NotFound { entity: "Mod", id: mod_id })?;
+
+    let mut data = ModUpdateData::sample();
+    data.mod_id = mod_id;
+    data.curseforge_id = tracked.curseforge_id;
+    data.mod_name = tracked.name.clone();
+    data.new_release_date = format_date(&tracked.last_updated);
+    data.released_at = tracked.last_updated.clone();
+    data.page_url = tracked.page_url.clone();
+
+    let metadata = get_latest_mod_activity(conn, mod_id, "mod_updated")?
+        .and_then(|activity| activity.metadata)
+        .and_then(|metadata| serde_json::from_str::<Value>(&metadata).ok());
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => {
+            warnings.push(format!(
+                "No update of \"{}\" is on record, so the file details are sample data",
+                tracked.name
+            ));
+            return Ok(data);
+        }
+    };
+
+    let text = |key: &str| metadata[key].as_str().map(str::to_string);
+    if let Some(old_date) = text("old_version_date") {
+        data.old_release_date = format_date(&old_date);
+    }
+    if let Some(author) = text("author") {
+        data.mod_author = author.clone();
+        data.authors = vec![author];
+    }
+    if let Some(file_name) = text("latest_file") {
+        data.latest_file_name = file_name.clone();
+        data.file_display_name = Some(file_name);
+    }
+    data.file_id = metadata["file_id"].as_i64();
+    data.release_type = text("release_type");
+    data.game_versions = serde_json::from_value(metadata["game_versions"].clone()).unwrap_or_default();
+    data.logo_url = text("logo_url");
+    data.page_url = text("page_url").or(data.page_url);
+    data.changelog = text("changelog");
+    data.changelog_html = text("changelog_html");
+    data.file_date = Some(data.new_release_date.clone());
+    data.files = data
+        .file_id
+        .map(|id| UpdateFile {
+            id,
+            display_name: data.latest_file_name.clone(),
+            file_name: data.latest_file_name.clone(),
+            file_date: data.released_at.clone(),
+            release_type: data.release_type.clone().unwrap_or_default(),
+            game_versions: data.game_versions.clone(),
+        })
+        .into_iter()
+        .collect();
+
+    Ok(data)
 }
 
 /// Validates that the embed fields string is proper JSON in the expected format
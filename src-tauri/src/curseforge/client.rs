@@ -0,0 +1,174 @@
+use crate::curseforge::models::{
+    ApiResponse, CurseForgeGameData, CurseForgeModData, GetModsByIdsRequest,
+};
+use crate::error::TrackerError;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://api.curseforge.com";
+
+// Overrides the API base URL, e.g. to point the tracker at a local mock server
+const BASE_URL_ENV_VAR: &str = "CURSEFORGE_API_BASE_URL";
+
+const USER_AGENT: &str = concat!("artis-curseforge-mod-tracker/", env!("CARGO_PKG_VERSION"));
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// CurseForge's API returns a stale name for ARK: Survival Ascended, so it is hardcoded
+const ARK_SURVIVAL_ASCENDED_GAME_ID: i64 = 83374;
+
+/// Returns the base URL from `CURSEFORGE_API_BASE_URL`, or the public API URL.
+pub fn base_url_from_env() -> String {
+    std::env::var(BASE_URL_ENV_VAR)
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+}
+
+/// Thin typed wrapper around the CurseForge REST API. Cloning is cheap: clones
+/// share the underlying `reqwest::Client` and its connection pool.
+#[derive(Debug, Clone)]
+pub struct CurseForgeClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl CurseForgeClient {
+    pub fn new(base_url: impl Into<String>) -> Result<Self, TrackerError> {
+        let http = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| TrackerError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: String::new(),
+        })
+    }
+
+    /// Returns a copy of this client that authenticates with `api_key`.
+    pub fn with_api_key(&self, api_key: &str) -> Self {
+        Self {
+            http: self.http.clone(),
+            base_url: self.base_url.clone(),
+            api_key: api_key.trim().to_string(),
+        }
+    }
+
+    pub async fn get_mod(&self, mod_id: i64) -> Result<CurseForgeModData, TrackerError> {
+        let request = self.http.get(self.url(&format!("/v1/mods/{}", mod_id)));
+        let response = self.send(request).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(TrackerError::ModNotFound {
+                curseforge_id: mod_id,
+            });
+        }
+
+        Self::parse::<CurseForgeModData>(Self::check_status(response).await?).await
+    }
+
+    /// Fetches several mods at once through the bulk `POST /v1/mods` endpoint.
+    /// Unknown IDs are silently left out of the result by CurseForge.
+    pub async fn get_mods(&self, mod_ids: &[i64]) -> Result<Vec<CurseForgeModData>, TrackerError> {
+        let request = self
+            .http
+            .post(self.url("/v1/mods"))
+            .json(&GetModsByIdsRequest { mod_ids });
+        let response = self.send(request).await?;
+
+        Self::parse::<Vec<CurseForgeModData>>(Self::check_status(response).await?).await
+    }
+
+    pub async fn get_game_name(&self, game_id: i64) -> Result<String, TrackerError> {
+        if game_id == ARK_SURVIVAL_ASCENDED_GAME_ID {
+            return Ok("ARK: Survival Ascended".to_string());
+        }
+
+        let request = self.http.get(self.url(&format!("/v1/games/{}", game_id)));
+        let response = Self::check_status(self.send(request).await?).await?;
+
+        let game = Self::parse::<CurseForgeGameData>(response).await?;
+        Ok(game.name)
+    }
+
+    /// Returns the raw changelog HTML for a file, or `None` if CurseForge has none.
+    pub async fn get_mod_file_changelog(
+        &self,
+        mod_id: i64,
+        file_id: i64,
+    ) -> Result<Option<String>, TrackerError> {
+        let request = self
+            .http
+            .get(self.url(&format!("/v1/mods/{}/files/{}/changelog", mod_id, file_id)));
+        let response = self.send(request).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Self::parse::<Option<String>>(Self::check_status(response).await?).await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn headers(&self) -> Result<HeaderMap, TrackerError> {
+        if self.api_key.is_empty() {
+            return Err(TrackerError::MissingApiKey);
+        }
+
+        let api_key =
+            HeaderValue::from_str(&self.api_key).map_err(|_| TrackerError::InvalidApiKey)?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", api_key);
+        Ok(headers)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response, TrackerError> {
+        Ok(request.headers(self.headers()?).send().await?)
+    }
+
+    /// Maps error statuses to typed errors, passing successful responses through.
+    async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, TrackerError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(TrackerError::InvalidApiKey),
+            StatusCode::TOO_MANY_REQUESTS => {
+                Err(TrackerError::rate_limited("CurseForge", response.headers()))
+            }
+            _ => Err(TrackerError::CurseForge {
+                status: status.as_u16(),
+                message: response
+                    .text()
+                    .await
+                    .ok()
+                    .filter(|text| !text.trim().is_empty())
+                    .unwrap_or_else(|| status.to_string()),
+            }),
+        }
+    }
+
+    async fn parse<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, TrackerError> {
+        let body: ApiResponse<T> = response
+            .json()
+            .await
+            .map_err(|e| TrackerError::CurseForge {
+                status: 200,
+                message: format!("Unexpected response from CurseForge: {}", e),
+            })?;
+        Ok(body.data)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod client;
+pub mod models;
+
+pub use client::{base_url_from_env, CurseForgeClient};
+pub use models::{CurseForgeModData, ModFile};
\ No newline at end of file
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+/// Envelope used by every CurseForge API response: `{ "data": ... }`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    pub data: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurseForgeModData {
+    pub id: i64,
+    pub name: String,
+    #[serde(rename = "dateModified")]
+    pub date_modified: String,
+    #[serde(rename = "dateReleased")]
+    pub date_released: String,
+    #[serde(rename = "dateCreated")]
+    pub date_created: String,
+    pub authors: Vec<ModAuthor>,
+    #[serde(rename = "latestFiles")]
+    pub latest_files: Vec<ModFile>,
+    #[serde(rename = "gameId")]
+    pub game_id: i64,
+    pub logo: Option<ModLogo>,
+    pub links: ModLinks,
+    #[serde(rename = "mainFileId")]
+    pub main_file_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModLinks {
+    #[serde(rename = "websiteUrl")]
+    pub website_url: Option<String>,
+    #[serde(rename = "wikiUrl")]
+    pub wiki_url: Option<String>,
+    #[serde(rename = "issuesUrl")]
+    pub issues_url: Option<String>,
+    #[serde(rename = "sourceUrl")]
+    pub source_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModAuthor {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModFile {
+    pub id: i64,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    #[serde(rename = "fileDate")]
+    pub file_date: String,
+    /// 1 = Release, 2 = Beta, 3 = Alpha
+    #[serde(rename = "releaseType")]
+    pub release_type: i64,
+    /// Game versions and mod loaders the file targets, e.g. `["1.20.1", "Forge"]`
+    #[serde(rename = "gameVersions", default)]
+    pub game_versions: Vec<String>,
+}
+
+impl ModFile {
+    pub fn release_type_name(&self) -> &'static str {
+        match self.release_type {
+            1 => "release",
+            2 => "beta",
+            3 => "alpha",
+            _ => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModLogo {
+    pub id: i64,
+    #[serde(rename = "modId")]
+    pub mod_id: i64,
+    pub title: String,
+    pub description: String,
+    #[serde(rename = "thumbnailUrl")]
+    pub thumbnail_url: String,
+    pub url: String,
+}
+
+impl CurseForgeModData {
+    /// IDs of all files CurseForge currently lists for the mod, main file included.
+    pub fn file_ids(&self) -> Vec<i64> {
+        let mut ids: Vec<i64> = self.latest_files.iter().map(|file| file.id).collect();
+        ids.push(self.main_file_id);
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurseForgeGameData {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Request body for the bulk `POST /v1/mods` endpoint
+#[derive(Debug, Serialize)]
+pub struct GetModsByIdsRequest<'a> {
+    #[serde(rename = "modIds")]
+    pub mod_ids: &'a [i64],
+}
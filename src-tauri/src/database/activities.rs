@@ -1,4 +1,4 @@
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, Result, Row, params};
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use tauri::Manager;
@@ -14,25 +14,7 @@ pub struct Activity {
     pub metadata: Option<String>, // JSON string for additional activity-specific data
 }
 
-const MAX_ACTIVITIES: i64 = 50;
-
-pub fn initialize_activities_table(conn: &Connection) -> Result<()> {
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS activities (
-            id INTEGER PRIMARY KEY,
-            activity_type TEXT NOT NULL,
-            mod_id INTEGER,
-            mod_name TEXT,
-            description TEXT NOT NULL,
-            timestamp TEXT NOT NULL,
-            metadata TEXT,
-            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE SET NULL
-        )",
-        [],
-    )?;
-
-    Ok(())
-}
+pub const MAX_ACTIVITIES: i64 = 50;
 
 pub fn add_activity(app_handle: Option<&tauri::AppHandle>, conn: &Connection, activity: &Activity) -> Result<i64> {
     // First, check if we need to remove old activities
@@ -82,6 +64,24 @@ pub fn add_activity(app_handle: Option<&tauri::AppHandle>, conn: &Connection, ac
     Ok(id)
 }
 
+fn activity_from_row(row: &Row) -> Result<Activity> {
+    Ok(Activity {
+        id: Some(row.get(0)?),
+        activity_type: row.get(1)?,
+        mod_id: row.get(2)?,
+        mod_name: row.get(3)?,
+        description: row.get(4)?,
+        timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
+                5,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+            ))?
+            .into(),
+        metadata: row.get(6)?,
+    })
+}
+
 pub fn get_recent_activities(conn: &Connection, limit: Option<i64>) -> Result<Vec<Activity>> {
     let limit = limit.unwrap_or(MAX_ACTIVITIES);
     let mut stmt = conn.prepare(
@@ -91,23 +91,7 @@ pub fn get_recent_activities(conn: &Connection, limit: Option<i64>) -> Result<Ve
          LIMIT ?1"
     )?;
 
-    let activities = stmt.query_map(params![limit], |row| {
-        Ok(Activity {
-            id: Some(row.get(0)?),
-            activity_type: row.get(1)?,
-            mod_id: row.get(2)?,
-            mod_name: row.get(3)?,
-            description: row.get(4)?,
-            timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
-                    5,
-                    rusqlite::types::Type::Text,
-                    Box::new(e),
-                ))?
-                .into(),
-            metadata: row.get(6)?,
-        })
-    })?;
+    let activities = stmt.query_map(params![limit], activity_from_row)?;
 
     let mut result = Vec::new();
     for activity in activities {
@@ -117,6 +101,22 @@ pub fn get_recent_activities(conn: &Connection, limit: Option<i64>) -> Result<Ve
     Ok(result)
 }
 
+/// Returns the most recent activity of `activity_type` logged for a mod, if any
+/// is still kept.
+pub fn get_latest_mod_activity(conn: &Connection, mod_id: i64, activity_type: &str) -> Result<Option<Activity>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, activity_type, mod_id, mod_name, description, timestamp, metadata
+         FROM activities
+         WHERE mod_id = ?1 AND activity_type = ?2
+         ORDER BY timestamp DESC
+         LIMIT 1"
+    )?;
+
+    let mut activities = stmt.query_map(params![mod_id, activity_type], activity_from_row)?;
+
+    activities.next().transpose()
+}
+
 pub fn clear_activities(conn: &Connection) -> Result<()> {
     conn.execute("DELETE FROM activities", [])?;
     Ok(())
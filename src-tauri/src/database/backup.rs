@@ -0,0 +1,593 @@
+use crate::database::activities::{get_recent_activities, Activity, MAX_ACTIVITIES};
+use crate::database::migrations::schema_version;
+use crate::database::mods::{self, format_id_list, Mod};
+use crate::database::webhook_templates::{get_all_webhook_templates, WebhookTemplate};
+use crate::database::webhooks::{self, Webhook};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Identifies a JSON file as a tracker backup.
+pub const BACKUP_FORMAT: &str = "artis-curseforge-mod-tracker-backup";
+/// Version of the backup document layout. Bump it when the layout changes in a
+/// way older versions of the app can't read. Version 2 shares templates between
+/// webhooks, where version 1 gave each custom template to one webhook.
+pub const BACKUP_VERSION: i64 = 2;
+
+const API_KEY_SETTING: &str = "api_key";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModWebhookAssignment {
+    pub mod_id: i64,
+    pub webhook_id: i64,
+}
+
+/// Complete snapshot of the tracker's state. IDs are the ones used in the
+/// exporting database and are only meaningful within the backup itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub format: String,
+    pub version: i64,
+    pub app_version: String,
+    pub schema_version: i64,
+    pub exported_at: DateTime<Utc>,
+    pub includes_api_key: bool,
+    #[serde(default)]
+    pub mods: Vec<Mod>,
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    #[serde(default)]
+    pub mod_webhook_assignments: Vec<ModWebhookAssignment>,
+    #[serde(default)]
+    pub webhook_templates: Vec<WebhookTemplate>,
+    #[serde(default)]
+    pub settings: BTreeMap<String, Option<String>>,
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Adds mods and webhooks that don't exist yet, keeping everything already
+    /// tracked. Mods are matched by CurseForge ID and webhooks by name.
+    Merge,
+    /// Wipes mods, webhooks, templates and activities and restores the backup.
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    pub mods_imported: usize,
+    pub mods_skipped: usize,
+    pub webhooks_imported: usize,
+    pub webhooks_skipped: usize,
+    pub assignments_imported: usize,
+    pub templates_imported: usize,
+    pub templates_skipped: usize,
+    pub settings_imported: usize,
+    pub activities_imported: usize,
+    pub activities_skipped: usize,
+}
+
+pub fn export_backup(conn: &Connection, include_api_key: bool) -> Result<Backup> {
+    let mut mods = Vec::new();
+    let mut mod_webhook_assignments = Vec::new();
+    for tracked in mods::get_all_mods(conn)? {
+        let mod_id = tracked.mod_info.id.unwrap_or_default();
+        for webhook_id in tracked.webhook_ids {
+            mod_webhook_assignments.push(ModWebhookAssignment { mod_id, webhook_id });
+        }
+        mods.push(tracked.mod_info);
+    }
+
+    let mut settings = BTreeMap::new();
+    let mut stmt = conn.prepare("SELECT key, value FROM settings ORDER BY key")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+    })?;
+    for row in rows {
+        let (key, value) = row?;
+        if key == API_KEY_SETTING && !include_api_key {
+            continue;
+        }
+        settings.insert(key, value);
+    }
+
+    Ok(Backup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: schema_version(conn)?,
+        exported_at: Utc::now(),
+        includes_api_key: include_api_key,
+        mods,
+        webhooks: webhooks::get_all_webhooks(conn)?,
+        mod_webhook_assignments,
+        webhook_templates: get_all_webhook_templates(conn)?,
+        settings,
+        activities: get_recent_activities(conn, Some(MAX_ACTIVITIES))?,
+    })
+}
+
+/// Checks that a backup is well-formed and internally consistent before anything
+/// is written, so a bad file can never leave the database half-restored.
+pub fn validate_backup(backup: &Backup) -> std::result::Result<(), String> {
+    if backup.format != BACKUP_FORMAT {
+        return Err("This file is not a mod tracker backup".to_string());
+    }
+    if backup.version < 1 || backup.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup version {} is not supported by this version of the app (expected {} or older)",
+            backup.version, BACKUP_VERSION
+        ));
+    }
+
+    let mut mod_ids = HashSet::new();
+    let mut curseforge_ids = HashSet::new();
+    for tracked in &backup.mods {
+        let id = tracked
+            .id
+            .ok_or_else(|| format!("Mod \"{}\" has no ID", tracked.name))?;
+        if !mod_ids.insert(id) {
+            return Err(format!("Mod ID {} appears more than once", id));
+        }
+        if !curseforge_ids.insert(tracked.curseforge_id) {
+            return Err(format!(
+                "CurseForge mod {} appears more than once",
+                tracked.curseforge_id
+            ));
+        }
+    }
+
+    let mut webhook_ids = HashSet::new();
+    let mut webhook_names = HashSet::new();
+    for webhook in &backup.webhooks {
+        let id = webhook
+            .id
+            .ok_or_else(|| format!("Webhook \"{}\" has no ID", webhook.name))?;
+        if !webhook_ids.insert(id) {
+            return Err(format!("Webhook ID {} appears more than once", id));
+        }
+        if webhook.name.trim().is_empty() || webhook.url.trim().is_empty() {
+            return Err(format!("Webhook {} is missing a name or URL", id));
+        }
+        if !webhook_names.insert(webhook.name.to_lowercase()) {
+            return Err(format!(
+                "Webhook name \"{}\" appears more than once",
+                webhook.name
+            ));
+        }
+    }
+
+    for assignment in &backup.mod_webhook_assignments {
+        if !mod_ids.contains(&assignment.mod_id) || !webhook_ids.contains(&assignment.webhook_id)
+        {
+            return Err(format!(
+                "Assignment of webhook {} to mod {} refers to a missing mod or webhook",
+                assignment.webhook_id, assignment.mod_id
+            ));
+        }
+    }
+
+    let mut has_default = false;
+    let mut template_ids = HashSet::new();
+    let mut template_names = HashSet::new();
+    for template in &backup.webhook_templates {
+        if template.is_default {
+            if has_default {
+                return Err("Backup contains more than one default template".to_string());
+            }
+            has_default = true;
+            // Restoring renames the default template, so its name must be free too
+            if !template.name.trim().is_empty()
+                && !template_names.insert(template.name.trim().to_lowercase())
+            {
+                return Err(format!(
+                    "Template name \"{}\" appears more than once",
+                    template.name
+                ));
+            }
+        } else if backup.version == 1 {
+            match template.webhook_id {
+                Some(id) if webhook_ids.contains(&id) => {}
+                _ => return Err("A custom template refers to a missing webhook".to_string()),
+            }
+        } else if template.name.trim().is_empty() {
+            return Err("A template has no name".to_string());
+        } else if !template_names.insert(template.name.trim().to_lowercase()) {
+            return Err(format!(
+                "Template name \"{}\" appears more than once",
+                template.name
+            ));
+        }
+        if let Some(id) = template.id {
+            if !template_ids.insert(id) {
+                return Err(format!("Template ID {} appears more than once", id));
+            }
+        }
+        serde_json::from_str::<Vec<serde_json::Value>>(&template.embed_fields)
+            .map_err(|e| format!("Template has invalid embed fields: {}", e))?;
+    }
+
+    for webhook in &backup.webhooks {
+        if let Some(template_id) = webhook.template_id {
+            if !template_ids.contains(&template_id) {
+                return Err(format!(
+                    "Webhook \"{}\" uses a template that is not in the backup",
+                    webhook.name
+                ));
+            }
+        }
+    }
+
+    if let Some(Some(interval)) = backup.settings.get("update_interval") {
+        match interval.parse::<i64>() {
+            Ok(minutes) if minutes >= 1 => {}
+            _ => return Err(format!("Invalid update interval \"{}\"", interval)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Restores a validated backup inside a single transaction.
+pub fn import_backup(conn: &mut Connection, backup: &Backup, mode: ImportMode) -> Result<ImportSummary> {
+    let tx = conn.transaction()?;
+    let mut summary = ImportSummary::default();
+
+    if mode == ImportMode::Replace {
+        tx.execute_batch(
+            "DELETE FROM mod_webhook_assignments;
+             DELETE FROM webhook_templates WHERE is_default = 0;
+             DELETE FROM activities;
+             DELETE FROM webhooks;
+             DELETE FROM mods;",
+        )?;
+    }
+
+    // Maps IDs from the backup to the IDs rows ended up with in this database
+    let mut mod_ids = HashMap::new();
+    for tracked in &backup.mods {
+        let backup_id = tracked.id.unwrap_or_default();
+        let existing: Option<i64> = match mode {
+            ImportMode::Replace => None,
+            ImportMode::Merge => tx
+                .query_row(
+                    "SELECT id FROM mods WHERE curseforge_id = ?1",
+                    [tracked.curseforge_id],
+                    |row| row.get(0),
+                )
+                .optional()?,
+        };
+
+        let id = match existing {
+            Some(id) => {
+                summary.mods_skipped += 1;
+                id
+            }
+            None => {
+                summary.mods_imported += 1;
+                insert_mod(&tx, tracked, mode)?
+            }
+        };
+        mod_ids.insert(backup_id, id);
+    }
+
+    let mut webhook_ids = HashMap::new();
+    let mut new_webhooks = HashSet::new();
+    for webhook in &backup.webhooks {
+        let backup_id = webhook.id.unwrap_or_default();
+        let existing: Option<i64> = match mode {
+            ImportMode::Replace => None,
+            ImportMode::Merge => tx
+                .query_row(
+                    "SELECT id FROM webhooks WHERE LOWER(name) = LOWER(?1)",
+                    [&webhook.name],
+                    |row| row.get(0),
+                )
+                .optional()?,
+        };
+
+        let id = match existing {
+            Some(id) => {
+                summary.webhooks_skipped += 1;
+                id
+            }
+            None => {
+                summary.webhooks_imported += 1;
+                new_webhooks.insert(backup_id);
+                insert_webhook(&tx, webhook, mode)?
+            }
+        };
+        webhook_ids.insert(backup_id, id);
+    }
+
+    for assignment in &backup.mod_webhook_assignments {
+        if let (Some(mod_id), Some(webhook_id)) = (
+            mod_ids.get(&assignment.mod_id),
+            webhook_ids.get(&assignment.webhook_id),
+        ) {
+            summary.assignments_imported += tx.execute(
+                "INSERT OR IGNORE INTO mod_webhook_assignments (mod_id, webhook_id) VALUES (?1, ?2)",
+                params![mod_id, webhook_id],
+            )?;
+        }
+    }
+
+    // Version 1 backups name the webhook each custom template belonged to
+    let mut template_ids = HashMap::new();
+    let mut legacy_templates = HashMap::new();
+    // Merging keeps the local default template. Replacing restores it before the
+    // other templates, whose names must not clash with its new name.
+    if mode == ImportMode::Replace {
+        if let Some(template) = backup.webhook_templates.iter().find(|t| t.is_default) {
+            replace_default_template(&tx, template)?;
+            summary.templates_imported += 1;
+        }
+    }
+    for template in &backup.webhook_templates {
+        let backup_id = template.id.unwrap_or_default();
+        if template.is_default {
+            continue;
+        }
+
+        let name = match template.webhook_id {
+            // Custom templates of version 1 only come along with the webhooks they belong to
+            Some(backup_webhook_id) => {
+                let webhook = backup
+                    .webhooks
+                    .iter()
+                    .find(|webhook| webhook.id == Some(backup_webhook_id));
+                match webhook {
+                    Some(webhook) if new_webhooks.contains(&backup_webhook_id) => {
+                        legacy_templates.insert(backup_webhook_id, backup_id);
+                        unused_template_name(&tx, &webhook.name)?
+                    }
+                    _ => continue,
+                }
+            }
+            // Only the kept default template can have the name of one being replaced
+            None if mode == ImportMode::Replace => unused_template_name(&tx, &template.name)?,
+            None => {
+                let existing: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM webhook_templates WHERE LOWER(name) = LOWER(?1)",
+                        [&template.name],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if let Some(id) = existing {
+                    summary.templates_skipped += 1;
+                    template_ids.insert(backup_id, id);
+                    continue;
+                }
+                template.name.clone()
+            }
+        };
+
+        template_ids.insert(backup_id, insert_template(&tx, template, &name)?);
+        summary.templates_imported += 1;
+    }
+
+    // Webhooks that already existed keep the template they use
+    for webhook in &backup.webhooks {
+        let backup_id = webhook.id.unwrap_or_default();
+        if !new_webhooks.contains(&backup_id) {
+            continue;
+        }
+        let template_id = webhook
+            .template_id
+            .or_else(|| legacy_templates.get(&backup_id).copied())
+            .and_then(|id| template_ids.get(&id));
+        if let (Some(template_id), Some(webhook_id)) = (template_id, webhook_ids.get(&backup_id)) {
+            tx.execute(
+                "UPDATE webhooks SET template_id = ?1 WHERE id = ?2",
+                params![template_id, webhook_id],
+            )?;
+        }
+    }
+
+    summary.settings_imported = import_settings(&tx, backup, mode)?;
+
+    for activity in &backup.activities {
+        // Merging the same backup twice shouldn't list its activities twice
+        if mode == ImportMode::Merge {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(
+                    SELECT 1 FROM activities
+                    WHERE timestamp = ?1 AND activity_type = ?2 AND description = ?3
+                )",
+                params![
+                    activity.timestamp.to_rfc3339(),
+                    activity.activity_type,
+                    activity.description,
+                ],
+                |row| row.get(0),
+            )?;
+            if exists {
+                summary.activities_skipped += 1;
+                continue;
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO activities (
+                activity_type, mod_id, mod_name, description, timestamp, metadata
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                activity.activity_type,
+                activity.mod_id.and_then(|id| mod_ids.get(&id)),
+                activity.mod_name,
+                activity.description,
+                activity.timestamp.to_rfc3339(),
+                activity.metadata,
+            ],
+        )?;
+        summary.activities_imported += 1;
+    }
+
+    // Merged activities may push the history past its usual size
+    tx.execute(
+        "DELETE FROM activities WHERE id NOT IN (
+            SELECT id FROM activities ORDER BY timestamp DESC LIMIT ?1
+        )",
+        params![MAX_ACTIVITIES],
+    )?;
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+fn insert_mod(tx: &Transaction, tracked: &Mod, mode: ImportMode) -> Result<i64> {
+    // A NULL id lets SQLite assign a fresh one
+    let id = if mode == ImportMode::Replace { tracked.id } else { None };
+    tx.execute(
+        "INSERT INTO mods (id, curseforge_id, name, game_name, last_updated, page_url,
+                           main_file_id, latest_file_ids,
+                           game_version_filter, mod_loader_filter, release_type_filter)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            id,
+            tracked.curseforge_id,
+            tracked.name,
+            tracked.game_name,
+            tracked.last_updated,
+            tracked.page_url,
+            tracked.main_file_id,
+            format_id_list(&tracked.latest_file_ids),
+            tracked.filters.game_version,
+            tracked.filters.mod_loader,
+            tracked.filters.release_type,
+        ],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+fn insert_webhook(tx: &Transaction, webhook: &Webhook, mode: ImportMode) -> Result<i64> {
+    let id = if mode == ImportMode::Replace { webhook.id } else { None };
+    tx.execute(
+        "INSERT INTO webhooks (id, name, url, avatar_url, username, enabled, kind, config)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            id,
+            webhook.name,
+            webhook.url,
+            webhook.avatar_url,
+            webhook.username,
+            webhook.enabled,
+            webhook.kind,
+            webhook.config,
+        ],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+fn replace_default_template(tx: &Transaction, template: &WebhookTemplate) -> Result<()> {
+    tx.execute(
+        "UPDATE webhook_templates
+         SET title = ?1, color = ?2, content = ?3, use_embed = ?4,
+             author_name = ?5, author_icon_url = ?6,
+             footer_text = ?7, footer_icon_url = ?8,
+             include_timestamp = ?9, use_thumbnail = ?10, embed_fields = ?11,
+             name = COALESCE(NULLIF(?12, ''), name)
+         WHERE is_default = 1",
+        params![
+            template.title,
+            template.color,
+            template.content,
+            template.use_embed,
+            template.author_name,
+            template.author_icon_url,
+            template.footer_text,
+            template.footer_icon_url,
+            template.include_timestamp,
+            template.use_thumbnail,
+            template.embed_fields,
+            // Version 1 backups have no template names
+            template.name.trim(),
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_template(tx: &Transaction, template: &WebhookTemplate, name: &str) -> Result<i64> {
+    tx.execute(
+        "INSERT INTO webhook_templates (
+            name, is_default, title, color, content, use_embed,
+            author_name, author_icon_url,
+            footer_text, footer_icon_url,
+            include_timestamp, use_thumbnail, embed_fields
+         ) VALUES (?1, 0, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            name,
+            template.title,
+            template.color,
+            template.content,
+            template.use_embed,
+            template.author_name,
+            template.author_icon_url,
+            template.footer_text,
+            template.footer_icon_url,
+            template.include_timestamp,
+            template.use_thumbnail,
+            template.embed_fields,
+        ],
+    )?;
+    Ok(tx.last_insert_rowid())
+}
+
+// Returns `base`, adding a number if a template with that name already exists
+fn unused_template_name(tx: &Transaction, base: &str) -> Result<String> {
+    let mut name = base.to_string();
+    let mut suffix = 2;
+    loop {
+        let taken: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM webhook_templates WHERE LOWER(name) = LOWER(?1))",
+            [&name],
+            |row| row.get(0),
+        )?;
+        if !taken {
+            return Ok(name);
+        }
+        name = format!("{} ({})", base, suffix);
+        suffix += 1;
+    }
+}
+
+/// Replace mode restores every setting in the backup. Merge mode keeps local
+/// settings and only fills in the API key if none is configured yet. Backups
+/// made without the API key never touch the local one.
+fn import_settings(tx: &Transaction, backup: &Backup, mode: ImportMode) -> Result<usize> {
+    let local_api_key: Option<String> = tx
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [API_KEY_SETTING],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten()
+        .filter(|key: &String| !key.trim().is_empty());
+
+    let mut imported = 0;
+    for (key, value) in &backup.settings {
+        let wanted = match mode {
+            ImportMode::Replace => key != API_KEY_SETTING || backup.includes_api_key,
+            ImportMode::Merge => {
+                key == API_KEY_SETTING && backup.includes_api_key && local_api_key.is_none()
+            }
+        };
+        if !wanted {
+            continue;
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+        imported += 1;
+    }
+
+    Ok(imported)
+}
@@ -0,0 +1,129 @@
+use rusqlite::{Connection, Result, params};
+use chrono::{DateTime, Utc};
+
+/// A mod update held back for a webhook's next digest.
+#[derive(Debug)]
+pub struct QueuedUpdate {
+    pub id: i64,
+    /// The update as JSON, exactly as it would have been sent on its own
+    pub update_info: String,
+    pub queued_at: DateTime<Utc>,
+}
+
+pub fn queue_update(conn: &Connection, webhook_id: i64, mod_id: i64, update_info: &str) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO digest_queue (webhook_id, mod_id, update_info, queued_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![webhook_id, mod_id, update_info, Utc::now().to_rfc3339()],
+    )?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Returns the updates waiting for a webhook's digest, oldest first.
+pub fn get_queued_updates(conn: &Connection, webhook_id: i64) -> Result<Vec<QueuedUpdate>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, update_info, queued_at
+         FROM digest_queue
+         WHERE webhook_id = ?1
+         ORDER BY queued_at, id",
+    )?;
+
+    let updates_iter = stmt.query_map(params![webhook_id], |row| {
+        let queued_at: String = row.get(2)?;
+        Ok(QueuedUpdate {
+            id: row.get(0)?,
+            update_info: row.get(1)?,
+            queued_at: DateTime::parse_from_rfc3339(&queued_at)
+                .map(|date| date.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    })?;
+
+    let mut updates = Vec::new();
+    for update in updates_iter {
+        updates.push(update?);
+    }
+
+    Ok(updates)
+}
+
+/// Removes the given updates once their digest was delivered and records the delivery time.
+pub fn mark_digest_sent(conn: &mut Connection, webhook_id: i64, update_ids: &[i64], sent_at: DateTime<Utc>) -> Result<()> {
+    let tx = conn.transaction()?;
+
+    for id in update_ids {
+        tx.execute(
+            "DELETE FROM digest_queue WHERE id = ?1 AND webhook_id = ?2",
+            params![id, webhook_id],
+        )?;
+    }
+
+    tx.execute(
+        "UPDATE webhooks SET last_digest_at = ?1, digest_attempts = 0, digest_retry_at = NULL
+         WHERE id = ?2",
+        params![sent_at.to_rfc3339(), webhook_id],
+    )?;
+
+    tx.commit()
+}
+
+pub fn get_last_digest_at(conn: &Connection, webhook_id: i64) -> Result<Option<DateTime<Utc>>> {
+    let last_digest_at: Option<String> = conn.query_row(
+        "SELECT last_digest_at FROM webhooks WHERE id = ?1",
+        params![webhook_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(last_digest_at
+        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+        .map(|date| date.with_timezone(&Utc)))
+}
+
+/// How many digest deliveries to a webhook failed in a row, and when to try again.
+#[derive(Debug)]
+pub struct DigestRetry {
+    pub attempts: i64,
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+pub fn get_digest_retry(conn: &Connection, webhook_id: i64) -> Result<DigestRetry> {
+    let (attempts, retry_at): (i64, Option<String>) = conn.query_row(
+        "SELECT digest_attempts, digest_retry_at FROM webhooks WHERE id = ?1",
+        params![webhook_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(DigestRetry {
+        attempts,
+        retry_at: retry_at
+            .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+            .map(|date| date.with_timezone(&Utc)),
+    })
+}
+
+/// Holds back a webhook's digest until `retry_at` after a failed delivery.
+pub fn record_digest_failure(conn: &Connection, webhook_id: i64, attempts: i64, retry_at: DateTime<Utc>) -> Result<()> {
+    conn.execute(
+        "UPDATE webhooks SET digest_attempts = ?1, digest_retry_at = ?2 WHERE id = ?3",
+        params![attempts, retry_at.to_rfc3339(), webhook_id],
+    )?;
+
+    Ok(())
+}
+
+/// Empties a webhook's digest queue and starts its retry count over, once the
+/// updates that were waiting have been moved elsewhere.
+pub fn clear_queue(conn: &Connection, webhook_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM digest_queue WHERE webhook_id = ?1",
+        params![webhook_id],
+    )?;
+
+    conn.execute(
+        "UPDATE webhooks SET digest_attempts = 0, digest_retry_at = NULL WHERE id = ?1",
+        params![webhook_id],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use rusqlite::{Connection, OptionalExtension, Result, params};
+use chrono::{DateTime, Utc};
+
+/// The last message a Discord webhook posted about a mod.
+#[derive(Debug)]
+pub struct DiscordMessage {
+    pub message_id: String,
+    pub posted_at: DateTime<Utc>,
+}
+
+pub fn get_message(conn: &Connection, webhook_id: i64, mod_id: i64) -> Result<Option<DiscordMessage>> {
+    let message: Option<(String, String)> = conn
+        .query_row(
+            "SELECT message_id, posted_at FROM discord_messages
+             WHERE webhook_id = ?1 AND mod_id = ?2",
+            params![webhook_id, mod_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    Ok(message.and_then(|(message_id, posted_at)| {
+        let posted_at = DateTime::parse_from_rfc3339(&posted_at).ok()?;
+        Some(DiscordMessage {
+            message_id,
+            posted_at: posted_at.with_timezone(&Utc),
+        })
+    }))
+}
+
+/// Remembers a newly posted message, replacing the one posted before it.
+pub fn save_message(conn: &Connection, webhook_id: i64, mod_id: i64, message_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO discord_messages (webhook_id, mod_id, message_id, posted_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![webhook_id, mod_id, message_id, Utc::now().to_rfc3339()],
+    )?;
+
+    Ok(())
+}
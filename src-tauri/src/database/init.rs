@@ -1,147 +1,97 @@
+use crate::database::migrations::{run_migrations, MigrationError};
 use rusqlite::{Connection, Result};
+use std::ffi::OsString;
+use std::fs;
 use std::path::Path;
 use tauri::AppHandle;
 use std::path::PathBuf;
-use crate::database::settings::initialize_settings_table;
-use crate::database::activities::initialize_activities_table;
+
+const DATABASE_FILE_NAME: &str = "curseforge_tracker.db";
+const DATA_DIR_ARG: &str = "--data-dir";
+const DATA_DIR_ENV: &str = "ARTIS_MOD_TRACKER_DATA_DIR";
+
+/// Directory given with `--data-dir <path>` (or `--data-dir=<path>`), falling back
+/// to the `ARTIS_MOD_TRACKER_DATA_DIR` environment variable. Used for portable
+/// installs and tests.
+pub fn data_dir_override() -> Option<PathBuf> {
+    data_dir_from_args(std::env::args_os().skip(1)).or_else(|| {
+        std::env::var_os(DATA_DIR_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+fn data_dir_from_args<I: Iterator<Item = OsString>>(mut args: I) -> Option<PathBuf> {
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy().into_owned();
+        if arg == DATA_DIR_ARG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix("--data-dir=") {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+pub fn get_data_dir(handle: &AppHandle) -> PathBuf {
+    data_dir_override().unwrap_or_else(|| {
+        handle
+            .path_resolver()
+            .app_data_dir()
+            .expect("failed to resolve app data directory")
+    })
+}
 
 pub fn get_database_path(handle: &AppHandle) -> PathBuf {
-    handle.path_resolver()
-        .resolve_resource("curseforge_tracker.db")
-        .expect("failed to resolve resource")
+    get_data_dir(handle).join(DATABASE_FILE_NAME)
+}
+
+/// Where older versions kept the database, next to the bundled resources.
+fn get_legacy_database_path(handle: &AppHandle) -> Option<PathBuf> {
+    handle.path_resolver().resolve_resource(DATABASE_FILE_NAME)
 }
 
 pub fn ensure_database_exists(db_path: &Path) -> Result<()> {
     if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| rusqlite::Error::SqliteFailure(
+        fs::create_dir_all(parent).map_err(|e| rusqlite::Error::SqliteFailure(
             rusqlite::ffi::Error::new(1), Some(e.to_string())
         ))?;
     }
     Ok(())
 }
 
-pub fn initialize_database(connection: &mut Connection) -> Result<()> {
-    // Enable foreign key support
-    connection.execute("PRAGMA foreign_keys = ON", [])?;
-
-    // Create mods table if it doesn't exist
-    connection.execute(
-        "CREATE TABLE IF NOT EXISTS mods (
-            id INTEGER PRIMARY KEY,
-            curseforge_id INTEGER NOT NULL UNIQUE,
-            name TEXT NOT NULL,
-            game_name TEXT NOT NULL,
-            last_updated TEXT NOT NULL,
-            page_url TEXT
-        )",
-        [],
-    )?;
-
-    // Create webhooks table if it doesn't exist
-    connection.execute(
-        "CREATE TABLE IF NOT EXISTS webhooks (
-            id INTEGER PRIMARY KEY,
-            name TEXT NOT NULL,
-            url TEXT NOT NULL,
-            avatar_url TEXT,
-            username TEXT,
-            enabled BOOLEAN NOT NULL DEFAULT 1,
-            use_custom_template BOOLEAN NOT NULL DEFAULT 0
-        )",
-        [],
-    )?;
-
-    // Create activities table
-    connection.execute(
-        "CREATE TABLE IF NOT EXISTS activities (
-            id INTEGER PRIMARY KEY,
-            activity_type TEXT NOT NULL,
-            mod_id INTEGER,
-            mod_name TEXT,
-            description TEXT NOT NULL,
-            timestamp TEXT NOT NULL,
-            metadata TEXT,
-            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE SET NULL
-        )",
-        [],
-    )?;
-
-    // Create mod_webhook_assignments table
-    connection.execute(
-        "CREATE TABLE IF NOT EXISTS mod_webhook_assignments (
-            mod_id INTEGER NOT NULL,
-            webhook_id INTEGER NOT NULL,
-            PRIMARY KEY (mod_id, webhook_id),
-            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE CASCADE,
-            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
-        )",
-        [],
-    )?;
-
-    // Create webhook_templates table
-    connection.execute(
-        "CREATE TABLE IF NOT EXISTS webhook_templates (
-            id INTEGER PRIMARY KEY,
-            is_default BOOLEAN NOT NULL DEFAULT 0,
-            webhook_id INTEGER UNIQUE,
-            title TEXT NOT NULL DEFAULT '🔄 Mod Update Available!',
-            color INTEGER NOT NULL DEFAULT 5814783,
-            content TEXT,
-            use_embed BOOLEAN NOT NULL DEFAULT 1,
-            author_name TEXT,
-            author_icon_url TEXT,
-            footer_text TEXT,
-            footer_icon_url TEXT,
-            include_timestamp BOOLEAN NOT NULL DEFAULT 1,
-            use_thumbnail BOOLEAN NOT NULL DEFAULT 0,
-            embed_fields TEXT NOT NULL DEFAULT '[
-                    {\"name\":\"Mod Name\",\"value\":\"{modName}\",\"inline\":true},
-                    {\"name\":\"Author\",\"value\":\"{modAuthorName}\",\"inline\":true},
-                    {\"name\":\"Last Updated\",\"value\":\"{newReleaseDate}\",\"inline\":true},
-                    {\"name\":\"Latest File\",\"value\":\"{modID}\",\"inline\":true}
-            ]',
-            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
-        )",
-        [],
-    )?;
+/// Copies a database left in the resource directory by an older version into the
+/// app data directory. Only runs when no database exists at the new location and
+/// no data directory override is set. The old file is left untouched, since the
+/// resource directory is often read-only.
+pub fn migrate_legacy_database(handle: &AppHandle, db_path: &Path) -> std::io::Result<bool> {
+    if data_dir_override().is_some() || db_path.exists() {
+        return Ok(false);
+    }
 
-    // Check if default template exists
-    let default_exists: bool = connection
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM webhook_templates WHERE is_default = 1)",
-            [],
-            |row| row.get(0),
-        )?;
+    let legacy_path = match get_legacy_database_path(handle) {
+        Some(path) if path.is_file() && path != db_path => path,
+        _ => return Ok(false),
+    };
 
-    // Only insert default template if it doesn't exist
-    if !default_exists {
-        connection.execute(
-            "INSERT INTO webhook_templates (
-                is_default, title, color, use_embed, 
-                author_name, author_icon_url,
-                footer_text, footer_icon_url, include_timestamp,
-                use_thumbnail, embed_fields
-            ) VALUES (
-                1, '🔄 Mod Update Available!', 5814783, 1,
-                'Mod Tracker', NULL,
-                'Powered by Artis Mod Tracker', NULL, 1,
-                0,
-                '[
-                    {\"name\":\"Mod Name\",\"value\":\"{modName}\",\"inline\":true},
-                    {\"name\":\"Author\",\"value\":\"{modAuthorName}\",\"inline\":true},
-                    {\"name\":\"Last Updated\",\"value\":\"{newReleaseDate}\",\"inline\":true},
-                    {\"name\":\"Latest File\",\"value\":\"{modID}\",\"inline\":true}
-                ]'
-            )",
-            [],
-        )?;
-    }
+    println!(
+        "Migrating database from {} to {}",
+        legacy_path.display(),
+        db_path.display()
+    );
 
-    // Initialize settings table
-    initialize_settings_table(connection)?;
+    // Copy to a temporary name first so an interrupted copy is never mistaken
+    // for a real database on the next launch
+    let partial_path = db_path.with_extension("db.partial");
+    fs::copy(&legacy_path, &partial_path)?;
+    fs::rename(&partial_path, db_path)?;
 
-    // Initialize activities table
-    initialize_activities_table(connection)?;
+    println!("Database migrated successfully");
+    Ok(true)
+}
 
-    Ok(())
+/// Brings the database schema up to date by applying any pending migrations.
+pub fn initialize_database(connection: &mut Connection) -> std::result::Result<(), MigrationError> {
+    run_migrations(connection)
 }
\ No newline at end of file
@@ -0,0 +1,425 @@
+use rusqlite::{params, Connection, Transaction};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// A single schema change. Migrations are applied in `version` order and the
+/// highest applied version is stored in `PRAGMA user_version`.
+struct Migration {
+    version: i64,
+    name: &'static str,
+    up: fn(&Transaction) -> rusqlite::Result<()>,
+}
+
+/// All schema migrations, oldest first. Never edit or reorder an entry that has
+/// shipped; append a new one instead.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial schema",
+        up: initial_schema,
+    },
+    Migration {
+        version: 2,
+        name: "track mod file IDs",
+        up: track_mod_file_ids,
+    },
+    Migration {
+        version: 3,
+        name: "per-mod file filters",
+        up: mod_file_filters,
+    },
+    Migration {
+        version: 4,
+        name: "webhook kinds",
+        up: webhook_kinds,
+    },
+    Migration {
+        version: 5,
+        name: "webhook service config",
+        up: webhook_config,
+    },
+    Migration {
+        version: 6,
+        name: "email digests",
+        up: email_digests,
+    },
+    Migration {
+        version: 7,
+        name: "notification outbox",
+        up: notification_outbox,
+    },
+    Migration {
+        version: 8,
+        name: "discord message ids",
+        up: discord_message_ids,
+    },
+    Migration {
+        version: 9,
+        name: "shared template library",
+        up: template_library,
+    },
+    Migration {
+        version: 10,
+        name: "digest retries",
+        up: digest_retries,
+    },
+    Migration {
+        version: 11,
+        name: "unique template names",
+        up: unique_template_names,
+    },
+];
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error(
+        "database schema version {found} is newer than this version of the app supports ({supported}); please update the app"
+    )]
+    UnsupportedVersion { found: i64, supported: i64 },
+    #[error("database migration {version} ({name}) failed, all changes were rolled back and the database is still at version {current}: {source}")]
+    Failed {
+        version: i64,
+        name: &'static str,
+        current: i64,
+        #[source]
+        source: rusqlite::Error,
+    },
+    #[error("failed to read or update the database schema version: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub fn schema_version(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Applies all pending migrations inside a single transaction. If any of them
+/// fails the whole upgrade is rolled back, leaving the file at its old version.
+pub fn run_migrations(conn: &mut Connection) -> Result<(), MigrationError> {
+    let current = schema_version(conn)?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(MigrationError::UnsupportedVersion {
+            found: current,
+            supported: latest,
+        });
+    }
+
+    if current == latest {
+        println!("Database schema is up to date (version {})", current);
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        println!(
+            "Applying database migration {}: {}",
+            migration.version, migration.name
+        );
+
+        let failed = |source| MigrationError::Failed {
+            version: migration.version,
+            name: migration.name,
+            current,
+            source,
+        };
+
+        (migration.up)(&tx).map_err(failed)?;
+        tx.pragma_update(None, "user_version", migration.version)
+            .map_err(failed)?;
+    }
+
+    // Dropping the transaction on an early return above rolls everything back
+    tx.commit()?;
+    println!("Database migrated from version {} to {}", current, latest);
+
+    Ok(())
+}
+
+/// Adds a column unless it already exists. Older builds added some columns
+/// ad hoc before migrations were tracked, so those steps must be idempotent.
+fn add_column_if_missing(
+    tx: &Transaction,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        tx.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Uses IF NOT EXISTS so databases created before migrations existed are adopted as-is
+fn initial_schema(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS mods (
+            id INTEGER PRIMARY KEY,
+            curseforge_id INTEGER NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            game_name TEXT NOT NULL,
+            last_updated TEXT NOT NULL,
+            page_url TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS webhooks (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            url TEXT NOT NULL,
+            avatar_url TEXT,
+            username TEXT,
+            enabled BOOLEAN NOT NULL DEFAULT 1,
+            use_custom_template BOOLEAN NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS activities (
+            id INTEGER PRIMARY KEY,
+            activity_type TEXT NOT NULL,
+            mod_id INTEGER,
+            mod_name TEXT,
+            description TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            metadata TEXT,
+            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE SET NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS mod_webhook_assignments (
+            mod_id INTEGER NOT NULL,
+            webhook_id INTEGER NOT NULL,
+            PRIMARY KEY (mod_id, webhook_id),
+            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE CASCADE,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS webhook_templates (
+            id INTEGER PRIMARY KEY,
+            is_default BOOLEAN NOT NULL DEFAULT 0,
+            webhook_id INTEGER UNIQUE,
+            title TEXT NOT NULL DEFAULT '🔄 Mod Update Available!',
+            color INTEGER NOT NULL DEFAULT 5814783,
+            content TEXT,
+            use_embed BOOLEAN NOT NULL DEFAULT 1,
+            author_name TEXT,
+            author_icon_url TEXT,
+            footer_text TEXT,
+            footer_icon_url TEXT,
+            include_timestamp BOOLEAN NOT NULL DEFAULT 1,
+            use_thumbnail BOOLEAN NOT NULL DEFAULT 0,
+            embed_fields TEXT NOT NULL DEFAULT '[
+                    {\"name\":\"Mod Name\",\"value\":\"{modName}\",\"inline\":true},
+                    {\"name\":\"Author\",\"value\":\"{modAuthorName}\",\"inline\":true},
+                    {\"name\":\"Last Updated\",\"value\":\"{newReleaseDate}\",\"inline\":true},
+                    {\"name\":\"Latest File\",\"value\":\"{modID}\",\"inline\":true}
+            ]',
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT
+        );
+
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('api_key', NULL);
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('update_interval', '30');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('show_quick_start', 'true');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('minimize_to_tray', 'false');
+        INSERT OR IGNORE INTO settings (key, value) VALUES ('close_to_tray', 'false');",
+    )?;
+
+    // Check if default template exists
+    let default_exists: bool = tx.query_row(
+        "SELECT EXISTS(SELECT 1 FROM webhook_templates WHERE is_default = 1)",
+        [],
+        |row| row.get(0),
+    )?;
+
+    // Only insert default template if it doesn't exist
+    if !default_exists {
+        tx.execute(
+            "INSERT INTO webhook_templates (
+                is_default, title, color, use_embed, 
+                author_name, author_icon_url,
+                footer_text, footer_icon_url, include_timestamp,
+                use_thumbnail, embed_fields
+            ) VALUES (
+                1, '🔄 Mod Update Available!', 5814783, 1,
+                'Mod Tracker', NULL,
+                'Powered by Artis Mod Tracker', NULL, 1,
+                0,
+                '[
+                    {\"name\":\"Mod Name\",\"value\":\"{modName}\",\"inline\":true},
+                    {\"name\":\"Author\",\"value\":\"{modAuthorName}\",\"inline\":true},
+                    {\"name\":\"Last Updated\",\"value\":\"{newReleaseDate}\",\"inline\":true},
+                    {\"name\":\"Latest File\",\"value\":\"{modID}\",\"inline\":true}
+                ]'
+            )",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn track_mod_file_ids(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "mods", "main_file_id", "INTEGER")?;
+    add_column_if_missing(tx, "mods", "latest_file_ids", "TEXT")?;
+    Ok(())
+}
+
+fn mod_file_filters(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "mods", "game_version_filter", "TEXT")?;
+    add_column_if_missing(tx, "mods", "mod_loader_filter", "TEXT")?;
+    add_column_if_missing(tx, "mods", "release_type_filter", "TEXT")?;
+    Ok(())
+}
+
+fn webhook_kinds(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "webhooks", "kind", "TEXT NOT NULL DEFAULT 'discord'")
+}
+
+fn webhook_config(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "webhooks", "config", "TEXT")
+}
+
+fn email_digests(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS digest_queue (
+            id INTEGER PRIMARY KEY,
+            webhook_id INTEGER NOT NULL,
+            mod_id INTEGER NOT NULL,
+            update_info TEXT NOT NULL,
+            queued_at TEXT NOT NULL,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE,
+            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE CASCADE
+        );",
+    )?;
+    add_column_if_missing(tx, "webhooks", "last_digest_at", "TEXT")
+}
+
+fn notification_outbox(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS notification_outbox (
+            id INTEGER PRIMARY KEY,
+            webhook_id INTEGER NOT NULL,
+            mod_id INTEGER NOT NULL,
+            mod_name TEXT NOT NULL,
+            update_info TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            next_attempt_at TEXT NOT NULL,
+            last_error TEXT,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE,
+            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_notification_outbox_due
+            ON notification_outbox (status, next_attempt_at);",
+    )
+}
+
+fn discord_message_ids(tx: &Transaction) -> rusqlite::Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS discord_messages (
+            webhook_id INTEGER NOT NULL,
+            mod_id INTEGER NOT NULL,
+            message_id TEXT NOT NULL,
+            posted_at TEXT NOT NULL,
+            PRIMARY KEY (webhook_id, mod_id),
+            FOREIGN KEY (webhook_id) REFERENCES webhooks (id) ON DELETE CASCADE,
+            FOREIGN KEY (mod_id) REFERENCES mods (id) ON DELETE CASCADE
+        );",
+    )
+}
+
+// Templates used to belong to one webhook each. They get a name and webhooks point at
+// the template they use instead, so one template can serve many webhooks.
+fn template_library(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(tx, "webhook_templates", "name", "TEXT NOT NULL DEFAULT ''")?;
+    add_column_if_missing(
+        tx,
+        "webhooks",
+        "template_id",
+        "INTEGER REFERENCES webhook_templates (id) ON DELETE SET NULL",
+    )?;
+
+    // SQLite can't drop `webhook_templates.webhook_id` or `webhooks.use_custom_template`
+    // without rebuilding the tables, so they stay behind unused
+    tx.execute_batch(
+        "UPDATE webhooks SET template_id = (
+            SELECT t.id FROM webhook_templates t WHERE t.webhook_id = webhooks.id
+        )
+        WHERE use_custom_template = 1;
+
+        UPDATE webhook_templates SET name = 'Default' WHERE is_default = 1;
+        UPDATE webhook_templates SET name = COALESCE(
+            (SELECT w.name FROM webhooks w WHERE w.id = webhook_templates.webhook_id),
+            'Template ' || id
+        )
+        WHERE is_default = 0;
+
+        UPDATE webhook_templates SET webhook_id = NULL;",
+    )
+}
+
+fn digest_retries(tx: &Transaction) -> rusqlite::Result<()> {
+    add_column_if_missing(
+        tx,
+        "webhooks",
+        "digest_attempts",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(tx, "webhooks", "digest_retry_at", "TEXT")
+}
+
+// Templates named after their webhook could clash with "Default" or with each other,
+// including names that differ only in case. The default template keeps its name and
+// the others get their ID appended until the name is free.
+fn unique_template_names(tx: &Transaction) -> rusqlite::Result<()> {
+    let templates = {
+        let mut stmt =
+            tx.prepare("SELECT id, name FROM webhook_templates ORDER BY is_default DESC, id")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    // SQLite's LOWER only folds ASCII letters, so compare names the way the index will
+    let mut taken = HashSet::new();
+    for (id, name) in templates {
+        let mut unique = name.clone();
+        while !taken.insert(unique.to_ascii_lowercase()) {
+            unique = format!("{} ({})", unique, id);
+        }
+        if unique != name {
+            tx.execute(
+                "UPDATE webhook_templates SET name = ?1 WHERE id = ?2",
+                params![unique, id],
+            )?;
+        }
+    }
+
+    tx.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_templates_name
+         ON webhook_templates (LOWER(name))",
+        [],
+    )?;
+
+    Ok(())
+}
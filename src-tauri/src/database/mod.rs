@@ -1,15 +1,25 @@
 pub mod activities;
+pub mod backup;
+pub mod digests;
+pub mod discord_messages;
 pub mod init;
+pub mod migrations;
 pub mod mods;
+pub mod outbox;
+pub mod pool;
 pub mod settings;
 pub mod webhook_templates;
 pub mod webhooks;
 
 pub use activities::{add_activity, clear_activities, get_recent_activities, Activity};
-pub use init::{ensure_database_exists, get_database_path, initialize_database};
+pub use init::{
+    ensure_database_exists, get_database_path, initialize_database,
+    migrate_legacy_database,
+};
 pub use settings::{
     get_api_key, get_close_to_tray, get_minimize_to_tray, get_show_quick_start,
     get_update_interval, set_api_key, set_close_to_tray, set_minimize_to_tray,
     set_show_quick_start, set_update_interval,
 };
-pub use webhooks::Webhook;
+pub use pool::{create_pool, get_connection, DbPool};
+pub use webhooks::{Webhook, WebhookKind};
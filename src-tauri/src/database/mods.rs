@@ -1,6 +1,23 @@
 use rusqlite::{Connection, Result, params};
 use serde::{Serialize, Deserialize};
 
+/// Optional per-mod filters restricting which CurseForge files count as updates.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModFileFilters {
+    /// Game version the file must target, e.g. `1.20.1`
+    pub game_version: Option<String>,
+    /// Mod loader the file must target, e.g. `Forge` or `Fabric`
+    pub mod_loader: Option<String>,
+    /// Least stable release type to accept: `release`, `beta` or `alpha`
+    pub release_type: Option<String>,
+}
+
+impl ModFileFilters {
+    pub fn is_empty(&self) -> bool {
+        self.game_version.is_none() && self.mod_loader.is_none() && self.release_type.is_none()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Mod {
     pub id: Option<i64>,
@@ -9,6 +26,13 @@ pub struct Mod {
     pub game_name: String,
     pub last_updated: String,
     pub page_url: Option<String>,
+    /// `mainFileId` reported by CurseForge at the last check
+    pub main_file_id: Option<i64>,
+    /// IDs of the `latestFiles` (plus the main file) seen at the last check
+    #[serde(default)]
+    pub latest_file_ids: Vec<i64>,
+    #[serde(default)]
+    pub filters: ModFileFilters,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,6 +45,8 @@ pub struct ModWithWebhooks {
 pub fn get_all_mods(conn: &Connection) -> Result<Vec<ModWithWebhooks>> {
     let mut stmt = conn.prepare(
         "SELECT m.id, m.curseforge_id, m.name, m.game_name, m.last_updated, m.page_url,
+         m.main_file_id, m.latest_file_ids,
+         m.game_version_filter, m.mod_loader_filter, m.release_type_filter,
          GROUP_CONCAT(mwa.webhook_id) as webhook_ids
          FROM mods m
          LEFT JOIN mod_webhook_assignments mwa ON m.id = mwa.mod_id
@@ -29,14 +55,7 @@ pub fn get_all_mods(conn: &Connection) -> Result<Vec<ModWithWebhooks>> {
     )?;
 
     let mods_iter = stmt.query_map([], |row| {
-        let webhook_ids_str: Option<String> = row.get(6)?;
-        let webhook_ids = webhook_ids_str
-            .map(|ids| {
-                ids.split(',')
-                    .filter_map(|id| id.parse::<i64>().ok())
-                    .collect()
-            })
-            .unwrap_or(Vec::new());
+        let webhook_ids = parse_id_list(row.get(11)?);
 
         let mod_info = Mod {
             id: Some(row.get(0)?),
@@ -45,6 +64,13 @@ pub fn get_all_mods(conn: &Connection) -> Result<Vec<ModWithWebhooks>> {
             game_name: row.get(3)?,
             last_updated: row.get(4)?,
             page_url: row.get(5)?,
+            main_file_id: row.get(6)?,
+            latest_file_ids: parse_id_list(row.get(7)?),
+            filters: ModFileFilters {
+                game_version: row.get(8)?,
+                mod_loader: row.get(9)?,
+                release_type: row.get(10)?,
+            },
         };
 
         Ok(ModWithWebhooks {
@@ -61,16 +87,69 @@ pub fn get_all_mods(conn: &Connection) -> Result<Vec<ModWithWebhooks>> {
     Ok(mods)
 }
 
+/// Parses a comma-separated list of IDs, as stored in `latest_file_ids` or
+/// produced by `GROUP_CONCAT`.
+fn parse_id_list(ids: Option<String>) -> Vec<i64> {
+    ids.map(|ids| {
+        ids.split(',')
+            .filter_map(|id| id.parse::<i64>().ok())
+            .collect()
+    })
+    .unwrap_or(Vec::new())
+}
+
+pub(crate) fn format_id_list(ids: &[i64]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn get_mod(conn: &Connection, mod_id: i64) -> Result<Mod> {
+    conn.query_row(
+        "SELECT id, curseforge_id, name, game_name, last_updated, page_url,
+                main_file_id, latest_file_ids,
+                game_version_filter, mod_loader_filter, release_type_filter
+         FROM mods
+         WHERE id = ?1",
+        params![mod_id],
+        |row| {
+            Ok(Mod {
+                id: Some(row.get(0)?),
+                curseforge_id: row.get(1)?,
+                name: row.get(2)?,
+                game_name: row.get(3)?,
+                last_updated: row.get(4)?,
+                page_url: row.get(5)?,
+                main_file_id: row.get(6)?,
+                latest_file_ids: parse_id_list(row.get(7)?),
+                filters: ModFileFilters {
+                    game_version: row.get(8)?,
+                    mod_loader: row.get(9)?,
+                    release_type: row.get(10)?,
+                },
+            })
+        },
+    )
+}
+
 pub fn insert_mod(conn: &Connection, mod_data: &Mod) -> Result<i64> {
     conn.execute(
-        "INSERT INTO mods (curseforge_id, name, game_name, last_updated, page_url)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
+        "INSERT INTO mods (curseforge_id, name, game_name, last_updated, page_url,
+                           main_file_id, latest_file_ids,
+                           game_version_filter, mod_loader_filter, release_type_filter)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
         params![
             mod_data.curseforge_id,
             mod_data.name,
             mod_data.game_name,
             mod_data.last_updated,
             mod_data.page_url,
+            mod_data.main_file_id,
+            format_id_list(&mod_data.latest_file_ids),
+            mod_data.filters.game_version,
+            mod_data.filters.mod_loader,
+            mod_data.filters.release_type,
         ],
     )?;
 
@@ -86,6 +165,43 @@ pub fn update_mod_last_updated(conn: &Connection, mod_id: i64, last_updated: &st
     Ok(())
 }
 
+/// Records the files seen at the latest check along with the new modified date.
+pub fn update_mod_files(
+    conn: &Connection,
+    mod_id: i64,
+    last_updated: &str,
+    main_file_id: i64,
+    latest_file_ids: &[i64],
+) -> Result<()> {
+    conn.execute(
+        "UPDATE mods SET last_updated = ?1, main_file_id = ?2, latest_file_ids = ?3 WHERE id = ?4",
+        params![
+            last_updated,
+            main_file_id,
+            format_id_list(latest_file_ids),
+            mod_id
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn update_mod_filters(conn: &Connection, mod_id: i64, filters: &ModFileFilters) -> Result<()> {
+    conn.execute(
+        "UPDATE mods
+         SET game_version_filter = ?1, mod_loader_filter = ?2, release_type_filter = ?3
+         WHERE id = ?4",
+        params![
+            filters.game_version,
+            filters.mod_loader,
+            filters.release_type,
+            mod_id
+        ],
+    )?;
+
+    Ok(())
+}
+
 pub fn assign_webhook_to_mod(conn: &Connection, mod_id: i64, webhook_id: i64) -> Result<()> {
     conn.execute(
         "INSERT OR IGNORE INTO mod_webhook_assignments (mod_id, webhook_id) VALUES (?1, ?2)",
@@ -0,0 +1,204 @@
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{Connection, Result, Row, params};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutboxStatus {
+    /// Waiting for its first delivery or for a retry
+    Pending,
+    /// Gave up after the maximum number of attempts; only re-sent on request
+    Failed,
+}
+
+impl OutboxStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutboxStatus::Pending => "pending",
+            OutboxStatus::Failed => "failed",
+        }
+    }
+}
+
+impl ToSql for OutboxStatus {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for OutboxStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "pending" => Ok(OutboxStatus::Pending),
+            "failed" => Ok(OutboxStatus::Failed),
+            other => Err(FromSqlError::Other(
+                format!("unknown outbox status \"{}\"", other).into(),
+            )),
+        }
+    }
+}
+
+/// A mod update notification waiting to be delivered to one webhook.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub webhook_name: String,
+    pub mod_id: i64,
+    pub mod_name: String,
+    /// The update as JSON, rendered with the webhook's template when it is sent
+    #[serde(skip_serializing)]
+    pub update_info: String,
+    pub status: OutboxStatus,
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+const OUTBOX_COLUMNS: &str = "o.id, o.webhook_id, w.name, o.mod_id, o.mod_name, o.update_info,
+     o.status, o.attempts, o.next_attempt_at, o.last_error, o.created_at";
+
+// A fixed-width format keeps the stored dates in order when compared as text
+fn format_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_date(value: String) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(&value)
+        .map(|date| date.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+fn entry_from_row(row: &Row) -> Result<OutboxEntry> {
+    Ok(OutboxEntry {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        webhook_name: row.get(2)?,
+        mod_id: row.get(3)?,
+        mod_name: row.get(4)?,
+        update_info: row.get(5)?,
+        status: row.get(6)?,
+        attempts: row.get(7)?,
+        next_attempt_at: parse_date(row.get(8)?),
+        last_error: row.get(9)?,
+        created_at: parse_date(row.get(10)?),
+    })
+}
+
+fn query_entries<P: rusqlite::Params>(conn: &Connection, filter: &str, params: P) -> Result<Vec<OutboxEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
+         FROM notification_outbox o
+         JOIN webhooks w ON w.id = o.webhook_id
+         {}",
+        OUTBOX_COLUMNS, filter
+    ))?;
+
+    let entries_iter = stmt.query_map(params, entry_from_row)?;
+
+    let mut entries = Vec::new();
+    for entry in entries_iter {
+        entries.push(entry?);
+    }
+
+    Ok(entries)
+}
+
+/// Adds a pending delivery, due immediately.
+pub fn enqueue(conn: &Connection, webhook_id: i64, mod_id: i64, mod_name: &str, update_info: &str) -> Result<i64> {
+    let now = format_date(Utc::now());
+    conn.execute(
+        "INSERT INTO notification_outbox (
+            webhook_id, mod_id, mod_name, update_info, status, attempts, next_attempt_at, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?6)",
+        params![webhook_id, mod_id, mod_name, update_info, OutboxStatus::Pending, now],
+    )?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Returns the pending deliveries whose next attempt is due, oldest first.
+pub fn get_due_entries(conn: &Connection, now: DateTime<Utc>) -> Result<Vec<OutboxEntry>> {
+    query_entries(
+        conn,
+        "WHERE o.status = ?1 AND o.next_attempt_at <= ?2 ORDER BY o.next_attempt_at, o.id",
+        params![OutboxStatus::Pending, format_date(now)],
+    )
+}
+
+/// When the earliest pending delivery is due, if there is one.
+pub fn get_next_attempt_at(conn: &Connection) -> Result<Option<DateTime<Utc>>> {
+    let next: Option<String> = conn.query_row(
+        "SELECT MIN(next_attempt_at) FROM notification_outbox WHERE status = ?1",
+        params![OutboxStatus::Pending],
+        |row| row.get(0),
+    )?;
+
+    Ok(next.map(parse_date))
+}
+
+/// Lists every delivery still in the outbox, failed ones first.
+pub fn get_all_entries(conn: &Connection) -> Result<Vec<OutboxEntry>> {
+    query_entries(
+        conn,
+        "ORDER BY o.status = 'failed' DESC, o.created_at DESC, o.id DESC",
+        [],
+    )
+}
+
+/// Records a failed attempt. With a `next_attempt_at` the delivery is retried then,
+/// without one it is moved to the failed deliveries.
+pub fn record_failure(
+    conn: &Connection,
+    id: i64,
+    attempts: i64,
+    next_attempt_at: Option<DateTime<Utc>>,
+    error: &str,
+) -> Result<()> {
+    let (status, next_attempt_at) = match next_attempt_at {
+        Some(next) => (OutboxStatus::Pending, next),
+        None => (OutboxStatus::Failed, Utc::now()),
+    };
+
+    conn.execute(
+        "UPDATE notification_outbox
+         SET status = ?1, attempts = ?2, next_attempt_at = ?3, last_error = ?4
+         WHERE id = ?5",
+        params![status, attempts, format_date(next_attempt_at), error, id],
+    )?;
+
+    Ok(())
+}
+
+/// Makes a failed delivery pending again with a fresh set of attempts.
+pub fn requeue_entry(conn: &Connection, id: i64) -> Result<bool> {
+    let updated = conn.execute(
+        "UPDATE notification_outbox
+         SET status = ?1, attempts = 0, next_attempt_at = ?2
+         WHERE id = ?3",
+        params![OutboxStatus::Pending, format_date(Utc::now()), id],
+    )?;
+
+    Ok(updated > 0)
+}
+
+/// Makes every failed delivery pending again. Returns how many were re-queued.
+pub fn requeue_failed_entries(conn: &Connection) -> Result<usize> {
+    conn.execute(
+        "UPDATE notification_outbox
+         SET status = ?1, attempts = 0, next_attempt_at = ?2
+         WHERE status = ?3",
+        params![OutboxStatus::Pending, format_date(Utc::now()), OutboxStatus::Failed],
+    )
+}
+
+pub fn delete_entry(conn: &Connection, id: i64) -> Result<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM notification_outbox WHERE id = ?1",
+        params![id],
+    )?;
+
+    Ok(deleted > 0)
+}
@@ -0,0 +1,45 @@
+use crate::error::TrackerError;
+use r2d2::{CustomizeConnection, Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::Connection;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+pub type DbPool = Pool<SqliteConnectionManager>;
+pub type DbConnection = PooledConnection<SqliteConnectionManager>;
+
+const MAX_CONNECTIONS: u32 = 8;
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Applies the per-connection settings every pooled connection relies on.
+/// SQLite forgets these between connections, so they must be set on each one.
+#[derive(Debug)]
+struct ConnectionOptions;
+
+impl CustomizeConnection<Connection, rusqlite::Error> for ConnectionOptions {
+    fn on_acquire(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             PRAGMA journal_mode = WAL;",
+        )
+    }
+}
+
+pub fn create_pool(db_path: &Path) -> Result<DbPool, r2d2::Error> {
+    let manager = SqliteConnectionManager::file(db_path);
+    Pool::builder()
+        .max_size(MAX_CONNECTIONS)
+        .connection_customizer(Box::new(ConnectionOptions))
+        .build(manager)
+}
+
+/// Borrows a connection from the pool managed as Tauri state. The connection
+/// goes back to the pool when dropped.
+pub fn get_connection(app_handle: &AppHandle) -> Result<DbConnection, TrackerError> {
+    app_handle.state::<DbPool>().get().map_err(|e| {
+        println!("Failed to get database connection: {}", e);
+        TrackerError::from(e)
+    })
+}
@@ -10,52 +10,6 @@ pub struct Settings {
     pub close_to_tray: bool,
 }
 
-pub fn initialize_settings_table(conn: &mut Connection) -> Result<()> {
-    println!("Initializing settings table...");
-
-    // Create settings table if it doesn't exist
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS settings (
-            key TEXT PRIMARY KEY,
-            value TEXT
-        )",
-        [],
-    )?;
-
-    // Insert default settings using a transaction
-    let tx = conn.transaction()?;
-
-    // Insert default values if they don't exist
-    tx.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('api_key', NULL)",
-        [],
-    )?;
-
-    tx.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('update_interval', '30')",
-        [],
-    )?;
-
-    tx.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('show_quick_start', 'true')",
-        [],
-    )?;
-
-    tx.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('minimize_to_tray', 'false')",
-        [],
-    )?;
-
-    tx.execute(
-        "INSERT OR IGNORE INTO settings (key, value) VALUES ('close_to_tray', 'false')",
-        [],
-    )?;
-
-    tx.commit()?;
-    println!("Settings table initialized successfully");
-    Ok(())
-}
-
 pub fn get_api_key(conn: &Connection) -> Result<Option<String>> {
     println!("Fetching API key from database...");
     match conn.query_row(
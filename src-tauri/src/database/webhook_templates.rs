@@ -1,10 +1,17 @@
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, OptionalExtension, Result, Row, params};
 use serde::{Serialize, Deserialize};
 
+/// A named message template. Any number of webhooks can use the same template;
+/// webhooks without one use the default template.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WebhookTemplate {
     pub id: Option<i64>,
+    #[serde(default)]
+    pub name: String,
     pub is_default: bool,
+    /// Only set in backups made before templates were shared, where each custom
+    /// template belonged to the webhook with this ID
+    #[serde(default, skip_serializing)]
     pub webhook_id: Option<i64>,
     pub title: String,
     pub color: i32,
@@ -19,159 +26,174 @@ pub struct WebhookTemplate {
     pub embed_fields: String,
 }
 
+const TEMPLATE_COLUMNS: &str = "t.id, t.name, t.is_default, t.title, t.color, t.content, t.use_embed,
+     t.author_name, t.author_icon_url, t.footer_text, t.footer_icon_url,
+     t.include_timestamp, t.use_thumbnail, t.embed_fields";
+
+fn template_from_row(row: &Row) -> Result<WebhookTemplate> {
+    Ok(WebhookTemplate {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        is_default: row.get(2)?,
+        webhook_id: None,
+        title: row.get(3)?,
+        color: row.get(4)?,
+        content: row.get(5)?,
+        use_embed: row.get(6)?,
+        author_name: row.get(7)?,
+        author_icon_url: row.get(8)?,
+        footer_text: row.get(9)?,
+        footer_icon_url: row.get(10)?,
+        include_timestamp: row.get(11)?,
+        use_thumbnail: row.get(12)?,
+        embed_fields: row.get(13)?,
+    })
+}
+
+/// Gets the template a webhook sends its notifications with: the one assigned to it,
+/// or the default template if it has none. A webhook_id of -1 returns the default.
 pub fn get_webhook_template(conn: &Connection, webhook_id: i64) -> Result<WebhookTemplate> {
-    let query = "SELECT id, is_default, webhook_id, title, color, content, use_embed,
-                       author_name, author_icon_url, footer_text, footer_icon_url,
-                       include_timestamp, use_thumbnail, embed_fields
-                FROM webhook_templates
-                WHERE ";
-
-    let query = if webhook_id == -1 {
-        format!("{} is_default = 1", query)
-    } else {
-        format!("{} webhook_id = ?1", query)
-    };
-
-    let params: Vec<&dyn rusqlite::ToSql> = if webhook_id == -1 {
-        vec![]
-    } else {
-        vec![&webhook_id]
-    };
-
-    let template = conn.query_row(
-        &query,
-        params.as_slice(),
-        |row| {
-            Ok(WebhookTemplate {
-                id: Some(row.get(0)?),
-                is_default: row.get(1)?,
-                webhook_id: row.get(2)?,
-                title: row.get(3)?,
-                color: row.get(4)?,
-                content: row.get(5)?,
-                use_embed: row.get(6)?,
-                author_name: row.get(7)?,
-                author_icon_url: row.get(8)?,
-                footer_text: row.get(9)?,
-                footer_icon_url: row.get(10)?,
-                include_timestamp: row.get(11)?,
-                use_thumbnail: row.get(12)?,
-                embed_fields: row.get(13)?,
-            })
-        },
-    ).or_else(|_| {
-        // If no template found, return the default one
-        conn.query_row(
-            "SELECT id, is_default, webhook_id, title, color, content, use_embed,
-                    author_name, author_icon_url, footer_text, footer_icon_url,
-                    include_timestamp, use_thumbnail, embed_fields
-             FROM webhook_templates
-             WHERE is_default = 1",
-            [],
-            |row| {
-                Ok(WebhookTemplate {
-                    id: Some(row.get(0)?),
-                    is_default: row.get(1)?,
-                    webhook_id: row.get(2)?,
-                    title: row.get(3)?,
-                    color: row.get(4)?,
-                    content: row.get(5)?,
-                    use_embed: row.get(6)?,
-                    author_name: row.get(7)?,
-                    author_icon_url: row.get(8)?,
-                    footer_text: row.get(9)?,
-                    footer_icon_url: row.get(10)?,
-                    include_timestamp: row.get(11)?,
-                    use_thumbnail: row.get(12)?,
-                    embed_fields: row.get(13)?,
-                })
-            },
-        )
-    })?;
-
-    Ok(template)
+    let assigned = conn.query_row(
+        &format!(
+            "SELECT {} FROM webhook_templates t
+             JOIN webhooks w ON w.template_id = t.id
+             WHERE w.id = ?1",
+            TEMPLATE_COLUMNS
+        ),
+        params![webhook_id],
+        template_from_row,
+    ).optional()?;
+
+    match assigned {
+        Some(template) => Ok(template),
+        None => get_default_template(conn),
+    }
+}
+
+pub fn get_default_template(conn: &Connection) -> Result<WebhookTemplate> {
+    conn.query_row(
+        &format!("SELECT {} FROM webhook_templates t WHERE t.is_default = 1", TEMPLATE_COLUMNS),
+        [],
+        template_from_row,
+    )
+}
+
+pub fn get_template(conn: &Connection, template_id: i64) -> Result<WebhookTemplate> {
+    conn.query_row(
+        &format!("SELECT {} FROM webhook_templates t WHERE t.id = ?1", TEMPLATE_COLUMNS),
+        params![template_id],
+        template_from_row,
+    )
+}
+
+/// Checks if a template name already exists, optionally excluding a specific template ID
+pub fn template_name_exists(conn: &Connection, name: &str, exclude_id: Option<i64>) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM webhook_templates
+         WHERE LOWER(name) = LOWER(?1) AND id != COALESCE(?2, -1)",
+        params![name, exclude_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(count > 0)
+}
+
+/// Adds a template to the library. The default template already exists, so new
+/// templates never become it.
+pub fn insert_template(conn: &Connection, template: &WebhookTemplate) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO webhook_templates (
+            name, is_default, title, color, content, use_embed,
+            author_name, author_icon_url,
+            footer_text, footer_icon_url,
+            include_timestamp, use_thumbnail, embed_fields
+         ) VALUES (?1, 0, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            template.name,
+            template.title,
+            template.color,
+            template.content,
+            template.use_embed,
+            template.author_name,
+            template.author_icon_url,
+            template.footer_text,
+            template.footer_icon_url,
+            template.include_timestamp,
+            template.use_thumbnail,
+            template.embed_fields,
+        ],
+    )?;
+
+    Ok(conn.last_insert_rowid())
 }
 
-pub fn update_webhook_template(conn: &mut Connection, template: &WebhookTemplate) -> Result<()> {
-    if template.is_default {
-        conn.execute(
-            "UPDATE webhook_templates 
-             SET title = ?1, color = ?2, content = ?3, use_embed = ?4,
-                 author_name = ?5, author_icon_url = ?6,
-                 footer_text = ?7, footer_icon_url = ?8,
-                 include_timestamp = ?9, use_thumbnail = ?10, embed_fields = ?11
-             WHERE is_default = 1",
-            params![
-                template.title,
-                template.color,
-                template.content,
-                template.use_embed,
-                template.author_name,
-                template.author_icon_url,
-                template.footer_text,
-                template.footer_icon_url,
-                template.include_timestamp,
-                template.use_thumbnail,
-                template.embed_fields,
-            ],
-        )?;
-    } else {
-        conn.execute(
-            "INSERT INTO webhook_templates (
-                webhook_id, title, color, content, use_embed,
-                author_name, author_icon_url,
-                footer_text, footer_icon_url,
-                include_timestamp, use_thumbnail, embed_fields
-             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
-             ON CONFLICT(webhook_id) DO UPDATE SET
-             title = ?2, color = ?3, content = ?4, use_embed = ?5,
+/// Saves a template by its ID. Every webhook using it sends the new version from
+/// then on.
+pub fn update_webhook_template(conn: &Connection, template: &WebhookTemplate) -> Result<()> {
+    conn.execute(
+        "UPDATE webhook_templates
+         SET name = ?1, title = ?2, color = ?3, content = ?4, use_embed = ?5,
              author_name = ?6, author_icon_url = ?7,
              footer_text = ?8, footer_icon_url = ?9,
-             include_timestamp = ?10, use_thumbnail = ?11, embed_fields = ?12",
-            params![
-                template.webhook_id,
-                template.title,
-                template.color,
-                template.content,
-                template.use_embed,
-                template.author_name,
-                template.author_icon_url,
-                template.footer_text,
-                template.footer_icon_url,
-                template.include_timestamp,
-                template.use_thumbnail,
-                template.embed_fields,
-            ],
-        )?;
-
-        // Update webhook to use custom template
-        if let Some(webhook_id) = template.webhook_id {
-            conn.execute(
-                "UPDATE webhooks SET use_custom_template = 1 WHERE id = ?1",
-                params![webhook_id],
-            )?;
-        }
-    }
+             include_timestamp = ?10, use_thumbnail = ?11, embed_fields = ?12
+         WHERE id = ?13",
+        params![
+            template.name,
+            template.title,
+            template.color,
+            template.content,
+            template.use_embed,
+            template.author_name,
+            template.author_icon_url,
+            template.footer_text,
+            template.footer_icon_url,
+            template.include_timestamp,
+            template.use_thumbnail,
+            template.embed_fields,
+            template.id,
+        ],
+    )?;
+
+    Ok(())
+}
+
+pub fn rename_template(conn: &Connection, template_id: i64, name: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE webhook_templates SET name = ?1 WHERE id = ?2",
+        params![name, template_id],
+    )?;
 
     Ok(())
 }
 
-pub fn delete_custom_template(conn: &mut Connection, webhook_id: i64) -> Result<()> {
+/// Deletes a template from the library. Webhooks that used it go back to the
+/// default template, which itself can't be deleted.
+pub fn delete_template(conn: &mut Connection, template_id: i64) -> Result<()> {
     let tx = conn.transaction()?;
 
-    // Delete the custom template
     tx.execute(
-        "DELETE FROM webhook_templates WHERE webhook_id = ?1",
-        params![webhook_id],
+        "UPDATE webhooks SET template_id = NULL WHERE template_id = ?1",
+        params![template_id],
     )?;
 
-    // Update webhook to not use custom template
     tx.execute(
-        "UPDATE webhooks SET use_custom_template = 0 WHERE id = ?1",
-        params![webhook_id],
+        "DELETE FROM webhook_templates WHERE id = ?1 AND is_default = 0",
+        params![template_id],
     )?;
 
     tx.commit()?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn get_all_webhook_templates(conn: &Connection) -> Result<Vec<WebhookTemplate>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM webhook_templates t
+         ORDER BY t.is_default DESC, LOWER(t.name), t.id",
+        TEMPLATE_COLUMNS
+    ))?;
+
+    let templates = stmt.query_map([], template_from_row)?;
+
+    templates.collect()
+}
@@ -1,6 +1,79 @@
-use rusqlite::{Connection, Result, params};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{Connection, Result, Row, params};
 use serde::{Serialize, Deserialize};
 
+/// The service a webhook posts to, which decides how notifications are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Discord,
+    Slack,
+    Telegram,
+    Http,
+    Matrix,
+    Ntfy,
+    Gotify,
+    Email,
+}
+
+impl Default for WebhookKind {
+    fn default() -> Self {
+        WebhookKind::Discord
+    }
+}
+
+impl WebhookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookKind::Discord => "discord",
+            WebhookKind::Slack => "slack",
+            WebhookKind::Telegram => "telegram",
+            WebhookKind::Http => "http",
+            WebhookKind::Matrix => "matrix",
+            WebhookKind::Ntfy => "ntfy",
+            WebhookKind::Gotify => "gotify",
+            WebhookKind::Email => "email",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WebhookKind::Discord => "Discord",
+            WebhookKind::Slack => "Slack",
+            WebhookKind::Telegram => "Telegram",
+            WebhookKind::Http => "HTTP",
+            WebhookKind::Matrix => "Matrix",
+            WebhookKind::Ntfy => "ntfy",
+            WebhookKind::Gotify => "Gotify",
+            WebhookKind::Email => "Email",
+        }
+    }
+}
+
+impl ToSql for WebhookKind {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for WebhookKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "discord" => Ok(WebhookKind::Discord),
+            "slack" => Ok(WebhookKind::Slack),
+            "telegram" => Ok(WebhookKind::Telegram),
+            "http" => Ok(WebhookKind::Http),
+            "matrix" => Ok(WebhookKind::Matrix),
+            "ntfy" => Ok(WebhookKind::Ntfy),
+            "gotify" => Ok(WebhookKind::Gotify),
+            "email" => Ok(WebhookKind::Email),
+            other => Err(FromSqlError::Other(
+                format!("unknown webhook kind \"{}\"", other).into(),
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Webhook {
     pub id: Option<i64>,
@@ -9,7 +82,32 @@ pub struct Webhook {
     pub avatar_url: Option<String>,
     pub username: Option<String>,
     pub enabled: bool,
-    pub use_custom_template: bool,
+    /// The library template this webhook sends with, or `None` for the default template
+    #[serde(default)]
+    pub template_id: Option<i64>,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    /// Service-specific settings, e.g. the bot token and chat ID of a Telegram webhook
+    /// or the method, headers and body template of an HTTP webhook.
+    #[serde(default)]
+    pub config: serde_json::Value,
+}
+
+const WEBHOOK_COLUMNS: &str = "w.id, w.name, w.url, w.avatar_url, w.username, w.enabled,
+     w.template_id, w.kind, w.config";
+
+fn webhook_from_row(row: &Row) -> Result<Webhook> {
+    Ok(Webhook {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        url: row.get(2)?,
+        avatar_url: row.get(3)?,
+        username: row.get(4)?,
+        enabled: row.get(5)?,
+        template_id: row.get(6)?,
+        kind: row.get(7)?,
+        config: row.get(8)?,
+    })
 }
 
 /// Checks if a webhook name already exists, optionally excluding a specific webhook ID
@@ -38,15 +136,17 @@ pub fn insert_webhook(conn: &mut Connection, webhook: &Webhook) -> Result<i64> {
     }
 
     conn.execute(
-        "INSERT INTO webhooks (name, url, avatar_url, username, enabled, use_custom_template)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        "INSERT INTO webhooks (name, url, avatar_url, username, enabled, template_id, kind, config)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         params![
             webhook.name,
             webhook.url,
             webhook.avatar_url,
             webhook.username,
             webhook.enabled,
-            webhook.use_custom_template,
+            webhook.template_id,
+            webhook.kind,
+            webhook.config,
         ],
     )?;
 
@@ -54,23 +154,12 @@ pub fn insert_webhook(conn: &mut Connection, webhook: &Webhook) -> Result<i64> {
 }
 
 pub fn get_all_webhooks(conn: &Connection) -> Result<Vec<Webhook>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, name, url, avatar_url, username, enabled, use_custom_template 
-         FROM webhooks
-         ORDER BY name"
-    )?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM webhooks w ORDER BY w.name",
+        WEBHOOK_COLUMNS
+    ))?;
 
-    let webhooks_iter = stmt.query_map([], |row| {
-        Ok(Webhook {
-            id: Some(row.get(0)?),
-            name: row.get(1)?,
-            url: row.get(2)?,
-            avatar_url: row.get(3)?,
-            username: row.get(4)?,
-            enabled: row.get(5)?,
-            use_custom_template: row.get(6)?,
-        })
-    })?;
+    let webhooks_iter = stmt.query_map([], webhook_from_row)?;
 
     let mut webhooks = Vec::new();
     for webhook_result in webhooks_iter {
@@ -91,15 +180,17 @@ pub fn update_webhook(conn: &mut Connection, webhook: &Webhook) -> Result<()> {
 
     conn.execute(
         "UPDATE webhooks 
-         SET name = ?1, url = ?2, avatar_url = ?3, username = ?4, enabled = ?5, use_custom_template = ?6
-         WHERE id = ?7",
+         SET name = ?1, url = ?2, avatar_url = ?3, username = ?4, enabled = ?5, template_id = ?6, kind = ?7, config = ?8
+         WHERE id = ?9",
         params![
             webhook.name,
             webhook.url,
             webhook.avatar_url,
             webhook.username,
             webhook.enabled,
-            webhook.use_custom_template,
+            webhook.template_id,
+            webhook.kind,
+            webhook.config,
             webhook.id,
         ],
     )?;
@@ -116,9 +207,21 @@ pub fn delete_webhook(conn: &mut Connection, webhook_id: i64) -> Result<()> {
         params![webhook_id],
     )?;
 
-    // Delete custom template if exists
+    // Drop deliveries still waiting in the outbox
+    tx.execute(
+        "DELETE FROM notification_outbox WHERE webhook_id = ?1",
+        params![webhook_id],
+    )?;
+
+    // Drop updates still waiting for a digest
     tx.execute(
-        "DELETE FROM webhook_templates WHERE webhook_id = ?1",
+        "DELETE FROM digest_queue WHERE webhook_id = ?1",
+        params![webhook_id],
+    )?;
+
+    // Forget the messages it posted
+    tx.execute(
+        "DELETE FROM discord_messages WHERE webhook_id = ?1",
         params![webhook_id],
     )?;
 
@@ -134,25 +237,16 @@ pub fn delete_webhook(conn: &mut Connection, webhook_id: i64) -> Result<()> {
 }
 
 pub fn get_mod_webhooks(conn: &Connection, mod_id: i64) -> Result<Vec<Webhook>> {
-    let mut stmt = conn.prepare(
-        "SELECT w.id, w.name, w.url, w.avatar_url, w.username, w.enabled, w.use_custom_template
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
          FROM webhooks w
          JOIN mod_webhook_assignments mwa ON w.id = mwa.webhook_id
          WHERE mwa.mod_id = ?1
-         ORDER BY w.name"
-    )?;
+         ORDER BY w.name",
+        WEBHOOK_COLUMNS
+    ))?;
 
-    let webhooks_iter = stmt.query_map(params![mod_id], |row| {
-        Ok(Webhook {
-            id: Some(row.get(0)?),
-            name: row.get(1)?,
-            url: row.get(2)?,
-            avatar_url: row.get(3)?,
-            username: row.get(4)?,
-            enabled: row.get(5)?,
-            use_custom_template: row.get(6)?,
-        })
-    })?;
+    let webhooks_iter = stmt.query_map(params![mod_id], webhook_from_row)?;
 
     let mut webhooks = Vec::new();
     for webhook_result in webhooks_iter {
@@ -160,4 +254,12 @@ pub fn get_mod_webhooks(conn: &Connection, mod_id: i64) -> Result<Vec<Webhook>>
     }
 
     Ok(webhooks)
+}
+
+pub fn get_webhook(conn: &Connection, webhook_id: i64) -> Result<Webhook> {
+    conn.query_row(
+        &format!("SELECT {} FROM webhooks w WHERE w.id = ?1", WEBHOOK_COLUMNS),
+        params![webhook_id],
+        webhook_from_row,
+    )
 }
\ No newline at end of file
@@ -0,0 +1,177 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use rusqlite::ErrorCode;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Error returned by every Tauri command. Serialized to the frontend as
+/// `{ code, message, details }`, where `code` is stable and safe to branch on and
+/// `details` carries variant-specific data such as `retryAfter` or `status`.
+#[derive(Debug, Clone, Error)]
+pub enum TrackerError {
+    #[error("No CurseForge API key configured")]
+    MissingApiKey,
+    #[error("The CurseForge API key was rejected, please check it in the settings")]
+    InvalidApiKey,
+    #[error("Mod {curseforge_id} was not found on CurseForge")]
+    ModNotFound { curseforge_id: i64 },
+    #[error("A mod with CurseForge ID {curseforge_id} is already being tracked")]
+    ModAlreadyTracked { curseforge_id: i64 },
+    #[error("A webhook named \"{name}\" already exists")]
+    WebhookNameTaken { name: String },
+    #[error("A template named \"{name}\" already exists")]
+    TemplateNameTaken { name: String },
+    #[error("{entity} {id} was not found")]
+    NotFound { entity: &'static str, id: i64 },
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("Rate limited by {service}")]
+    RateLimited {
+        service: String,
+        retry_after: Option<f64>,
+    },
+    #[error("CurseForge API error ({status}): {message}")]
+    CurseForge { status: u16, message: String },
+    #[error("{service} API error ({status}): {message}")]
+    WebhookDelivery {
+        service: String,
+        status: u16,
+        message: String,
+    },
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("The database is busy, please try again")]
+    DatabaseLocked,
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("File error: {0}")]
+    Io(String),
+    #[error("An update check is already in progress")]
+    UpdateCheckInProgress,
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl TrackerError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TrackerError::MissingApiKey => "missing_api_key",
+            TrackerError::InvalidApiKey => "invalid_api_key",
+            TrackerError::ModNotFound { .. } => "mod_not_found",
+            TrackerError::ModAlreadyTracked { .. } => "mod_already_tracked",
+            TrackerError::WebhookNameTaken { .. } => "webhook_name_taken",
+            TrackerError::TemplateNameTaken { .. } => "template_name_taken",
+            TrackerError::NotFound { .. } => "not_found",
+            TrackerError::InvalidInput(_) => "invalid_input",
+            TrackerError::RateLimited { .. } => "rate_limited",
+            TrackerError::CurseForge { .. } => "curseforge_error",
+            TrackerError::WebhookDelivery { .. } => "webhook_delivery_failed",
+            TrackerError::Network(_) => "network_error",
+            TrackerError::DatabaseLocked => "database_locked",
+            TrackerError::Database(_) => "database_error",
+            TrackerError::Io(_) => "io_error",
+            TrackerError::UpdateCheckInProgress => "update_check_in_progress",
+            TrackerError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            TrackerError::ModNotFound { curseforge_id }
+            | TrackerError::ModAlreadyTracked { curseforge_id } => {
+                json!({ "curseforgeId": curseforge_id })
+            }
+            TrackerError::WebhookNameTaken { name } | TrackerError::TemplateNameTaken { name } => {
+                json!({ "name": name })
+            }
+            TrackerError::NotFound { entity, id } => json!({ "entity": entity, "id": id }),
+            TrackerError::RateLimited {
+                service,
+                retry_after,
+            } => json!({ "service": service, "retryAfter": retry_after }),
+            TrackerError::CurseForge { status, .. } => {
+                json!({ "service": "CurseForge", "status": status })
+            }
+            TrackerError::WebhookDelivery {
+                service, status, ..
+            } => json!({ "service": service, "status": status }),
+            _ => Value::Null,
+        }
+    }
+
+    /// Seconds to wait before retrying, for errors where the remote service said so.
+    pub fn retry_after(&self) -> Option<f64> {
+        match self {
+            TrackerError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Builds a rate limit error from a 429 response's `Retry-After` header.
+    pub fn rate_limited(service: &str, headers: &HeaderMap) -> Self {
+        TrackerError::RateLimited {
+            service: service.to_string(),
+            retry_after: headers
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<f64>().ok()),
+        }
+    }
+
+    pub fn webhook_delivery(service: &str, status: StatusCode, message: impl Into<String>) -> Self {
+        TrackerError::WebhookDelivery {
+            service: service.to_string(),
+            status: status.as_u16(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Serialize for TrackerError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TrackerError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<rusqlite::Error> for TrackerError {
+    fn from(error: rusqlite::Error) -> Self {
+        match error.sqlite_error_code() {
+            Some(ErrorCode::DatabaseBusy) | Some(ErrorCode::DatabaseLocked) => {
+                TrackerError::DatabaseLocked
+            }
+            _ => TrackerError::Database(error.to_string()),
+        }
+    }
+}
+
+impl From<r2d2::Error> for TrackerError {
+    fn from(error: r2d2::Error) -> Self {
+        TrackerError::Database(error.to_string())
+    }
+}
+
+impl From<reqwest::Error> for TrackerError {
+    fn from(error: reqwest::Error) -> Self {
+        // Webhook and bot URLs carry their token, so the URL never goes into the
+        // message that ends up in the activity log and the outbox
+        TrackerError::Network(error.without_url().to_string())
+    }
+}
+
+impl From<std::io::Error> for TrackerError {
+    fn from(error: std::io::Error) -> Self {
+        TrackerError::Io(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for TrackerError {
+    fn from(error: serde_json::Error) -> Self {
+        TrackerError::Internal(error.to_string())
+    }
+}
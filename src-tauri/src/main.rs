@@ -1,24 +1,42 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
+mod curseforge;
 mod database;
+mod error;
+mod notifications;
 mod tray;
+mod update_service;
 
 use commands::activity_commands::*;
+use commands::backup_commands::*;
 use commands::mod_commands::*;
+use commands::outbox_commands::*;
 use commands::settings_commands::*;
+use commands::update_service_commands::*;
 use commands::webhook_commands::*;
 use commands::webhook_template_commands::*;
-use database::{ensure_database_exists, get_database_path, initialize_database};
-use rusqlite::Connection;
+use curseforge::{base_url_from_env, CurseForgeClient};
+use database::{
+    create_pool, ensure_database_exists, get_database_path, initialize_database,
+    migrate_legacy_database,
+};
+use notifications::rate_limit::DiscordRateLimiter;
 use tauri::Manager;
 use tray::{create_tray, handle_tray_event, handle_window_event};
+use update_service::UpdateService;
 
 use tauri_plugin_context_menu::init as init_context_menu;
 
 fn main() {
+    let curseforge_client =
+        CurseForgeClient::new(base_url_from_env()).expect("failed to create CurseForge client");
+
     tauri::Builder::default()
         .plugin(init_context_menu())
+        .manage(curseforge_client)
+        .manage(UpdateService::new())
+        .manage(DiscordRateLimiter::new())
         .system_tray(create_tray())
         .on_system_tray_event(handle_tray_event)
         .on_window_event(|event| {
@@ -29,8 +47,11 @@ fn main() {
         .setup(|app| {
             let db_path = get_database_path(&app.handle());
             ensure_database_exists(&db_path)?;
-            let mut conn = Connection::open(&db_path)?;
-            initialize_database(&mut conn)?;
+            migrate_legacy_database(&app.handle(), &db_path)?;
+            let pool = create_pool(&db_path)?;
+            initialize_database(&mut pool.get()?)?;
+            app.manage(pool);
+            update_service::start(&app.handle());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -42,6 +63,7 @@ fn main() {
             remove_webhook_assignment,
             check_mod_update,
             get_mod_assigned_webhooks,
+            set_mod_filters,
             // Webhook commands
             add_webhook,
             get_webhooks,
@@ -49,10 +71,20 @@ fn main() {
             delete_webhook,
             test_webhook,
             send_update_notification,
+            // Outbox commands
+            get_outbox_entries,
+            resend_failed_delivery,
+            resend_all_failed_deliveries,
+            discard_failed_delivery,
             // Webhook template commands
+            get_webhook_templates,
             get_webhook_template,
+            create_webhook_template,
             update_webhook_template,
-            delete_custom_template,
+            duplicate_webhook_template,
+            rename_webhook_template,
+            delete_webhook_template,
+            render_webhook_payload,
             // Settings commands
             get_api_key,
             set_api_key,
@@ -69,6 +101,15 @@ fn main() {
             add_activity_entry,
             get_activities,
             clear_activity_history,
+            // Backup commands
+            export_backup,
+            import_backup,
+            // Update service commands
+            start_update_service,
+            pause_update_service,
+            resume_update_service,
+            check_now,
+            get_update_service_status,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,903 @@
+use crate::database::WebhookKind;
+
+/// The markup a changelog is converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangelogFormat {
+    /// Discord's Markdown: `**bold**`, `[text](url)`, `- ` lists
+    Markdown,
+    /// Slack's mrkdwn: `*bold*`, `_italic_`, `• ` lists
+    Slack,
+    /// No markup; links are written out after their text
+    PlainText,
+}
+
+impl ChangelogFormat {
+    /// The format a webhook of `kind` shows changelogs in. Services that escape the
+    /// rendered text themselves get plain text.
+    pub fn for_kind(kind: WebhookKind) -> Self {
+        match kind {
+            WebhookKind::Discord => ChangelogFormat::Markdown,
+            WebhookKind::Slack => ChangelogFormat::Slack,
+            WebhookKind::Telegram
+            | WebhookKind::Http
+            | WebhookKind::Matrix
+            | WebhookKind::Ntfy
+            | WebhookKind::Gotify
+            | WebhookKind::Email => ChangelogFormat::PlainText,
+        }
+    }
+
+    fn bold(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "**",
+            ChangelogFormat::Slack => "*",
+            ChangelogFormat::PlainText => "",
+        }
+    }
+
+    fn italic(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "*",
+            ChangelogFormat::Slack => "_",
+            ChangelogFormat::PlainText => "",
+        }
+    }
+
+    fn underline(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "__",
+            ChangelogFormat::Slack | ChangelogFormat::PlainText => "",
+        }
+    }
+
+    fn strikethrough(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "~~",
+            ChangelogFormat::Slack => "~",
+            ChangelogFormat::PlainText => "",
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown | ChangelogFormat::Slack => "`",
+            ChangelogFormat::PlainText => "",
+        }
+    }
+
+    fn bullet(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "-",
+            ChangelogFormat::Slack | ChangelogFormat::PlainText => "•",
+        }
+    }
+}
+
+const CURSEFORGE_URL: &str = "https://www.curseforge.com";
+
+// Elements that never have content
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+// Elements whose content is never shown
+const HIDDEN_ELEMENTS: &[&str] = &["script", "style", "head", "title", "template"];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "center",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "pre",
+    "hr",
+    "table",
+    "details",
+    "summary",
+];
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+}
+
+impl Node {
+    fn is_block(&self) -> bool {
+        match self {
+            Node::Element { name, .. } => BLOCK_ELEMENTS.contains(&name.as_str()),
+            Node::Text(_) => false,
+        }
+    }
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn decode(text: &str) -> String {
+    html_escape::decode_html_entities(text).to_string()
+}
+
+// Reads the attributes of a start tag, up to its closing `>`. Returns them, whether
+// the tag closed itself with `/>` and the rest of the input.
+fn parse_attributes(input: &str) -> (Vec<(String, String)>, bool, &str) {
+    let mut attributes = Vec::new();
+    let mut rest = input;
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return (attributes, true, after);
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return (attributes, false, after);
+        }
+        if rest.is_empty() {
+            return (attributes, false, rest);
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        // A stray `/` inside the tag is skipped
+        let name_end = name_end.max(rest.chars().next().map_or(0, char::len_utf8));
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            match after.chars().next() {
+                Some(quote) if quote == '"' || quote == '\'' => {
+                    let end = after[1..].find(quote).map_or(after.len(), |end| end + 1);
+                    value = decode(&after[1..end]);
+                    rest = after.get(end + 1..).unwrap_or("");
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(after.len());
+                    value = decode(&after[..end]);
+                    rest = &after[end..];
+                }
+            }
+        }
+        if name != "/" {
+            attributes.push((name, value));
+        }
+    }
+}
+
+// An element being parsed, whose children are still being collected
+struct OpenElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+// Closes the innermost open element, adding it to its parent
+fn close_element(stack: &mut Vec<OpenElement>, root: &mut Vec<Node>) {
+    if let Some(element) = stack.pop() {
+        let node = Node::Element {
+            name: element.name,
+            attributes: element.attributes,
+            children: element.children,
+        };
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => root.push(node),
+        }
+    }
+}
+
+fn push_node(stack: &mut [OpenElement], root: &mut Vec<Node>, node: Node) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => root.push(node),
+    }
+}
+
+// Builds a tree out of HTML as leniently as browsers do: unclosed elements are
+// closed by their parent, stray end tags are ignored
+fn parse(html: &str) -> Vec<Node> {
+    let mut root = Vec::new();
+    let mut stack: Vec<OpenElement> = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let start = rest.find('<').unwrap_or(rest.len());
+        if start > 0 {
+            push_node(&mut stack, &mut root, Node::Text(decode(&rest[..start])));
+            rest = &rest[start..];
+            continue;
+        }
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        if let Some(end_tag) = rest.strip_prefix("</") {
+            let end = end_tag.find('>').unwrap_or(end_tag.len());
+            let name = end_tag[..end].trim().to_ascii_lowercase();
+            rest = end_tag.get(end + 1..).unwrap_or("");
+            if let Some(position) = stack.iter().rposition(|element| element.name == name) {
+                while stack.len() > position {
+                    close_element(&mut stack, &mut root);
+                }
+            }
+            continue;
+        }
+
+        let tag = &rest[1..];
+        if !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // A `<` that doesn't start a tag is just text
+            push_node(&mut stack, &mut root, Node::Text("<".to_string()));
+            rest = tag;
+            continue;
+        }
+
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        let (attributes, self_closing, after) = parse_attributes(&tag[name_end..]);
+        rest = after;
+
+        if HIDDEN_ELEMENTS.contains(&name.as_str()) {
+            if !self_closing {
+                let end_tag = format!("</{}", name);
+                // ASCII lowercasing keeps byte offsets valid in the original
+                rest = rest
+                    .to_ascii_lowercase()
+                    .find(&end_tag)
+                    .and_then(|end| rest.get(end..))
+                    .and_then(|rest| rest.find('>').map(|end| &rest[end + 1..]))
+                    .unwrap_or("");
+            }
+            continue;
+        }
+
+        // A list item ends the one before it in the same list, and any block ends an
+        // open paragraph
+        if name == "li" {
+            let open_item = stack
+                .iter()
+                .rposition(|open| matches!(open.name.as_str(), "li" | "ul" | "ol"))
+                .filter(|&position| stack[position].name == "li");
+            if let Some(position) = open_item {
+                while stack.len() > position {
+                    close_element(&mut stack, &mut root);
+                }
+            }
+        }
+        if BLOCK_ELEMENTS.contains(&name.as_str())
+            && stack.last().map_or(false, |open| open.name == "p")
+        {
+            close_element(&mut stack, &mut root);
+        }
+
+        let element = OpenElement {
+            name,
+            attributes,
+            children: Vec::new(),
+        };
+        if self_closing || VOID_ELEMENTS.contains(&element.name.as_str()) {
+            push_node(
+                &mut stack,
+                &mut root,
+                Node::Element {
+                    name: element.name,
+                    attributes: element.attributes,
+                    children: Vec::new(),
+                },
+            );
+        } else {
+            stack.push(element);
+        }
+    }
+
+    while !stack.is_empty() {
+        close_element(&mut stack, &mut root);
+    }
+    root
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                result.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            result.push(c);
+            in_whitespace = false;
+        }
+    }
+    result
+}
+
+// Backslash-escapes Markdown characters in changelog text, leaving URLs intact
+fn escape_markdown(text: &str) -> String {
+    text.split_inclusive(' ')
+        .map(|word| {
+            if word.starts_with("http://") || word.starts_with("https://") {
+                word.to_string()
+            } else {
+                word.chars().fold(String::new(), |mut escaped, c| {
+                    if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|') {
+                        escaped.push('\\');
+                    }
+                    escaped.push(c);
+                    escaped
+                })
+            }
+        })
+        .collect()
+}
+
+// Wraps text in a formatting marker, keeping surrounding spaces outside of it since
+// `** bold **` isn't bold
+fn wrap(content: String, marker: &str) -> String {
+    let trimmed = content.trim();
+    if marker.is_empty() || trimmed.is_empty() {
+        return content;
+    }
+    let leading = if content.starts_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    let trailing = if content.ends_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    format!("{}{}{}{}{}", leading, marker, trimmed, marker, trailing)
+}
+
+// Decodes `%XX` escapes, keeping anything that isn't valid UTF-8 afterwards as it was
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| text.to_string())
+}
+
+// CurseForge points external links at its `linkout` redirect, with the target encoded
+// once or twice; the target is shown instead. Site-relative links get the domain.
+fn link_target(href: &str) -> String {
+    let href = href.trim();
+    if let Some(start) = href.find("/linkout?remoteUrl=") {
+        let encoded = &href[start + "/linkout?remoteUrl=".len()..];
+        let encoded = encoded.split('&').next().unwrap_or(encoded);
+        let mut target = percent_decode(encoded);
+        let lowercase = target.to_ascii_lowercase();
+        if lowercase.starts_with("http%3a") || lowercase.starts_with("https%3a") {
+            target = percent_decode(&target);
+        }
+        return target;
+    }
+    if href.starts_with('/') && !href.starts_with("//") {
+        return format!("{}{}", CURSEFORGE_URL, href);
+    }
+    href.to_string()
+}
+
+struct Renderer {
+    format: ChangelogFormat,
+}
+
+impl Renderer {
+    // Renders a sequence of nodes as lines of text. Inline content between blocks
+    // becomes a paragraph of its own.
+    fn blocks(&self, nodes: &[Node], depth: usize) -> String {
+        let mut parts = Vec::new();
+        let mut inline = String::new();
+
+        for node in nodes {
+            if node.is_block() {
+                self.flush(&mut inline, &mut parts);
+                parts.push(self.block(node, depth));
+            } else {
+                inline.push_str(&self.inline(node));
+            }
+        }
+        self.flush(&mut inline, &mut parts);
+
+        parts.join("\n")
+    }
+
+    fn flush(&self, inline: &mut String, parts: &mut Vec<String>) {
+        let lines: Vec<&str> = inline.split('\n').map(str::trim).collect();
+        let paragraph = lines.join("\n");
+        if !paragraph.trim().is_empty() {
+            parts.push(paragraph.trim_matches('\n').to_string());
+        }
+        inline.clear();
+    }
+
+    fn block(&self, node: &Node, depth: usize) -> String {
+        let (name, attributes, children) = match node {
+            Node::Element {
+                name,
+                attributes,
+                children,
+            } => (name.as_str(), attributes, children),
+            Node::Text(text) => return collapse_whitespace(text).trim().to_string(),
+        };
+
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let heading = self.inline_children(children);
+                let heading = heading.split_whitespace().collect::<Vec<_>>().join(" ");
+                wrap(heading, self.format.bold())
+            }
+            "ul" | "ol" => self.list(name == "ol", attributes, children, depth),
+            "li" => self.list_item(&format!("{} ", self.format.bullet()), children, depth),
+            "blockquote" => {
+                let quote = self.blocks(children, depth);
+                match self.format {
+                    ChangelogFormat::PlainText => quote,
+                    ChangelogFormat::Markdown | ChangelogFormat::Slack => quote
+                        .lines()
+                        .map(|line| format!("> {}", line))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                }
+            }
+            "pre" => {
+                let mut code = String::new();
+                raw_text(children, &mut code);
+                let code = code.trim_matches('\n');
+                match self.format {
+                    ChangelogFormat::PlainText => code.to_string(),
+                    ChangelogFormat::Markdown | ChangelogFormat::Slack => {
+                        format!("```\n{}\n```", code)
+                    }
+                }
+            }
+            "hr" => "---".to_string(),
+            "table" => self.table(children),
+            // An explicitly empty paragraph is a spacer between others
+            _ => self.blocks(children, depth),
+        }
+    }
+
+    fn list(
+        &self,
+        ordered: bool,
+        attributes: &[(String, String)],
+        children: &[Node],
+        depth: usize,
+    ) -> String {
+        let mut number = attribute(attributes, "start")
+            .and_then(|start| start.trim().parse::<i64>().ok())
+            .unwrap_or(1);
+        let mut items = Vec::new();
+
+        for child in children {
+            match child {
+                Node::Element { name, children, .. } if name == "li" => {
+                    let marker = if ordered {
+                        number += 1;
+                        format!("{}. ", number - 1)
+                    } else {
+                        format!("{} ", self.format.bullet())
+                    };
+                    items.push(self.list_item(&marker, children, depth));
+                }
+                // Lists nested directly in lists, without an item around them
+                Node::Element { .. } if child.is_block() => {
+                    items.push(self.block(child, depth + 1));
+                }
+                Node::Element { .. } | Node::Text(_) => {
+                    let text = self.inline(child);
+                    if !text.trim().is_empty() {
+                        items.push(format!(
+                            "{}{} {}",
+                            "  ".repeat(depth),
+                            self.format.bullet(),
+                            text.trim()
+                        ));
+                    }
+                }
+            }
+        }
+
+        items.join("\n")
+    }
+
+    fn list_item(&self, marker: &str, children: &[Node], depth: usize) -> String {
+        let indent = "  ".repeat(depth);
+        let content = self.blocks(children, depth + 1);
+        let mut lines = content.lines();
+        let first = lines.next().unwrap_or("");
+        let mut item = format!("{}{}{}", indent, marker, first);
+        for line in lines {
+            item.push('\n');
+            // Nested lists come indented already
+            if !line.starts_with(' ') {
+                item.push_str(&indent);
+                item.push_str("  ");
+            }
+            item.push_str(line);
+        }
+        item
+    }
+
+    fn table(&self, children: &[Node]) -> String {
+        let mut rows = Vec::new();
+        collect_rows(children, &mut |cells: &[Node]| {
+            let cells: Vec<String> = cells
+                .iter()
+                .filter(|cell| matches!(cell, Node::Element { name, .. } if name == "td" || name == "th"))
+                .map(|cell| match cell {
+                    Node::Element { children, .. } => {
+                        let text = self.inline_children(children);
+                        text.split_whitespace().collect::<Vec<_>>().join(" ")
+                    }
+                    Node::Text(_) => String::new(),
+                })
+                .collect();
+            if !cells.is_empty() {
+                rows.push(cells.join(" | "));
+            }
+        });
+        rows.join("\n")
+    }
+
+    fn inline_children(&self, nodes: &[Node]) -> String {
+        nodes.iter().map(|node| self.inline(node)).collect()
+    }
+
+    fn inline(&self, node: &Node) -> String {
+        let (name, attributes, children) = match node {
+            Node::Text(text) => {
+                let text = collapse_whitespace(text);
+                return match self.format {
+                    ChangelogFormat::Markdown => escape_markdown(&text),
+                    ChangelogFormat::Slack | ChangelogFormat::PlainText => text,
+                };
+            }
+            Node::Element {
+                name,
+                attributes,
+                children,
+            } => (name.as_str(), attributes, children),
+        };
+
+        if node.is_block() {
+            return format!("\n{}\n", self.block(node, 0));
+        }
+
+        match name {
+            "br" => "\n".to_string(),
+            "strong" | "b" => wrap(self.inline_children(children), self.format.bold()),
+            "em" | "i" => wrap(self.inline_children(children), self.format.italic()),
+            "u" | "ins" => wrap(self.inline_children(children), self.format.underline()),
+            "s" | "del" | "strike" => {
+                wrap(self.inline_children(children), self.format.strikethrough())
+            }
+            "code" | "kbd" | "samp" => {
+                let mut code = String::new();
+                raw_text(children, &mut code);
+                wrap(collapse_whitespace(&code), self.format.code())
+            }
+            "a" => self.link(attributes, children),
+            "img" => attribute(attributes, "alt").unwrap_or("").to_string(),
+            _ => self.inline_children(children),
+        }
+    }
+
+    fn link(&self, attributes: &[(String, String)], children: &[Node]) -> String {
+        let text = self.inline_children(children);
+        let href = match attribute(attributes, "href") {
+            Some(href) if !href.trim().is_empty() && !href.trim().starts_with('#') => href,
+            _ => return text,
+        };
+        if href.trim().to_lowercase().starts_with("javascript:") {
+            return text;
+        }
+
+        let url = link_target(href);
+        let label = text.trim();
+        if label.is_empty() || label == url || label == escape_markdown(&url) {
+            return url;
+        }
+
+        let leading = if text.starts_with(char::is_whitespace) {
+            " "
+        } else {
+            ""
+        };
+        let trailing = if text.ends_with(char::is_whitespace) {
+            " "
+        } else {
+            ""
+        };
+        let link = match self.format {
+            ChangelogFormat::Markdown => {
+                format!(
+                    "[{}]({})",
+                    label,
+                    url.replace('(', "%28").replace(')', "%29")
+                )
+            }
+            // Slack links only work with `<url|text>`, which its escaping would break,
+            // so the URL is written out and linked automatically
+            ChangelogFormat::Slack | ChangelogFormat::PlainText => {
+                format!("{} ({})", label, url)
+            }
+        };
+        format!("{}{}{}", leading, link, trailing)
+    }
+}
+
+// The text of `nodes` exactly as written, for code
+fn raw_text(nodes: &[Node], text: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(content) => text.push_str(content),
+            Node::Element { name, .. } if name == "br" => text.push('\n'),
+            Node::Element { children, .. } => raw_text(children, text),
+        }
+    }
+}
+
+// Calls `row` with the cells of every table row, looking through `thead` and `tbody`
+fn collect_rows(nodes: &[Node], row: &mut dyn FnMut(&[Node])) {
+    for node in nodes {
+        if let Node::Element { name, children, .. } = node {
+            if name == "tr" {
+                row(children);
+            } else {
+                collect_rows(children, row);
+            }
+        }
+    }
+}
+
+/// Converts a CurseForge changelog from HTML to `format`: paragraphs and line breaks
+/// become lines, and lists, emphasis, links, headings and code keep their meaning.
+/// All text, including emoji and non-Latin scripts, is kept as it is.
+pub fn convert(html: &str, format: ChangelogFormat) -> String {
+    let text = Renderer { format }.blocks(&parse(html), 0);
+
+    // At most one empty line in a row, and none at the start or end
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() && lines.last().map_or(true, |last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().map_or(false, |last| last.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Shaped like the changelogs CurseForge returns for ARK mods
+    const CHANGELOG: &str = r#"<p><strong>Version 1.2.0</strong></p>
+<ul>
+<li>Added <em>two</em> new creatures</li>
+<li>Fixed a crash
+<ul>
+<li>on dedicated servers</li>
+<li>in single player</li>
+</ul>
+</li>
+</ul>
+<p>See <a href="https://www.curseforge.com/linkout?remoteUrl=https%253a%252f%252fexample.com%252fwiki" rel="nofollow">the wiki</a> for details.</p>"#;
+
+    #[test]
+    fn converts_to_markdown() {
+        assert_eq!(
+            convert(CHANGELOG, ChangelogFormat::Markdown),
+            "**Version 1.2.0**\n\
+             - Added *two* new creatures\n\
+             - Fixed a crash\n  \
+               - on dedicated servers\n  \
+               - in single player\n\
+             See [the wiki](https://example.com/wiki) for details."
+        );
+    }
+
+    #[test]
+    fn converts_to_slack() {
+        assert_eq!(
+            convert(CHANGELOG, ChangelogFormat::Slack),
+            "*Version 1.2.0*\n\
+             • Added _two_ new creatures\n\
+             • Fixed a crash\n  \
+               • on dedicated servers\n  \
+               • in single player\n\
+             See the wiki (https://example.com/wiki) for details."
+        );
+    }
+
+    #[test]
+    fn converts_to_plain_text() {
+        assert_eq!(
+            convert(CHANGELOG, ChangelogFormat::PlainText),
+            "Version 1.2.0\n\
+             • Added two new creatures\n\
+             • Fixed a crash\n  \
+               • on dedicated servers\n  \
+               • in single player\n\
+             See the wiki (https://example.com/wiki) for details."
+        );
+    }
+
+    #[test]
+    fn numbers_ordered_lists_from_their_start() {
+        let html = "<ol start=\"3\"><li>Third<li>Fourth</ol>";
+        assert_eq!(
+            convert(html, ChangelogFormat::Markdown),
+            "3. Third\n4. Fourth"
+        );
+    }
+
+    #[test]
+    fn decodes_entities() {
+        let html = "<p>Tom &amp; Jerry&#39;s &lt;fix&gt; &#8211; caf&#233; &#x1F996;</p>";
+        assert_eq!(
+            convert(html, ChangelogFormat::PlainText),
+            "Tom & Jerry's <fix> – café 🦖"
+        );
+    }
+
+    #[test]
+    fn escapes_markdown_in_text_but_not_in_urls() {
+        let html = "<p>Fixed *stars* and_underscores at https://example.com/a_b*c</p>";
+        assert_eq!(
+            convert(html, ChangelogFormat::Markdown),
+            "Fixed \\*stars\\* and\\_underscores at https://example.com/a_b*c"
+        );
+    }
+
+    #[test]
+    fn drops_hidden_elements() {
+        let html = "<style>p { color: red; }</style><p>Visible</p>\
+                    <SCRIPT>document.write(\"<p>Hidden</p>\")</SCRIPT>\
+                    <!-- <p>Commented</p> --><p>After</p>";
+        assert_eq!(convert(html, ChangelogFormat::PlainText), "Visible\nAfter");
+    }
+
+    #[test]
+    fn decodes_linkout_targets() {
+        assert_eq!(
+            link_target("/linkout?remoteUrl=https%3a%2f%2fgithub.com%2fowner%2frepo"),
+            "https://github.com/owner/repo"
+        );
+        assert_eq!(
+            link_target(
+                "https://www.curseforge.com/linkout?remoteUrl=https%253a%252f%252fdiscord.gg%252fabc&foo=1"
+            ),
+            "https://discord.gg/abc"
+        );
+        assert_eq!(
+            link_target("/ark-survival-ascended/mods/example"),
+            "https://www.curseforge.com/ark-survival-ascended/mods/example"
+        );
+        assert_eq!(
+            link_target("//cdn.example.com/a.png"),
+            "//cdn.example.com/a.png"
+        );
+    }
+
+    #[test]
+    fn keeps_link_text_that_is_the_url() {
+        let html = "<a href=\"https://example.com\">https://example.com</a> \
+                    <a href=\"javascript:alert(1)\">click</a>";
+        assert_eq!(
+            convert(html, ChangelogFormat::Markdown),
+            "https://example.com click"
+        );
+    }
+
+    #[test]
+    fn parses_attributes_with_unicode() {
+        let (attributes, self_closing, rest) =
+            parse_attributes(" TITLE=\"日本語\" alt=恐竜 data-x='🦖' / ü>après");
+        assert_eq!(
+            attributes,
+            vec![
+                ("title".to_string(), "日本語".to_string()),
+                ("alt".to_string(), "恐竜".to_string()),
+                ("data-x".to_string(), "🦖".to_string()),
+                ("ü".to_string(), String::new()),
+            ]
+        );
+        assert!(!self_closing);
+        assert_eq!(rest, "après");
+
+        let (attributes, self_closing, rest) = parse_attributes(" alt=\"Ünïcödé\"/>");
+        assert_eq!(attributes, vec![("alt".to_string(), "Ünïcödé".to_string())]);
+        assert!(self_closing);
+        assert_eq!(rest, "");
+
+        // An unterminated quote runs to the end instead of slicing inside a character
+        let (attributes, _, rest) = parse_attributes(" alt=\"é");
+        assert_eq!(attributes, vec![("alt".to_string(), "é".to_string())]);
+        assert_eq!(rest, "");
+    }
+
+    #[test]
+    fn percent_decodes_unicode() {
+        assert_eq!(percent_decode("caf%C3%A9%20%F0%9F%A6%96"), "café 🦖");
+        assert_eq!(percent_decode("déjà%20vu"), "déjà vu");
+        // Escapes cut short or next to multibyte characters stay as they are
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%é%zz"), "%é%zz");
+        // Escapes that don't decode to UTF-8 leave the whole text untouched
+        assert_eq!(percent_decode("%FF%FEabc"), "%FF%FEabc");
+    }
+
+    #[test]
+    fn keeps_unicode_text_in_every_format() {
+        let html = "<p><b>Mise à jour</b> – 恐竜を追加 🦖</p>";
+        assert_eq!(
+            convert(html, ChangelogFormat::Markdown),
+            "**Mise à jour** – 恐竜を追加 🦖"
+        );
+        assert_eq!(
+            convert(html, ChangelogFormat::Slack),
+            "*Mise à jour* – 恐竜を追加 🦖"
+        );
+        assert_eq!(
+            convert(html, ChangelogFormat::PlainText),
+            "Mise à jour – 恐竜を追加 🦖"
+        );
+    }
+}
@@ -0,0 +1,744 @@
+use super::{
+    deserialize_optional_int, non_empty, replace_id_placeholders, replace_template_variables,
+    truncate_text, DigestMessage, ModUpdateData, UpdateMessages,
+};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+pub const FIELD_VALUE_LIMIT: usize = 1024; // Discord's field value character limit
+
+// Per-message limits Discord enforces on webhook posts
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+const EMBED_TOTAL_LIMIT: usize = 6000;
+const DESCRIPTION_LIMIT: usize = 4096;
+const CONTENT_LIMIT: usize = 2000;
+const TITLE_LIMIT: usize = 256;
+const MAX_FIELDS: usize = 25;
+const FIELD_NAME_LIMIT: usize = 256;
+const FOOTER_TEXT_LIMIT: usize = 2048;
+const AUTHOR_NAME_LIMIT: usize = 256;
+
+const MAX_DIGEST_WINDOW_MINUTES: i64 = 24 * 60;
+const MAX_EDIT_WINDOW_MINUTES: i64 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestStyle {
+    /// Each update keeps its own embed, up to ten per message
+    Embeds,
+    /// All updates are listed in a single embed, one line per mod
+    List,
+}
+
+impl Default for DigestStyle {
+    fn default() -> Self {
+        DigestStyle::Embeds
+    }
+}
+
+/// The `config` of a Discord webhook. Webhooks created before digests existed have
+/// no config at all, which means every update is posted on its own.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DiscordConfig {
+    /// Minutes to collect updates for before posting them together; off when empty or 0
+    #[serde(deserialize_with = "deserialize_optional_int")]
+    pub digest_window: Option<i64>,
+    pub digest_style: DigestStyle,
+    /// Minutes after posting during which a mod's message is edited instead of a new
+    /// one being posted when the mod updates again; off when empty or 0
+    #[serde(deserialize_with = "deserialize_optional_int")]
+    pub edit_window: Option<i64>,
+}
+
+impl DiscordConfig {
+    pub fn from_webhook(webhook: &Webhook) -> Result<Self, TrackerError> {
+        if webhook.config.is_null() {
+            return Ok(DiscordConfig::default());
+        }
+
+        let config: DiscordConfig = serde_json::from_value(webhook.config.clone())
+            .map_err(|e| TrackerError::InvalidInput(format!("Invalid Discord settings: {}", e)))?;
+        if let Some(window) = config.digest_window {
+            if !(0..=MAX_DIGEST_WINDOW_MINUTES).contains(&window) {
+                return Err(TrackerError::InvalidInput(
+                    "The digest window must be between 0 and 1440 minutes".into(),
+                ));
+            }
+        }
+        if let Some(window) = config.edit_window {
+            if !(0..=MAX_EDIT_WINDOW_MINUTES).contains(&window) {
+                return Err(TrackerError::InvalidInput(
+                    "The edit window must be between 0 and 1440 minutes".into(),
+                ));
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// How long after posting a mod's message is edited on the next update of that
+/// mod, or `None` when every update gets a new message.
+pub fn edit_window(webhook: &Webhook) -> Option<chrono::Duration> {
+    if webhook.kind != WebhookKind::Discord {
+        return None;
+    }
+    DiscordConfig::from_webhook(webhook)
+        .ok()
+        .and_then(|config| config.edit_window)
+        .filter(|minutes| *minutes > 0)
+        .map(chrono::Duration::minutes)
+}
+
+/// The webhook URL with `wait=true`, which makes Discord answer with the posted
+/// message instead of an empty 204.
+pub fn wait_url(url: &str) -> String {
+    if url.contains("wait=") {
+        url.to_string()
+    } else if url.contains('?') {
+        format!("{}&wait=true", url)
+    } else {
+        format!("{}?wait=true", url)
+    }
+}
+
+/// The URL of a message the webhook posted, keeping a `thread_id` query if any.
+pub fn message_url(url: &str, message_id: &str) -> String {
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let query: Vec<&str> = query
+        .split('&')
+        .filter(|param| !param.is_empty() && !param.starts_with("wait="))
+        .collect();
+    let mut message_url = format!("{}/messages/{}", base.trim_end_matches('/'), message_id);
+    if !query.is_empty() {
+        message_url.push('?');
+        message_url.push_str(&query.join("&"));
+    }
+    message_url
+}
+
+/// Reads the message ID from Discord's answer to a `wait=true` post.
+pub fn message_id(response_body: &str) -> Option<String> {
+    let message: Value = serde_json::from_str(response_body).ok()?;
+    message["id"].as_str().map(str::to_string)
+}
+
+/// Turns a post payload into the body of a message edit. Edits can't change the
+/// webhook's name or avatar, and whatever the new message leaves out is cleared.
+pub fn edit_payload(payload: &Value) -> Value {
+    let mut body = payload.clone();
+    if let Some(body) = body.as_object_mut() {
+        body.remove("username");
+        body.remove("avatar_url");
+        body.entry("content").or_insert(Value::Null);
+        body.entry("embeds").or_insert_with(|| json!([]));
+    }
+    body
+}
+
+/// How long updates are collected before this webhook posts them as one digest,
+/// or `None` when every update is posted right away.
+pub fn digest_window(webhook: &Webhook) -> Option<chrono::Duration> {
+    if webhook.kind != WebhookKind::Discord {
+        return None;
+    }
+    DiscordConfig::from_webhook(webhook)
+        .ok()
+        .and_then(|config| config.digest_window)
+        .filter(|minutes| *minutes > 0)
+        .map(chrono::Duration::minutes)
+}
+
+/// A digest is due once its oldest queued update has waited for the whole window.
+pub fn digest_due(webhook: &Webhook, oldest_queued_at: DateTime<Utc>) -> bool {
+    digest_window(webhook).map_or(false, |window| oldest_queued_at + window <= Utc::now())
+}
+
+fn is_snowflake(id: &str) -> bool {
+    id.parse::<u64>().is_ok()
+}
+
+/// Renders `{everyone}`, `{here}`, `{&role}` and `{#channel}` as Discord mentions.
+pub fn replace_mentions(text: &str) -> String {
+    let result = text
+        .replace("{everyone}", "@everyone")
+        .replace("{here}", "@here");
+    let result = replace_id_placeholders(&result, "&", is_snowflake, &|id| format!("<@&{}>", id));
+    replace_id_placeholders(&result, "#", is_snowflake, &|id| format!("<#{}>", id))
+}
+
+fn base_payload(webhook: &Webhook) -> Value {
+    let mut payload = json!({
+        "username": non_empty(&webhook.username).unwrap_or("Mod Tracker"),
+    });
+
+    if let Some(avatar_url) = non_empty(&webhook.avatar_url) {
+        payload["avatar_url"] = json!(avatar_url);
+    }
+
+    payload
+}
+
+pub fn build_test_payload(webhook: &Webhook) -> Value {
+    let mut payload = base_payload(webhook);
+    payload["embeds"] = json!([{
+        "title": "🧪 Test Message",
+        "description": "This is a test message from Arti's CurseForge Mod Tracker!",
+        "color": 5814783,
+        "footer": {
+            "text": "Test completed successfully"
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }]);
+    payload
+}
+
+pub fn build_update_payload(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let render = |text: &str| replace_template_variables(text, data, WebhookKind::Discord);
+
+    let mut payload = base_payload(webhook);
+
+    if !template.use_embed {
+        let content = template
+            .content
+            .as_deref()
+            .unwrap_or("🔄 Mod Update Available!");
+        payload["content"] = json!(render(content));
+        return Ok(payload);
+    }
+
+    let mut embed = json!({
+        "title": render(&template.title),
+        "color": template.color,
+        "fields": serde_json::from_str::<Vec<Value>>(&template.embed_fields)?
+            .iter()
+            .map(|field| {
+                let mut new_field = field.clone();
+                new_field["name"] = json!(render(field["name"].as_str().unwrap_or("")));
+                new_field["value"] = json!(render(field["value"].as_str().unwrap_or("")));
+                new_field
+            })
+            // Discord rejects empty field values, which templates produce for fields
+            // they hide behind a condition
+            .filter(|field| !field["value"].as_str().unwrap_or("").trim().is_empty())
+            .collect::<Vec<_>>()
+    });
+
+    if let Some(author_name) = non_empty(&template.author_name) {
+        let mut author = json!({ "name": render(author_name) });
+        if let Some(icon_url) = non_empty(&template.author_icon_url) {
+            author["icon_url"] = json!(render(icon_url));
+        }
+        embed["author"] = author;
+    }
+
+    // Add thumbnail if enabled and URL is available
+    if template.use_thumbnail {
+        if let Some(url) = &data.logo_url {
+            embed["thumbnail"] = json!({ "url": url });
+        }
+    }
+
+    let footer_text = non_empty(&template.footer_text);
+    let footer_icon_url = non_empty(&template.footer_icon_url);
+    if footer_text.is_some() || footer_icon_url.is_some() || template.include_timestamp {
+        let mut footer = json!({});
+        if let Some(text) = footer_text {
+            footer["text"] = json!(render(text));
+        }
+        if let Some(icon_url) = footer_icon_url {
+            footer["icon_url"] = json!(render(icon_url));
+        }
+        embed["footer"] = footer;
+    }
+
+    if template.include_timestamp {
+        embed["timestamp"] = json!(chrono::Utc::now().to_rfc3339());
+    }
+
+    payload["embeds"] = json!([embed]);
+    Ok(payload)
+}
+
+// Discord counts the title, description, field names and values, footer text and
+// author name towards an embed's total
+fn embed_text_length(embed: &Value) -> usize {
+    let chars = |value: &Value| value.as_str().map_or(0, |text| text.chars().count());
+    let fields = embed["fields"].as_array().map_or(0, |fields| {
+        fields
+            .iter()
+            .map(|field| chars(&field["name"]) + chars(&field["value"]))
+            .sum()
+    });
+    chars(&embed["title"])
+        + chars(&embed["description"])
+        + chars(&embed["footer"]["text"])
+        + chars(&embed["author"]["name"])
+        + fields
+}
+
+// Shortens the text at `value` to `limit` characters, noting it in `truncations`
+fn shorten(value: Option<&mut Value>, limit: usize, label: &str, truncations: &mut Vec<String>) {
+    let value = match value {
+        Some(value) => value,
+        None => return,
+    };
+    if let Some(text) = value.as_str() {
+        let length = text.chars().count();
+        if length > limit {
+            *value = json!(truncate_text(text, limit));
+            truncations.push(format!(
+                "{} was shortened from {} to {} characters",
+                label, length, limit
+            ));
+        }
+    }
+}
+
+// Splits `text` into pieces of at most `limit` characters, `first_limit` for the
+// first one, breaking at line ends or spaces where possible
+fn split_text(text: &str, first_limit: usize, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    let mut max_chars = first_limit;
+
+    while rest.chars().count() > max_chars {
+        let cut = rest
+            .char_indices()
+            .nth(max_chars)
+            .map(|(index, _)| index)
+            .unwrap_or(rest.len());
+        let cut = rest[..cut]
+            .rfind('\n')
+            .or_else(|| rest[..cut].rfind(' '))
+            .filter(|&index| index > 0)
+            .unwrap_or(cut);
+        pieces.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start();
+        max_chars = limit;
+    }
+
+    if !rest.is_empty() || pieces.is_empty() {
+        pieces.push(rest.to_string());
+    }
+    pieces
+}
+
+// Brings one embed within Discord's limits. Parts that are too long are shortened;
+// a long description and fields beyond the 25 or the character total that fit
+// continue in further embeds of the same color.
+fn fit_embed(mut embed: Value, truncations: &mut Vec<String>) -> Vec<Value> {
+    shorten(
+        embed.get_mut("title"),
+        TITLE_LIMIT,
+        "The title",
+        truncations,
+    );
+    shorten(
+        embed.pointer_mut("/author/name"),
+        AUTHOR_NAME_LIMIT,
+        "The author name",
+        truncations,
+    );
+    shorten(
+        embed.pointer_mut("/footer/text"),
+        FOOTER_TEXT_LIMIT,
+        "The footer text",
+        truncations,
+    );
+
+    // Fields are put back below, once it is clear which embed they fit in
+    let mut fields = match embed
+        .as_object_mut()
+        .and_then(|object| object.remove("fields"))
+    {
+        Some(Value::Array(fields)) => fields,
+        _ => Vec::new(),
+    };
+    for (index, field) in fields.iter_mut().enumerate() {
+        // Discord rejects empty field names, a zero-width space stands in for them
+        if field["name"].as_str().unwrap_or("").trim().is_empty() {
+            field["name"] = json!("\u{200b}");
+        }
+        shorten(
+            field.get_mut("name"),
+            FIELD_NAME_LIMIT,
+            &format!("The name of field {}", index + 1),
+            truncations,
+        );
+        shorten(
+            field.get_mut("value"),
+            FIELD_VALUE_LIMIT,
+            &format!("The value of field {}", index + 1),
+            truncations,
+        );
+    }
+
+    let color = embed["color"].clone();
+    let mut embeds = vec![embed];
+
+    // The first piece of the description shares the embed's total with its title,
+    // author and footer
+    let description = embeds[0]["description"].as_str().map(str::to_string);
+    if let Some(description) = description {
+        let others = embed_text_length(&embeds[0]) - description.chars().count();
+        let first_limit = DESCRIPTION_LIMIT.min(EMBED_TOTAL_LIMIT - others);
+        let mut pieces = split_text(&description, first_limit, DESCRIPTION_LIMIT).into_iter();
+        embeds[0]["description"] = json!(pieces.next().unwrap_or_default());
+        for piece in pieces {
+            embeds.push(json!({ "color": color, "description": piece }));
+        }
+        if embeds.len() > 1 {
+            truncations.push(format!(
+                "The description was split across {} embeds",
+                embeds.len()
+            ));
+        }
+    }
+
+    // Fields fill the last embed, then continue in new ones
+    let embeds_before_fields = embeds.len();
+    for field in fields {
+        let last = embeds.len() - 1;
+        let field_count = embeds[last]["fields"].as_array().map_or(0, Vec::len);
+        let field_length = embed_text_length(&json!({ "fields": [&field] }));
+        if field_count == MAX_FIELDS
+            || embed_text_length(&embeds[last]) + field_length > EMBED_TOTAL_LIMIT
+        {
+            embeds.push(json!({ "color": color, "fields": [] }));
+        }
+        let last = embeds.len() - 1;
+        match embeds[last]["fields"].as_array_mut() {
+            Some(last_fields) => last_fields.push(field),
+            None => embeds[last]["fields"] = json!([field]),
+        }
+    }
+    if embeds.len() > embeds_before_fields {
+        truncations.push(format!(
+            "The fields were split across {} embeds",
+            embeds.len() - embeds_before_fields + 1
+        ));
+    }
+
+    embeds
+}
+
+/// Brings a message built for Discord within its limits: every text is shortened
+/// on character boundaries, and content, descriptions or fields too long for one
+/// message continue in follow-up messages.
+pub fn fit_to_limits(payload: Value) -> UpdateMessages {
+    let mut truncations = Vec::new();
+
+    let mut base = payload.clone();
+    if let Some(object) = base.as_object_mut() {
+        object.remove("content");
+        object.remove("embeds");
+    }
+
+    let mut embeds = Vec::new();
+    if let Some(payload_embeds) = payload["embeds"].as_array() {
+        for embed in payload_embeds {
+            embeds.extend(fit_embed(embed.clone(), &mut truncations));
+        }
+    }
+
+    let mut contents = match payload["content"].as_str() {
+        Some(content) if content.chars().count() > CONTENT_LIMIT => {
+            let pieces = split_text(content, CONTENT_LIMIT, CONTENT_LIMIT);
+            truncations.push(format!(
+                "The content was split across {} messages",
+                pieces.len()
+            ));
+            pieces
+        }
+        Some(content) => vec![content.to_string()],
+        None => Vec::new(),
+    };
+
+    // The content comes first, so the embeds are attached to its last piece
+    let mut payloads = Vec::new();
+    let mut last_content = contents.pop();
+    for content in contents {
+        let mut message = base.clone();
+        message["content"] = json!(content);
+        payloads.push(message);
+    }
+
+    let groups = group_embeds(embeds.into_iter().map(|embed| (embed, ())).collect());
+    for group in groups {
+        let mut message = base.clone();
+        if let Some(content) = last_content.take() {
+            message["content"] = json!(content);
+        }
+        message["embeds"] = json!(group
+            .into_iter()
+            .map(|(embed, _)| embed)
+            .collect::<Vec<_>>());
+        payloads.push(message);
+    }
+    if let Some(content) = last_content {
+        let mut message = base;
+        message["content"] = json!(content);
+        payloads.push(message);
+    }
+
+    if payloads.is_empty() {
+        payloads.push(payload);
+    } else if payloads.len() > 1 {
+        truncations.push(format!(
+            "The notification was split into {} messages",
+            payloads.len()
+        ));
+    }
+
+    UpdateMessages {
+        payloads,
+        truncations,
+    }
+}
+
+// Groups embeds into as few messages as Discord accepts: at most ten embeds and
+// 6000 characters of embed text per message. Each embed keeps what it comes with.
+fn group_embeds<T>(embeds: Vec<(Value, T)>) -> Vec<Vec<(Value, T)>> {
+    let mut groups = Vec::new();
+    let mut group = Vec::new();
+    let mut group_length = 0;
+
+    for (embed, extra) in embeds {
+        let length = embed_text_length(&embed);
+        if !group.is_empty()
+            && (group.len() == MAX_EMBEDS_PER_MESSAGE || group_length + length > EMBED_TOTAL_LIMIT)
+        {
+            groups.push(std::mem::take(&mut group));
+            group_length = 0;
+        }
+        group_length += length;
+        group.push((embed, extra));
+    }
+
+    if !group.is_empty() {
+        groups.push(group);
+    }
+    groups
+}
+
+// Fills messages with as many embeds as Discord accepts in one post. Each embed
+// comes with the indices of the updates it shows.
+fn pack_embeds(webhook: &Webhook, embeds: Vec<(Value, Vec<usize>)>) -> Vec<DigestMessage> {
+    group_embeds(embeds)
+        .into_iter()
+        .map(|group| {
+            let (embeds, updates): (Vec<Value>, Vec<Vec<usize>>) = group.into_iter().unzip();
+            let mut payload = base_payload(webhook);
+            payload["embeds"] = json!(embeds);
+            DigestMessage {
+                payload,
+                updates: updates.concat(),
+                truncations: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+// Joins plain-text messages into as few posts as fit Discord's content limit
+fn pack_contents(webhook: &Webhook, contents: Vec<String>) -> Vec<DigestMessage> {
+    let mut messages: Vec<DigestMessage> = Vec::new();
+    let mut content = String::new();
+    let mut updates = Vec::new();
+
+    for (index, text) in contents.into_iter().enumerate() {
+        let text = truncate_text(&text, CONTENT_LIMIT);
+        if !content.is_empty() && content.chars().count() + 2 + text.chars().count() > CONTENT_LIMIT
+        {
+            let mut payload = base_payload(webhook);
+            payload["content"] = json!(std::mem::take(&mut content));
+            messages.push(DigestMessage {
+                payload,
+                updates: std::mem::take(&mut updates),
+                truncations: Vec::new(),
+            });
+        }
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&text);
+        updates.push(index);
+    }
+
+    if !content.is_empty() {
+        let mut payload = base_payload(webhook);
+        payload["content"] = json!(content);
+        messages.push(DigestMessage {
+            payload,
+            updates,
+            truncations: Vec::new(),
+        });
+    }
+
+    messages
+}
+
+// One line per update, split over as many embeds as the description limit needs
+fn list_embeds(template: &WebhookTemplate, updates: &[ModUpdateData]) -> Vec<(Value, Vec<usize>)> {
+    let mut embeds = Vec::new();
+    let mut description = String::new();
+    let mut covered = Vec::new();
+
+    let new_embed = |description: String, first: bool| {
+        let mut embed = json!({
+            "description": description,
+            "color": template.color,
+        });
+        if first {
+            embed["title"] = json!(format!(
+                "📦 {} Mod {}",
+                updates.len(),
+                if updates.len() == 1 {
+                    "Update"
+                } else {
+                    "Updates"
+                }
+            ));
+        }
+        if template.include_timestamp {
+            embed["timestamp"] = json!(Utc::now().to_rfc3339());
+        }
+        embed
+    };
+
+    for (index, update) in updates.iter().enumerate() {
+        let name = match non_empty(&update.page_url) {
+            Some(url) => format!("[**{}**]({})", update.mod_name, url),
+            None => format!("**{}**", update.mod_name),
+        };
+        let file = update
+            .file_display_name
+            .as_deref()
+            .unwrap_or(&update.latest_file_name);
+        let line = truncate_text(&format!("• {} — {}", name, file), DESCRIPTION_LIMIT);
+
+        if !description.is_empty()
+            && description.chars().count() + 1 + line.chars().count() > DESCRIPTION_LIMIT
+        {
+            embeds.push((
+                new_embed(std::mem::take(&mut description), embeds.is_empty()),
+                std::mem::take(&mut covered),
+            ));
+        }
+        if !description.is_empty() {
+            description.push('\n');
+        }
+        description.push_str(&line);
+        covered.push(index);
+    }
+
+    if !description.is_empty() {
+        embeds.push((new_embed(description, embeds.is_empty()), covered));
+    }
+
+    embeds
+}
+
+/// Renders the updates collected for a digest as one or more messages, split so
+/// each stays within Discord's embed count and character limits.
+pub fn build_digest_messages(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    updates: &[ModUpdateData],
+) -> Result<Vec<DigestMessage>, TrackerError> {
+    let config = DiscordConfig::from_webhook(webhook)?;
+
+    if config.digest_style == DigestStyle::List {
+        return Ok(pack_embeds(webhook, list_embeds(template, updates)));
+    }
+
+    let mut payloads = Vec::new();
+    for update in updates {
+        payloads.push(build_update_payload(webhook, template, update)?);
+    }
+
+    // What had to be cut from each update, reported with the message that completes it
+    let mut notes: Vec<Vec<String>> = vec![Vec::new(); updates.len()];
+
+    let mut messages = if !template.use_embed {
+        let contents = payloads
+            .iter()
+            .enumerate()
+            .map(|(index, payload)| {
+                let mut content = payload["content"].clone();
+                shorten(
+                    Some(&mut content),
+                    CONTENT_LIMIT,
+                    "The content",
+                    &mut notes[index],
+                );
+                content.as_str().unwrap_or_default().to_string()
+            })
+            .collect();
+        pack_contents(webhook, contents)
+    } else {
+        let mut embeds = Vec::new();
+        for (index, payload) in payloads.into_iter().enumerate() {
+            let fitted = fit_embed(payload["embeds"][0].clone(), &mut notes[index]);
+            let last = fitted.len() - 1;
+            // The update only counts as sent once its last embed is
+            embeds.extend(fitted.into_iter().enumerate().map(|(position, embed)| {
+                (
+                    embed,
+                    if position == last {
+                        vec![index]
+                    } else {
+                        Vec::new()
+                    },
+                )
+            }));
+        }
+        pack_embeds(webhook, embeds)
+    };
+
+    for message in &mut messages {
+        message.truncations = message
+            .updates
+            .iter()
+            .flat_map(|&index| {
+                notes[index]
+                    .iter()
+                    .map(move |note| format!("{}: {}", updates[index].mod_name, note))
+            })
+            .collect();
+    }
+
+    Ok(messages)
+}
+
+/// Turns a failed Discord response into a typed error, keeping Discord's
+/// `retry_after` hint for rate limits.
+pub async fn error_from_response(response: reqwest::Response) -> TrackerError {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let mut error = TrackerError::rate_limited("Discord", response.headers());
+        if let TrackerError::RateLimited { retry_after, .. } = &mut error {
+            let body: Option<Value> = response.json().await.ok();
+            if let Some(seconds) = body.as_ref().and_then(|b| b["retry_after"].as_f64()) {
+                *retry_after = Some(seconds);
+            }
+        }
+        println!("Discord rate limited the webhook: {}", error);
+        return error;
+    }
+
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    println!("Discord API error: {}", error_text);
+    TrackerError::webhook_delivery("Discord", status, error_text)
+}
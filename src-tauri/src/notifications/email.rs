@@ -0,0 +1,608 @@
+use super::{
+    deserialize_optional_int, escape_html, fields_show_changelog, non_empty,
+    replace_template_variables, truncate_text, DeliveryResponse, ModUpdateData,
+};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use chrono::{DateTime, Local, TimeZone, Timelike, Utc};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::response::Code;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+pub const CHANGELOG_LIMIT: usize = 4000;
+
+const DEFAULT_DIGEST_HOUR: u32 = 8;
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsMode {
+    /// Plain connection upgraded with STARTTLS, usually on port 587
+    Starttls,
+    /// TLS from the first byte (SMTPS), usually on port 465
+    Tls,
+    /// No encryption at all, meant for local SMTP sinks such as MailHog
+    None,
+}
+
+impl Default for TlsMode {
+    fn default() -> Self {
+        TlsMode::Starttls
+    }
+}
+
+impl TlsMode {
+    fn default_port(&self) -> u16 {
+        match self {
+            TlsMode::Starttls => 587,
+            TlsMode::Tls => 465,
+            TlsMode::None => 25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestMode {
+    /// Every update is mailed as soon as it is found
+    Off,
+    /// Updates are collected and mailed together once a day
+    Daily,
+}
+
+impl Default for DigestMode {
+    fn default() -> Self {
+        DigestMode::Off
+    }
+}
+
+/// The `config` of an email webhook. The webhook URL is the SMTP server's host name.
+#[derive(Debug, Deserialize)]
+pub struct EmailConfig {
+    #[serde(default, deserialize_with = "deserialize_optional_int")]
+    pub port: Option<i64>,
+    #[serde(default)]
+    pub tls_mode: TlsMode,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    /// One or more recipients, separated by commas
+    pub to: String,
+    #[serde(default)]
+    pub digest: DigestMode,
+    /// Local hour of the day after which the daily digest goes out
+    #[serde(default, deserialize_with = "deserialize_optional_int")]
+    pub digest_hour: Option<i64>,
+}
+
+impl EmailConfig {
+    pub fn from_webhook(webhook: &Webhook) -> Result<Self, TrackerError> {
+        let missing = || {
+            TrackerError::InvalidInput(
+                "Email webhooks need a sender and at least one recipient".into(),
+            )
+        };
+        let config: EmailConfig =
+            serde_json::from_value(webhook.config.clone()).map_err(|_| missing())?;
+        if config.from.trim().is_empty() || config.to.trim().is_empty() {
+            return Err(missing());
+        }
+
+        if webhook.url.trim().is_empty() {
+            return Err(TrackerError::InvalidInput(
+                "Email webhooks need an SMTP server".into(),
+            ));
+        }
+        if let Some(port) = config.port {
+            if !(1..=65535).contains(&port) {
+                return Err(TrackerError::InvalidInput(
+                    "SMTP ports range from 1 to 65535".into(),
+                ));
+            }
+        }
+        if let Some(hour) = config.digest_hour {
+            if !(0..=23).contains(&hour) {
+                return Err(TrackerError::InvalidInput(
+                    "The digest hour must be between 0 and 23".into(),
+                ));
+            }
+        }
+
+        config.sender()?;
+        // A list of nothing but separators names no one
+        if config.recipients()?.is_empty() {
+            return Err(missing());
+        }
+        Ok(config)
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+            .map(|port| port as u16)
+            .unwrap_or_else(|| self.tls_mode.default_port())
+    }
+
+    fn digest_hour(&self) -> u32 {
+        self.digest_hour
+            .map(|hour| hour as u32)
+            .unwrap_or(DEFAULT_DIGEST_HOUR)
+    }
+
+    fn sender(&self) -> Result<Mailbox, TrackerError> {
+        parse_mailbox(&self.from)
+    }
+
+    fn recipients(&self) -> Result<Vec<Mailbox>, TrackerError> {
+        self.to
+            .split(&[',', ';'][..])
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(parse_mailbox)
+            .collect()
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox, TrackerError> {
+    address.trim().parse().map_err(|_| {
+        TrackerError::InvalidInput(format!("\"{}\" is not a valid email address", address))
+    })
+}
+
+/// Whether updates for this webhook are held back for a daily digest instead of
+/// being mailed one by one.
+pub fn uses_daily_digest(webhook: &Webhook) -> bool {
+    webhook.kind == WebhookKind::Email
+        && EmailConfig::from_webhook(webhook)
+            .map_or(false, |config| config.digest == DigestMode::Daily)
+}
+
+/// A digest is due once the configured local hour has passed and none has been
+/// sent yet today. After a failed digest it waits for `retry_at` instead.
+pub fn digest_due(
+    webhook: &Webhook,
+    last_sent: Option<DateTime<Utc>>,
+    retry_at: Option<DateTime<Utc>>,
+) -> bool {
+    let config = match EmailConfig::from_webhook(webhook) {
+        Ok(config) if config.digest == DigestMode::Daily => config,
+        _ => return false,
+    };
+
+    digest_due_at(config.digest_hour(), last_sent, retry_at, Local::now())
+}
+
+// The decision behind `digest_due`, for any time in any time zone
+fn digest_due_at<Tz: TimeZone>(
+    digest_hour: u32,
+    last_sent: Option<DateTime<Utc>>,
+    retry_at: Option<DateTime<Utc>>,
+    now: DateTime<Tz>,
+) -> bool {
+    // The last digest time only moves on success, so a failed digest would count
+    // as due on every check without the retry time
+    if retry_at.map_or(false, |retry_at| retry_at > now.with_timezone(&Utc)) {
+        return false;
+    }
+    if now.hour() < digest_hour {
+        return false;
+    }
+    last_sent.map_or(true, |sent| {
+        sent.with_timezone(&now.timezone()).date_naive() < now.date_naive()
+    })
+}
+
+fn html_text(text: &str) -> String {
+    escape_html(text).replace('\n', "<br>")
+}
+
+fn html_document(body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><body style=\"font-family:Arial,Helvetica,sans-serif;color:#222222;\">{}</body></html>",
+        body
+    )
+}
+
+fn message(subject: String, text: String, html: String) -> Value {
+    json!({
+        "subject": subject,
+        "text": text,
+        "html": html_document(&html),
+    })
+}
+
+/// One rendered update: the email subject plus its HTML and plain-text bodies.
+struct RenderedUpdate {
+    title: String,
+    html: String,
+    text: String,
+}
+
+// Renders the template as an HTML card, with the embed color as its left border,
+// and as the matching plain text
+fn render_update(
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<RenderedUpdate, TrackerError> {
+    let render = |text: &str| replace_template_variables(text, data, WebhookKind::Email);
+    let title = render(&template.title);
+
+    if !template.use_embed {
+        let content = render(
+            template
+                .content
+                .as_deref()
+                .unwrap_or("🔄 Mod Update Available!"),
+        );
+        return Ok(RenderedUpdate {
+            title,
+            html: format!("<p>{}</p>", html_text(&content)),
+            text: content,
+        });
+    }
+
+    let mut html = vec![format!(
+        "<div style=\"border-left:4px solid #{:06x};padding:8px 16px;margin:16px 0;\">",
+        template.color & 0xFF_FF_FF
+    )];
+    let mut text = vec![title.clone()];
+
+    if template.use_thumbnail {
+        if let Some(logo_url) = non_empty(&data.logo_url) {
+            html.push(format!(
+                "<img src=\"{}\" alt=\"\" width=\"64\" height=\"64\" style=\"float:right;margin:0 0 8px 16px;border-radius:8px;\">",
+                escape_html(logo_url).replace('"', "&quot;")
+            ));
+        }
+    }
+
+    let heading = match non_empty(&data.page_url) {
+        Some(page_url) => format!(
+            "<a href=\"{}\" style=\"color:inherit;\">{}</a>",
+            escape_html(page_url).replace('"', "&quot;"),
+            html_text(&title)
+        ),
+        None => html_text(&title),
+    };
+    html.push(format!(
+        "<h2 style=\"margin:0 0 4px;font-size:18px;\">{}</h2>",
+        heading
+    ));
+
+    if let Some(author_name) = non_empty(&template.author_name) {
+        let author = render(author_name);
+        html.push(format!(
+            "<p style=\"margin:0 0 12px;color:#666666;\">{}</p>",
+            html_text(&author)
+        ));
+        text.push(author);
+    }
+
+    let mut rows = Vec::new();
+    for field in serde_json::from_str::<Vec<Value>>(&template.embed_fields)? {
+        let name = render(field["name"].as_str().unwrap_or(""));
+        let value = render(field["value"].as_str().unwrap_or(""));
+        if value.trim().is_empty() {
+            continue;
+        }
+        rows.push(format!(
+            "<tr><td style=\"padding:4px 12px 4px 0;font-weight:bold;vertical-align:top;\">{}</td><td style=\"padding:4px 0;\">{}</td></tr>",
+            html_text(&name),
+            html_text(&value)
+        ));
+        text.push(format!("{}: {}", name, value));
+    }
+    if !rows.is_empty() {
+        html.push(format!(
+            "<table style=\"border-collapse:collapse;\">{}</table>",
+            rows.concat()
+        ));
+    }
+
+    // Only add the changelog on its own when no field already shows it
+    if let Some(changelog) = data.changelog_for(WebhookKind::Email).as_deref() {
+        if !fields_show_changelog(template) && !changelog.trim().is_empty() {
+            let changelog = truncate_text(changelog.trim(), CHANGELOG_LIMIT);
+            html.push(format!(
+                "<h3 style=\"margin:16px 0 4px;font-size:15px;\">Changelog</h3><p style=\"margin:0;\">{}</p>",
+                html_text(&changelog)
+            ));
+            text.push(format!("\nChangelog\n{}", changelog));
+        }
+    }
+
+    let mut footer = non_empty(&template.footer_text)
+        .map(render)
+        .unwrap_or_default();
+    if template.include_timestamp {
+        if !footer.is_empty() {
+            footer.push_str(" • ");
+        }
+        footer.push_str(&Utc::now().format("%Y-%m-%d %H:%M UTC").to_string());
+    }
+    if !footer.is_empty() {
+        html.push(format!(
+            "<p style=\"margin:12px 0 0;color:#999999;font-size:12px;\">{}</p>",
+            html_text(&footer)
+        ));
+        text.push(format!("\n{}", footer));
+    }
+
+    html.push("</div>".to_string());
+    Ok(RenderedUpdate {
+        title,
+        html: html.concat(),
+        text: text.join("\n"),
+    })
+}
+
+pub fn build_test_payload(webhook: &Webhook) -> Result<Value, TrackerError> {
+    EmailConfig::from_webhook(webhook)?;
+    let text = "This is a test message from Arti's CurseForge Mod Tracker!";
+    Ok(message(
+        "🧪 Test Message".to_string(),
+        text.to_string(),
+        format!("<h2>🧪 Test Message</h2><p>{}</p>", html_text(text)),
+    ))
+}
+
+/// Renders the template as an email whose subject is the template title, with an
+/// HTML body and a plain-text alternative for clients that don't show HTML.
+pub fn build_update_payload(
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let update = render_update(template, data)?;
+    Ok(message(update.title, update.text, update.html))
+}
+
+/// Renders every update held back for a daily digest into a single email.
+pub fn build_digest_payload(
+    template: &WebhookTemplate,
+    updates: &[ModUpdateData],
+) -> Result<Value, TrackerError> {
+    let rendered = updates
+        .iter()
+        .map(|data| render_update(template, data))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let subject = match updates {
+        [update] => format!("Daily mod update digest: {}", update.mod_name),
+        _ => format!("Daily mod update digest: {} updates", updates.len()),
+    };
+    let summary = match updates.len() {
+        1 => "1 mod update since the last digest".to_string(),
+        count => format!("{} mod updates since the last digest", count),
+    };
+
+    let html = format!(
+        "<h1 style=\"font-size:20px;\">📬 Daily Mod Update Digest</h1><p style=\"color:#666666;\">{}</p>{}",
+        summary,
+        rendered
+            .iter()
+            .map(|update| update.html.as_str())
+            .collect::<String>()
+    );
+    let text = format!(
+        "Daily Mod Update Digest\n{}\n\n{}",
+        summary,
+        rendered
+            .iter()
+            .map(|update| update.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n\n----------------------------------------\n\n")
+    );
+
+    Ok(message(subject, text, html))
+}
+
+fn build_transport(
+    webhook: &Webhook,
+    config: &EmailConfig,
+) -> Result<AsyncSmtpTransport<Tokio1Executor>, TrackerError> {
+    let host = webhook.url.trim();
+    let builder = match config.tls_mode {
+        TlsMode::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        TlsMode::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        TlsMode::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            host,
+        )),
+    }
+    .map_err(|e| TrackerError::InvalidInput(format!("Invalid SMTP server \"{}\": {}", host, e)))?;
+
+    let mut builder = builder.port(config.port()).timeout(Some(SMTP_TIMEOUT));
+    if let Some(username) = non_empty(&config.username) {
+        builder = builder.credentials(Credentials::new(
+            username.to_string(),
+            config.password.clone().unwrap_or_default(),
+        ));
+    }
+    Ok(builder.build())
+}
+
+fn status_code(code: Code) -> u16 {
+    code.to_string().parse().unwrap_or_default()
+}
+
+/// Sends a payload built for an email webhook over SMTP. The server's reply code
+/// and text stand in for the HTTP status and body other services answer with.
+pub async fn send(webhook: &Webhook, payload: &Value) -> Result<DeliveryResponse, TrackerError> {
+    let config = EmailConfig::from_webhook(webhook)?;
+
+    let mut builder = Message::builder()
+        .from(config.sender()?)
+        .subject(payload["subject"].as_str().unwrap_or("Mod Update"));
+    for recipient in config.recipients()? {
+        builder = builder.to(recipient);
+    }
+    let email = builder
+        .multipart(MultiPart::alternative_plain_html(
+            payload["text"].as_str().unwrap_or_default().to_string(),
+            payload["html"].as_str().unwrap_or_default().to_string(),
+        ))
+        .map_err(|e| TrackerError::InvalidInput(format!("Could not build the email: {}", e)))?;
+
+    let transport = build_transport(webhook, &config)?;
+    match transport.send(email).await {
+        Ok(response) => Ok(DeliveryResponse {
+            status: status_code(response.code()),
+            body: response.message().collect::<Vec<_>>().join("\n"),
+            rate_limit_delay: None,
+        }),
+        Err(error) => {
+            println!("SMTP error: {}", error);
+            // Errors without a reply code never reached the server, e.g. a refused connection
+            Err(match error.status() {
+                Some(code) => TrackerError::WebhookDelivery {
+                    service: "Email".to_string(),
+                    status: status_code(code),
+                    message: error.to_string(),
+                },
+                None => TrackerError::Network(error.to_string()),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn email_webhook(config: Value) -> Webhook {
+        Webhook {
+            id: Some(1),
+            name: "Mail".to_string(),
+            url: "smtp.example.com".to_string(),
+            avatar_url: None,
+            username: None,
+            enabled: true,
+            template_id: None,
+            kind: WebhookKind::Email,
+            config,
+        }
+    }
+
+    fn config_error(config: Value) -> String {
+        match EmailConfig::from_webhook(&email_webhook(config)) {
+            Ok(_) => panic!("config was accepted"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn accepts_several_recipients() {
+        let config = EmailConfig::from_webhook(&email_webhook(json!({
+            "from": "Tracker <tracker@example.com>",
+            "to": "a@example.com, b@example.com; c@example.com",
+        })))
+        .unwrap();
+        assert_eq!(config.recipients().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn rejects_missing_sender_or_recipients() {
+        let missing = "need a sender and at least one recipient";
+        assert!(config_error(json!({ "from": "", "to": "a@example.com" })).contains(missing));
+        assert!(config_error(json!({ "from": "t@example.com", "to": " " })).contains(missing));
+        assert!(config_error(json!({ "from": "t@example.com", "to": "," })).contains(missing));
+        assert!(config_error(json!({ "from": "t@example.com", "to": " ; , " })).contains(missing));
+    }
+
+    #[test]
+    fn rejects_invalid_addresses_and_settings() {
+        assert!(
+            config_error(json!({ "from": "t@example.com", "to": "a@example.com, nope" }))
+                .contains("\"nope\" is not a valid email address")
+        );
+        assert!(config_error(
+            json!({ "from": "t@example.com", "to": "a@example.com", "port": 70000 })
+        )
+        .contains("SMTP ports range"));
+        assert!(config_error(
+            json!({ "from": "t@example.com", "to": "a@example.com", "digest_hour": 24 })
+        )
+        .contains("digest hour"));
+    }
+
+    fn at(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(time).unwrap()
+    }
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        at(time).with_timezone(&Utc)
+    }
+
+    #[test]
+    fn digest_is_due_after_the_digest_hour() {
+        assert!(!digest_due_at(
+            8,
+            None,
+            None,
+            at("2026-03-10T07:59:00+02:00")
+        ));
+        assert!(digest_due_at(
+            8,
+            None,
+            None,
+            at("2026-03-10T08:00:00+02:00")
+        ));
+        assert!(digest_due_at(
+            8,
+            Some(utc("2026-03-09T08:05:00+02:00")),
+            None,
+            at("2026-03-10T08:00:00+02:00")
+        ));
+    }
+
+    #[test]
+    fn digest_is_sent_once_per_local_day() {
+        // 23:30 UTC on the 9th is already the 10th at +02:00
+        let sent = Some(utc("2026-03-09T23:30:00Z"));
+        assert!(!digest_due_at(
+            0,
+            sent,
+            None,
+            at("2026-03-10T09:00:00+02:00")
+        ));
+        assert!(!digest_due_at(
+            0,
+            sent,
+            None,
+            at("2026-03-10T23:59:00+02:00")
+        ));
+        assert!(digest_due_at(
+            0,
+            sent,
+            None,
+            at("2026-03-11T00:00:00+02:00")
+        ));
+    }
+
+    #[test]
+    fn failed_digest_waits_for_its_retry() {
+        let retry_at = Some(utc("2026-03-10T09:15:00+02:00"));
+        assert!(!digest_due_at(
+            8,
+            None,
+            retry_at,
+            at("2026-03-10T09:00:00+02:00")
+        ));
+        assert!(digest_due_at(
+            8,
+            None,
+            retry_at,
+            at("2026-03-10T09:15:00+02:00")
+        ));
+        // A retry time that has passed doesn't make a digest due before the hour
+        assert!(!digest_due_at(
+            8,
+            None,
+            retry_at,
+            at("2026-03-11T07:00:00+02:00")
+        ));
+    }
+}
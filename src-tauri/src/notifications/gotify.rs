@@ -0,0 +1,137 @@
+use super::{deserialize_optional_int, non_empty, plain_text_message, split_tags, ModUpdateData};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use reqwest::{Client, RequestBuilder, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const DEFAULT_PRIORITY: i64 = 5;
+
+/// The `config` of a Gotify webhook. The webhook URL is the Gotify server URL.
+#[derive(Debug, Deserialize)]
+pub struct GotifyConfig {
+    pub app_token: String,
+    #[serde(default, deserialize_with = "deserialize_optional_int")]
+    pub priority: Option<i64>,
+    #[serde(default)]
+    pub tags: String,
+}
+
+impl GotifyConfig {
+    pub fn from_webhook(webhook: &Webhook) -> Result<Self, TrackerError> {
+        let missing = || TrackerError::InvalidInput("Gotify webhooks need an app token".into());
+        let config: GotifyConfig =
+            serde_json::from_value(webhook.config.clone()).map_err(|_| missing())?;
+        if config.app_token.trim().is_empty() {
+            return Err(missing());
+        }
+
+        if let Some(priority) = config.priority {
+            if !(0..=10).contains(&priority) {
+                return Err(TrackerError::InvalidInput(
+                    "Gotify priorities range from 0 to 10".into(),
+                ));
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn message(webhook: &Webhook, title: String, text: String) -> Result<Value, TrackerError> {
+    let config = GotifyConfig::from_webhook(webhook)?;
+    let mut payload = json!({
+        "title": title,
+        "message": text,
+        "priority": config.priority.unwrap_or(DEFAULT_PRIORITY),
+    });
+
+    // Gotify has no tags of its own, so they travel as a custom extra for clients
+    // and plugins that want to filter on them
+    let tags = split_tags(&config.tags);
+    if !tags.is_empty() {
+        payload["extras"] = json!({ "modtracker::tags": tags });
+    }
+
+    Ok(payload)
+}
+
+pub fn build_test_payload(webhook: &Webhook) -> Result<Value, TrackerError> {
+    message(
+        webhook,
+        "🧪 Test Message".to_string(),
+        "This is a test message from Arti's CurseForge Mod Tracker!".to_string(),
+    )
+}
+
+/// Renders the template as a Gotify message. Gotify only shows the app's own
+/// icon, so the mod logo is sent as the notification's big image and tapping the
+/// notification opens the mod page.
+pub fn build_update_payload(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let (title, text) = plain_text_message(template, data, WebhookKind::Gotify)?;
+    let mut payload = message(webhook, title, text)?;
+
+    let mut notification = json!({});
+    if let Some(page_url) = non_empty(&data.page_url) {
+        notification["click"] = json!({ "url": page_url });
+    }
+    if let Some(logo_url) = non_empty(&data.logo_url) {
+        notification["bigImageUrl"] = json!(logo_url);
+    }
+    if notification.as_object().map_or(false, |n| !n.is_empty()) {
+        if payload.get("extras").is_none() {
+            payload["extras"] = json!({});
+        }
+        payload["extras"]["client::notification"] = notification;
+    }
+
+    Ok(payload)
+}
+
+pub fn build_request(
+    client: &Client,
+    webhook: &Webhook,
+    payload: &Value,
+) -> Result<RequestBuilder, TrackerError> {
+    let config = GotifyConfig::from_webhook(webhook)?;
+    let invalid_url =
+        || TrackerError::InvalidInput(format!("Invalid Gotify server URL \"{}\"", webhook.url));
+
+    let mut url = Url::parse(webhook.url.trim()).map_err(|_| invalid_url())?;
+    url.path_segments_mut()
+        .map_err(|_| invalid_url())?
+        .pop_if_empty()
+        .push("message");
+
+    Ok(client
+        .post(url)
+        .header("X-Gotify-Key", config.app_token.trim())
+        .json(payload))
+}
+
+/// Turns a failed Gotify response into a typed error. Gotify answers with
+/// `{"error": "...", "errorCode": ..., "errorDescription": "..."}`.
+pub async fn error_from_response(response: reqwest::Response) -> TrackerError {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let error = TrackerError::rate_limited("Gotify", response.headers());
+        println!("Gotify rate limited the webhook: {}", error);
+        return error;
+    }
+
+    let body: Option<Value> = response.json().await.ok();
+    let message = body
+        .as_ref()
+        .and_then(|b| {
+            b["errorDescription"]
+                .as_str()
+                .or_else(|| b["error"].as_str())
+        })
+        .unwrap_or("Unknown error")
+        .to_string();
+    println!("Gotify error: {}", message);
+    TrackerError::webhook_delivery("Gotify", status, message)
+}
@@ -0,0 +1,121 @@
+use super::{replace_template_variables, ModUpdateData};
+use crate::database::{Webhook, WebhookKind};
+use crate::error::TrackerError;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+
+pub const CHANGELOG_LIMIT: usize = 4000;
+
+const ALLOWED_METHODS: &[&str] = &["POST", "PUT", "PATCH"];
+
+#[derive(Debug, Deserialize)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// The `config` of a generic HTTP webhook.
+#[derive(Debug, Deserialize)]
+pub struct HttpConfig {
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: Vec<HttpHeader>,
+    pub body_template: String,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+impl HttpConfig {
+    pub fn from_webhook(webhook: &Webhook) -> Result<Self, TrackerError> {
+        let config: HttpConfig = serde_json::from_value(webhook.config.clone()).map_err(|_| {
+            TrackerError::InvalidInput("HTTP webhooks need a JSON body template".into())
+        })?;
+
+        if !ALLOWED_METHODS.contains(&config.method.to_uppercase().as_str()) {
+            return Err(TrackerError::InvalidInput(format!(
+                "Unsupported HTTP method \"{}\", use one of {}",
+                config.method,
+                ALLOWED_METHODS.join(", ")
+            )));
+        }
+        config.header_map()?;
+
+        Ok(config)
+    }
+
+    fn method(&self) -> Method {
+        Method::from_bytes(self.method.to_uppercase().as_bytes()).unwrap_or(Method::POST)
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, TrackerError> {
+        let mut headers = HeaderMap::new();
+        for header in self.headers.iter().filter(|h| !h.name.trim().is_empty()) {
+            let name = HeaderName::from_bytes(header.name.trim().as_bytes()).map_err(|_| {
+                TrackerError::InvalidInput(format!("Invalid header name \"{}\"", header.name))
+            })?;
+            let value = HeaderValue::from_str(header.value.trim()).map_err(|_| {
+                TrackerError::InvalidInput(format!("Invalid value for header \"{}\"", header.name))
+            })?;
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+}
+
+/// Escapes a value for use inside a JSON string literal, without the quotes.
+pub fn escape_json(text: &str) -> String {
+    let quoted = serde_json::to_string(text).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Fills in the webhook's body template and parses the result, so a template that
+/// doesn't produce valid JSON is reported instead of being sent.
+pub fn build_update_payload(
+    webhook: &Webhook,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let config = HttpConfig::from_webhook(webhook)?;
+    let body = replace_template_variables(&config.body_template, data, WebhookKind::Http);
+
+    serde_json::from_str(&body).map_err(|e| {
+        TrackerError::InvalidInput(format!("The body template is not valid JSON: {}", e))
+    })
+}
+
+/// HTTP targets are tested by sending the body template filled with sample data.
+pub fn build_test_payload(webhook: &Webhook) -> Result<Value, TrackerError> {
+    build_update_payload(webhook, &ModUpdateData::sample())
+}
+
+pub fn build_request(
+    client: &Client,
+    webhook: &Webhook,
+    payload: &Value,
+) -> Result<RequestBuilder, TrackerError> {
+    let config = HttpConfig::from_webhook(webhook)?;
+    Ok(client
+        .request(config.method(), &webhook.url)
+        .headers(config.header_map()?)
+        .json(payload))
+}
+
+pub async fn error_from_response(response: reqwest::Response) -> TrackerError {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let error = TrackerError::rate_limited("HTTP", response.headers());
+        println!("HTTP endpoint rate limited the webhook: {}", error);
+        return error;
+    }
+
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    println!("HTTP endpoint error: {}", error_text);
+    TrackerError::webhook_delivery("HTTP", status, error_text)
+}
@@ -0,0 +1,213 @@
+use super::{
+    escape_html, fields_show_changelog, non_empty, replace_id_placeholders,
+    replace_template_variables, truncate_text, ModUpdateData,
+};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use chrono::Utc;
+use reqwest::{Client, RequestBuilder, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub const CHANGELOG_LIMIT: usize = 4000;
+
+// Makes transaction IDs unique even for several sends within one millisecond
+static TRANSACTION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// The `config` of a Matrix webhook. The webhook URL is the homeserver URL.
+#[derive(Debug, Deserialize)]
+pub struct MatrixConfig {
+    pub access_token: String,
+    pub room_id: String,
+}
+
+impl MatrixConfig {
+    pub fn from_webhook(webhook: &Webhook) -> Result<Self, TrackerError> {
+        let missing = || {
+            TrackerError::InvalidInput("Matrix webhooks need an access token and room ID".into())
+        };
+        let config: MatrixConfig =
+            serde_json::from_value(webhook.config.clone()).map_err(|_| missing())?;
+        if config.access_token.trim().is_empty() || config.room_id.trim().is_empty() {
+            return Err(missing());
+        }
+        Ok(config)
+    }
+}
+
+/// `{everyone}` and `{here}` become `@room`; Matrix has no role or channel mentions.
+pub fn replace_mentions(text: &str) -> String {
+    let result = text
+        .replace("{everyone}", "@room")
+        .replace("{here}", "@room");
+    let result = replace_id_placeholders(&result, "&", |id| !id.is_empty(), &|_| String::new());
+    replace_id_placeholders(&result, "#", |id| !id.is_empty(), &|_| String::new())
+}
+
+fn html_text(text: &str) -> String {
+    escape_html(text).replace('\n', "<br>")
+}
+
+fn message(plain: String, html: String) -> Value {
+    json!({
+        "msgtype": "m.text",
+        "body": plain,
+        "format": "org.matrix.custom.html",
+        "formatted_body": html,
+    })
+}
+
+pub fn build_test_payload() -> Value {
+    let text = "This is a test message from Arti's CurseForge Mod Tracker!";
+    message(
+        format!("🧪 Test Message\n{}", text),
+        format!("<h4>🧪 Test Message</h4><p>{}</p>", html_text(text)),
+    )
+}
+
+/// Renders the template as an `m.room.message` with an `org.matrix.custom.html`
+/// body and a plain-text fallback. Thumbnails are left out: Matrix clients only
+/// display images uploaded to the homeserver.
+pub fn build_update_payload(
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let render = |text: &str| replace_template_variables(text, data, WebhookKind::Matrix);
+
+    if !template.use_embed {
+        let content = render(
+            template
+                .content
+                .as_deref()
+                .unwrap_or("🔄 Mod Update Available!"),
+        );
+        let html = format!("<p>{}</p>", html_text(&content));
+        return Ok(message(content, html));
+    }
+
+    let title = render(&template.title);
+    let mut html = vec![format!("<h4>{}</h4>", html_text(&title))];
+    let mut plain = vec![title];
+
+    if let Some(author_name) = non_empty(&template.author_name) {
+        let author = render(author_name);
+        html.push(format!("<p><em>{}</em></p>", html_text(&author)));
+        plain.push(author);
+    }
+
+    let mut field_lines = Vec::new();
+    for field in serde_json::from_str::<Vec<Value>>(&template.embed_fields)? {
+        let name = render(field["name"].as_str().unwrap_or(""));
+        let value = render(field["value"].as_str().unwrap_or(""));
+        if value.trim().is_empty() {
+            continue;
+        }
+        let (separator, html_separator) = if field["inline"].as_bool().unwrap_or(false) {
+            (": ", ": ")
+        } else {
+            ("\n", "<br>")
+        };
+        field_lines.push(format!(
+            "<b>{}</b>{}{}",
+            html_text(&name),
+            html_separator,
+            html_text(&value)
+        ));
+        plain.push(format!("{}{}{}", name, separator, value));
+    }
+    if !field_lines.is_empty() {
+        html.push(format!("<p>{}</p>", field_lines.join("<br>")));
+    }
+
+    // Only add the changelog on its own when no field already shows it
+    if let Some(changelog) = data.changelog_for(WebhookKind::Matrix).as_deref() {
+        if !fields_show_changelog(template) && !changelog.trim().is_empty() {
+            let changelog = truncate_text(changelog.trim(), CHANGELOG_LIMIT);
+            html.push(format!(
+                "<p><b>Changelog</b></p><blockquote>{}</blockquote>",
+                html_text(&changelog)
+            ));
+            plain.push(format!("Changelog\n{}", changelog));
+        }
+    }
+
+    let mut footer = non_empty(&template.footer_text)
+        .map(render)
+        .unwrap_or_default();
+    if template.include_timestamp {
+        if !footer.is_empty() {
+            footer.push_str(" • ");
+        }
+        footer.push_str(&Utc::now().format("%Y-%m-%d %H:%M UTC").to_string());
+    }
+    if !footer.is_empty() {
+        html.push(format!("<p><sub>{}</sub></p>", html_text(&footer)));
+        plain.push(footer);
+    }
+
+    Ok(message(plain.join("\n\n"), html.concat()))
+}
+
+/// Builds the `PUT /_matrix/client/v3/rooms/{roomId}/send/m.room.message/{txnId}`
+/// request. The room ID is percent-encoded as a single path segment.
+pub fn build_request(
+    client: &Client,
+    webhook: &Webhook,
+    payload: &Value,
+) -> Result<RequestBuilder, TrackerError> {
+    let config = MatrixConfig::from_webhook(webhook)?;
+    let invalid_url =
+        || TrackerError::InvalidInput(format!("Invalid homeserver URL \"{}\"", webhook.url));
+
+    let mut url = Url::parse(webhook.url.trim()).map_err(|_| invalid_url())?;
+    let transaction_id = format!(
+        "modtracker-{}-{}",
+        Utc::now().timestamp_millis(),
+        TRANSACTION_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+    url.path_segments_mut()
+        .map_err(|_| invalid_url())?
+        .pop_if_empty()
+        .extend(&["_matrix", "client", "v3", "rooms"])
+        .push(config.room_id.trim())
+        .extend(&["send", "m.room.message"])
+        .push(&transaction_id);
+
+    Ok(client
+        .put(url)
+        .bearer_auth(config.access_token.trim())
+        .json(payload))
+}
+
+/// Turns a failed client-server API response into a typed error. Matrix reports
+/// `errcode`/`error` and, for rate limits, `retry_after_ms`.
+pub async fn error_from_response(response: reqwest::Response) -> TrackerError {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body: Option<Value> = response.json().await.ok();
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let mut error = TrackerError::rate_limited("Matrix", &headers);
+        if let TrackerError::RateLimited { retry_after, .. } = &mut error {
+            if let Some(ms) = body.as_ref().and_then(|b| b["retry_after_ms"].as_f64()) {
+                *retry_after = Some(ms / 1000.0);
+            }
+        }
+        println!("Matrix rate limited the bot: {}", error);
+        return error;
+    }
+
+    let message = body
+        .as_ref()
+        .map(|b| {
+            format!(
+                "{}: {}",
+                b["errcode"].as_str().unwrap_or("M_UNKNOWN"),
+                b["error"].as_str().unwrap_or("Unknown error")
+            )
+        })
+        .unwrap_or_else(|| "Unknown error".to_string());
+    println!("Matrix API error: {}", message);
+    TrackerError::webhook_delivery("Matrix", status, message)
+}
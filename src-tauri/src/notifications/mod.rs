@@ -0,0 +1,652 @@
+pub mod changelog;
+pub mod discord;
+pub mod email;
+pub mod gotify;
+pub mod http;
+pub mod matrix;
+pub mod ntfy;
+pub mod rate_limit;
+pub mod slack;
+pub mod telegram;
+pub mod template;
+
+use crate::commands::mod_commands::{ModUpdateInfo, UpdateFile};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use changelog::ChangelogFormat;
+use chrono::{DateTime, Datelike, Utc};
+use rate_limit::DiscordRateLimiter;
+use reqwest::{Client, RequestBuilder};
+use rusqlite::Connection;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+// Push notifications are read on phones, so long changelogs are cut early
+const PUSH_CHANGELOG_LIMIT: usize = 1000;
+
+/// Everything a template can reference for a single mod update.
+#[derive(Debug)]
+pub struct ModUpdateData {
+    pub mod_id: i64,
+    pub curseforge_id: i64,
+    pub mod_name: String,
+    pub mod_author: String,
+    pub new_release_date: String,
+    pub old_release_date: String,
+    pub latest_file_name: String,
+    pub logo_url: Option<String>,
+    pub page_url: Option<String>,
+    /// The changelog as plain text
+    pub changelog: Option<String>,
+    /// The changelog as CurseForge published it, converted to each service's markup
+    pub changelog_html: Option<String>,
+    pub file_id: Option<i64>,
+    pub file_display_name: Option<String>,
+    pub file_date: Option<String>,
+    pub release_type: Option<String>,
+    pub game_versions: Vec<String>,
+    pub authors: Vec<String>,
+    pub files: Vec<UpdateFile>,
+    /// The new release date as RFC 3339, for templates that format it themselves
+    pub released_at: String,
+}
+
+impl ModUpdateData {
+    pub fn from_update(conn: &Connection, update: &ModUpdateInfo) -> Self {
+        ModUpdateData {
+            mod_id: update.mod_id,
+            curseforge_id: update.curseforge_id,
+            mod_name: update.name.clone(),
+            mod_author: update.mod_author.clone(),
+            new_release_date: format_date(&update.new_update_time),
+            old_release_date: format_date(&update.old_update_time),
+            latest_file_name: update.latest_file_name.clone(),
+            logo_url: update.logo_url.clone(),
+            page_url: conn
+                .query_row(
+                    "SELECT page_url FROM mods WHERE id = ?1",
+                    [update.mod_id],
+                    |row| row.get(0),
+                )
+                .unwrap_or(None),
+            changelog: update.changelog.clone(),
+            changelog_html: update.changelog_html.clone(),
+            file_id: update.file_id,
+            file_display_name: update.file_display_name.clone(),
+            file_date: update.file_date.as_deref().map(format_date),
+            release_type: update.release_type.clone(),
+            game_versions: update.game_versions.clone(),
+            authors: if update.authors.is_empty() {
+                vec![update.mod_author.clone()]
+            } else {
+                update.authors.clone()
+            },
+            files: update.files.clone(),
+            released_at: update.new_update_time.clone(),
+        }
+    }
+
+    /// Placeholder data for test sends of templates that need a mod to render.
+    pub fn sample() -> Self {
+        let now = Utc::now().to_rfc3339();
+        ModUpdateData {
+            mod_id: 0,
+            curseforge_id: 0,
+            mod_name: "Test Mod".to_string(),
+            mod_author: "Mod Tracker".to_string(),
+            new_release_date: format_date(&now),
+            old_release_date: format_date(&now),
+            latest_file_name: "test-mod-1.0.0.zip".to_string(),
+            logo_url: None,
+            page_url: None,
+            changelog: Some(
+                "This is a test message from Arti's CurseForge Mod Tracker!".to_string(),
+            ),
+            changelog_html: None,
+            file_id: None,
+            file_display_name: Some("Test Mod 1.0.0".to_string()),
+            file_date: Some(format_date(&now)),
+            release_type: Some("Release".to_string()),
+            game_versions: Vec::new(),
+            authors: vec!["Mod Tracker".to_string()],
+            files: vec![UpdateFile {
+                id: 0,
+                display_name: "Test Mod 1.0.0".to_string(),
+                file_name: "test-mod-1.0.0.zip".to_string(),
+                file_date: now.clone(),
+                release_type: "release".to_string(),
+                game_versions: Vec::new(),
+            }],
+            released_at: now.clone(),
+        }
+    }
+
+    /// The changelog in the markup of the service `kind` posts to. Updates recorded
+    /// before the HTML was kept only have the plain text.
+    pub fn changelog_for(&self, kind: WebhookKind) -> Option<String> {
+        match self.changelog_html.as_deref() {
+            Some(html) => Some(changelog::convert(html, ChangelogFormat::for_kind(kind)))
+                .filter(|changelog| !changelog.trim().is_empty()),
+            None => self.changelog.clone(),
+        }
+    }
+}
+
+/// What the receiving service answered to a successful delivery.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryResponse {
+    pub status: u16,
+    pub body: String,
+    /// Seconds the send was held back to stay within the service's rate limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_delay: Option<f64>,
+}
+
+impl DeliveryResponse {
+    /// Recovers the status and body of a delivery the service rejected.
+    pub fn from_error(error: &TrackerError) -> Option<Self> {
+        match error {
+            TrackerError::WebhookDelivery {
+                status, message, ..
+            } => Some(DeliveryResponse {
+                status: *status,
+                body: message.clone(),
+                rate_limit_delay: None,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// One message of a digest, together with the indices of the updates it covers.
+#[derive(Debug)]
+pub struct DigestMessage {
+    pub payload: Value,
+    pub updates: Vec<usize>,
+    /// What had to be shortened to stay within the service's limits
+    pub truncations: Vec<String>,
+}
+
+/// An update notification ready to send: the notification itself, followed by any
+/// messages carrying what didn't fit into it.
+#[derive(Debug)]
+pub struct UpdateMessages {
+    pub payloads: Vec<Value>,
+    /// What had to be shortened or split off to stay within the service's limits
+    pub truncations: Vec<String>,
+}
+
+fn get_ordinal_suffix(day: u32) -> &'static str {
+    if (11..=13).contains(&(day % 100)) {
+        return "th";
+    }
+    match day % 10 {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    }
+}
+
+pub fn format_date(date_str: &str) -> String {
+    if let Ok(date) = DateTime::parse_from_rfc3339(date_str) {
+        let utc_date: DateTime<Utc> = date.into();
+        let day = utc_date.day();
+        let suffix = get_ordinal_suffix(day);
+
+        format!(
+            "{}{} {} {} at {:02}:{:02} UTC",
+            day,
+            suffix,
+            utc_date.format("%B"),
+            utc_date.format("%Y"),
+            utc_date.format("%H"),
+            utc_date.format("%M")
+        )
+    } else {
+        date_str.to_string()
+    }
+}
+
+/// Cuts `text` to at most `max_chars` characters, ending in "..." when shortened.
+/// Prefers breaking at the last space so words aren't split.
+pub fn truncate_text(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let keep = max_chars.saturating_sub(3);
+    let cut = text
+        .char_indices()
+        .nth(keep)
+        .map(|(index, _)| index)
+        .unwrap_or(text.len());
+    let head = &text[..cut];
+    let head = match head.rfind(' ') {
+        Some(last_space) if last_space > 0 => &head[..last_space],
+        _ => head,
+    };
+    format!("{}...", head)
+}
+
+pub fn format_changelog(changelog: Option<&str>, max_chars: usize) -> String {
+    match changelog {
+        Some(text) if !text.trim().is_empty() => truncate_text(text.trim(), max_chars),
+        _ => "No changelog available".to_string(),
+    }
+}
+
+// Replaces every `{<prefix>ID}` placeholder whose ID passes `is_valid_id` with `render(ID)`
+pub(crate) fn replace_id_placeholders(
+    text: &str,
+    prefix: &str,
+    is_valid_id: fn(&str) -> bool,
+    render: &dyn Fn(&str) -> String,
+) -> String {
+    let open = format!("{{{}", prefix);
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        match after.find('}') {
+            Some(end) if is_valid_id(&after[..end]) => {
+                result.push_str(&rest[..start]);
+                result.push_str(&render(&after[..end]));
+                rest = &after[end + 1..];
+            }
+            _ => {
+                result.push_str(&rest[..start + open.len()]);
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Whether one of the template's fields already shows the changelog, in which case
+/// it isn't added again on its own.
+pub(crate) fn fields_show_changelog(template: &WebhookTemplate) -> bool {
+    template::references(&template.embed_fields, "changelog")
+}
+
+/// Drops mention placeholders for services that have no equivalent.
+pub(crate) fn strip_mentions(text: &str) -> String {
+    let result = text.replace("{everyone}", "").replace("{here}", "");
+    let result = replace_id_placeholders(&result, "&", |id| !id.is_empty(), &|_| String::new());
+    replace_id_placeholders(&result, "#", |id| !id.is_empty(), &|_| String::new())
+}
+
+/// Renders a template string: its `{{...}}` expressions through the template engine,
+/// then the `{...}` placeholders. Values are escaped and mentions are rendered in the
+/// markup of the service `kind` posts to.
+pub fn replace_template_variables(text: &str, data: &ModUpdateData, kind: WebhookKind) -> String {
+    let escape: fn(&str) -> String = match kind {
+        WebhookKind::Discord => str::to_string,
+        WebhookKind::Slack => slack::escape,
+        // Telegram escapes the whole rendered text, including the template's own
+        WebhookKind::Telegram => str::to_string,
+        WebhookKind::Http => http::escape_json,
+        // Matrix and email escape the rendered text as HTML afterwards
+        WebhookKind::Matrix | WebhookKind::Ntfy | WebhookKind::Gotify | WebhookKind::Email => {
+            str::to_string
+        }
+    };
+    let changelog_limit = match kind {
+        WebhookKind::Discord => discord::FIELD_VALUE_LIMIT,
+        WebhookKind::Slack => slack::SECTION_TEXT_LIMIT,
+        WebhookKind::Telegram => telegram::CHANGELOG_LIMIT,
+        WebhookKind::Http => http::CHANGELOG_LIMIT,
+        WebhookKind::Matrix => matrix::CHANGELOG_LIMIT,
+        WebhookKind::Ntfy | WebhookKind::Gotify => PUSH_CHANGELOG_LIMIT,
+        WebhookKind::Email => email::CHANGELOG_LIMIT,
+    };
+
+    let changelog = data
+        .changelog_for(kind)
+        .filter(|changelog| !changelog.trim().is_empty())
+        .map(|changelog| format_changelog(Some(&changelog), changelog_limit));
+
+    // Conditionals, loops and filters run first, the `{placeholder}` values after that
+    let mut result = match template::render(text, data, escape, changelog.as_deref()) {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            // Templates are validated when saved, so this only catches older ones
+            println!("Failed to render template {:?}: {}", text, e);
+            text.to_string()
+        }
+    };
+
+    // Basic replacements
+    let replacements = vec![
+        ("{modID}", data.curseforge_id.to_string()),
+        ("{modDatabaseID}", data.mod_id.to_string()),
+        ("{modName}", escape(&data.mod_name)),
+        ("{newReleaseDate}", escape(&data.new_release_date)),
+        ("{oldPreviousDate}", escape(&data.old_release_date)),
+        ("{lastestModFileName}", escape(&data.latest_file_name)),
+        ("{modAuthorName}", escape(&data.mod_author)),
+        (
+            "{logoUrl}",
+            escape(data.logo_url.as_deref().unwrap_or_default()),
+        ),
+        (
+            "{modURL}",
+            escape(data.page_url.as_deref().unwrap_or_default()),
+        ),
+        (
+            "{changelog}",
+            escape(&format_changelog(changelog.as_deref(), changelog_limit)),
+        ),
+        (
+            "{fileID}",
+            data.file_id.map(|id| id.to_string()).unwrap_or_default(),
+        ),
+        (
+            "{fileDisplayName}",
+            escape(data.file_display_name.as_deref().unwrap_or_default()),
+        ),
+        (
+            "{fileDate}",
+            escape(data.file_date.as_deref().unwrap_or_default()),
+        ),
+        (
+            "{fileReleaseType}",
+            escape(data.release_type.as_deref().unwrap_or_default()),
+        ),
+        ("{fileGameVersions}", escape(&data.game_versions.join(", "))),
+    ];
+
+    for (key, value) in replacements {
+        result = result.replace(key, &value);
+    }
+
+    match kind {
+        WebhookKind::Discord => discord::replace_mentions(&result),
+        WebhookKind::Slack => slack::replace_mentions(&result),
+        WebhookKind::Matrix => matrix::replace_mentions(&result),
+        WebhookKind::Telegram
+        | WebhookKind::Http
+        | WebhookKind::Ntfy
+        | WebhookKind::Gotify
+        | WebhookKind::Email => strip_mentions(&result),
+    }
+}
+
+/// Renders the template as a plain-text title and message for push services:
+/// the content for simple messages, or one line per field plus the changelog.
+pub(crate) fn plain_text_message(
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+    kind: WebhookKind,
+) -> Result<(String, String), TrackerError> {
+    let render = |text: &str| replace_template_variables(text, data, kind);
+    let title = render(&template.title);
+
+    if !template.use_embed {
+        let content = template
+            .content
+            .as_deref()
+            .unwrap_or("🔄 Mod Update Available!");
+        return Ok((title, render(content)));
+    }
+
+    let mut lines = Vec::new();
+    for field in serde_json::from_str::<Vec<Value>>(&template.embed_fields)? {
+        let name = render(field["name"].as_str().unwrap_or(""));
+        let value = render(field["value"].as_str().unwrap_or(""));
+        // Fields a template hides with a condition render empty and are left out
+        if value.trim().is_empty() {
+            continue;
+        }
+        lines.push(format!("{}: {}", name, value));
+    }
+
+    // Only add the changelog on its own when no field already shows it
+    if let Some(changelog) = data.changelog_for(kind).as_deref() {
+        if !fields_show_changelog(template) && !changelog.trim().is_empty() {
+            lines.push(String::new());
+            lines.push(truncate_text(changelog.trim(), PUSH_CHANGELOG_LIMIT));
+        }
+    }
+
+    Ok((title, lines.join("\n")))
+}
+
+/// Accepts an integer given either as a JSON number or as a string, since select
+/// inputs in the webhook form submit their values as strings.
+pub(crate) fn deserialize_optional_int<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<i64>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(text) if text.trim().is_empty() => Ok(None),
+        Value::String(text) => text.trim().parse().map(Some).map_err(D::Error::custom),
+        Value::Number(number) => number
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| D::Error::custom("expected an integer")),
+        _ => Err(D::Error::custom("expected an integer")),
+    }
+}
+
+/// Splits a comma-separated tag list, dropping empty entries.
+pub(crate) fn split_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Builds the request body announcing `data` to `webhook`, in that service's format.
+pub fn build_update_payload(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    match webhook.kind {
+        WebhookKind::Discord => discord::build_update_payload(webhook, template, data),
+        WebhookKind::Slack => slack::build_update_payload(webhook, template, data),
+        WebhookKind::Telegram => telegram::build_update_payload(webhook, template, data),
+        // HTTP webhooks render their own body template instead of the embed template
+        WebhookKind::Http => http::build_update_payload(webhook, data),
+        WebhookKind::Matrix => matrix::build_update_payload(template, data),
+        WebhookKind::Ntfy => ntfy::build_update_payload(webhook, template, data),
+        WebhookKind::Gotify => gotify::build_update_payload(webhook, template, data),
+        WebhookKind::Email => email::build_update_payload(template, data),
+    }
+}
+
+/// Builds the messages announcing `data` to `webhook`. Discord messages are brought
+/// within Discord's limits, which may take more than one message.
+pub fn build_update_messages(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<UpdateMessages, TrackerError> {
+    let payload = build_update_payload(webhook, template, data)?;
+    Ok(match webhook.kind {
+        WebhookKind::Discord => discord::fit_to_limits(payload),
+        _ => UpdateMessages {
+            payloads: vec![payload],
+            truncations: Vec::new(),
+        },
+    })
+}
+
+pub fn build_test_payload(webhook: &Webhook) -> Result<Value, TrackerError> {
+    match webhook.kind {
+        WebhookKind::Discord => Ok(discord::build_test_payload(webhook)),
+        WebhookKind::Slack => Ok(slack::build_test_payload(webhook)),
+        WebhookKind::Telegram => telegram::build_test_payload(webhook),
+        WebhookKind::Http => http::build_test_payload(webhook),
+        WebhookKind::Matrix => Ok(matrix::build_test_payload()),
+        WebhookKind::Ntfy => ntfy::build_test_payload(webhook),
+        WebhookKind::Gotify => gotify::build_test_payload(webhook),
+        WebhookKind::Email => email::build_test_payload(webhook),
+    }
+}
+
+/// Whether updates for this webhook are collected into digests instead of being
+/// sent one by one.
+pub fn uses_digest(webhook: &Webhook) -> bool {
+    match webhook.kind {
+        WebhookKind::Discord => discord::digest_window(webhook).is_some(),
+        WebhookKind::Email => email::uses_daily_digest(webhook),
+        _ => false,
+    }
+}
+
+/// Renders the updates collected for a webhook's digest as the messages to send.
+pub fn build_digest_messages(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    updates: &[ModUpdateData],
+) -> Result<Vec<DigestMessage>, TrackerError> {
+    match webhook.kind {
+        WebhookKind::Discord => discord::build_digest_messages(webhook, template, updates),
+        WebhookKind::Email => Ok(vec![DigestMessage {
+            payload: email::build_digest_payload(template, updates)?,
+            updates: (0..updates.len()).collect(),
+            truncations: Vec::new(),
+        }]),
+        _ => Err(TrackerError::InvalidInput(format!(
+            "{} webhooks don't support digests",
+            webhook.kind.display_name()
+        ))),
+    }
+}
+
+/// Rejects webhooks whose service-specific settings are missing or malformed.
+pub fn validate_webhook(webhook: &Webhook) -> Result<(), TrackerError> {
+    match webhook.kind {
+        WebhookKind::Discord => discord::DiscordConfig::from_webhook(webhook).map(|_| ()),
+        WebhookKind::Slack => Ok(()),
+        WebhookKind::Telegram => telegram::TelegramConfig::from_webhook(webhook).map(|_| ()),
+        // Rendering sample data also catches body templates that aren't valid JSON
+        WebhookKind::Http => {
+            let config = http::HttpConfig::from_webhook(webhook)?;
+            template::validate_text("the body template", &config.body_template)?;
+            http::build_test_payload(webhook).map(|_| ())
+        }
+        WebhookKind::Matrix => matrix::MatrixConfig::from_webhook(webhook).map(|_| ()),
+        WebhookKind::Ntfy => ntfy::build_test_payload(webhook).map(|_| ()),
+        WebhookKind::Gotify => gotify::GotifyConfig::from_webhook(webhook).map(|_| ()),
+        WebhookKind::Email => email::EmailConfig::from_webhook(webhook).map(|_| ()),
+    }
+}
+
+fn build_request(
+    client: &Client,
+    webhook: &Webhook,
+    payload: &Value,
+) -> Result<RequestBuilder, TrackerError> {
+    match webhook.kind {
+        WebhookKind::Discord | WebhookKind::Slack => Ok(client.post(&webhook.url).json(payload)),
+        WebhookKind::Telegram => Ok(client
+            .post(telegram::request_url(webhook, payload)?)
+            .json(payload)),
+        WebhookKind::Http => http::build_request(client, webhook, payload),
+        WebhookKind::Matrix => matrix::build_request(client, webhook, payload),
+        WebhookKind::Ntfy => ntfy::build_request(client, webhook, payload),
+        WebhookKind::Gotify => gotify::build_request(client, webhook, payload),
+        WebhookKind::Email => unreachable!("email webhooks are sent over SMTP"),
+    }
+}
+
+/// Sends a payload built for `webhook` and turns a failed response into a typed error.
+/// Discord sends are paced by `rate_limiter`; email webhooks are handed to the SMTP
+/// transport instead.
+pub async fn post_payload(
+    client: &Client,
+    rate_limiter: &DiscordRateLimiter,
+    webhook: &Webhook,
+    payload: &Value,
+) -> Result<DeliveryResponse, TrackerError> {
+    // Only log where the payload goes; the payload itself can run to several KB
+    println!(
+        "Sending {} webhook: {}",
+        webhook.kind.display_name(),
+        webhook.name
+    );
+
+    if webhook.kind == WebhookKind::Email {
+        return email::send(webhook, payload).await;
+    }
+
+    let (response, waited) = if webhook.kind == WebhookKind::Discord {
+        // `wait=true` returns the posted message, whose ID later edits need
+        let url = discord::wait_url(&webhook.url);
+        rate_limiter
+            .send(&url, || client.post(&url).json(payload))
+            .await?
+    } else {
+        let response = build_request(client, webhook, payload)?.send().await?;
+        (response, Duration::ZERO)
+    };
+    if response.status().is_success() {
+        return Ok(DeliveryResponse {
+            status: response.status().as_u16(),
+            body: response.text().await.unwrap_or_default(),
+            rate_limit_delay: Some(waited.as_secs_f64()).filter(|secs| *secs > 0.0),
+        });
+    }
+
+    Err(match webhook.kind {
+        WebhookKind::Discord => discord::error_from_response(response).await,
+        WebhookKind::Slack => slack::error_from_response(response).await,
+        WebhookKind::Telegram => telegram::error_from_response(response).await,
+        WebhookKind::Http => http::error_from_response(response).await,
+        WebhookKind::Matrix => matrix::error_from_response(response).await,
+        WebhookKind::Ntfy => ntfy::error_from_response(response).await,
+        WebhookKind::Gotify => gotify::error_from_response(response).await,
+        WebhookKind::Email => unreachable!("email webhooks are sent over SMTP"),
+    })
+}
+
+/// Replaces a message a Discord webhook posted earlier with `payload`.
+pub async fn edit_discord_message(
+    client: &Client,
+    rate_limiter: &DiscordRateLimiter,
+    webhook: &Webhook,
+    message_id: &str,
+    payload: &Value,
+) -> Result<DeliveryResponse, TrackerError> {
+    let url = discord::message_url(&webhook.url, message_id);
+    let body = discord::edit_payload(payload);
+    println!(
+        "Editing message {} of Discord webhook: {}",
+        message_id, webhook.name
+    );
+
+    let (response, waited) = rate_limiter
+        .send(&url, || client.patch(&url).json(&body))
+        .await?;
+    if response.status().is_success() {
+        return Ok(DeliveryResponse {
+            status: response.status().as_u16(),
+            body: response.text().await.unwrap_or_default(),
+            rate_limit_delay: Some(waited.as_secs_f64()).filter(|secs| *secs > 0.0),
+        });
+    }
+
+    Err(discord::error_from_response(response).await)
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub(crate) fn non_empty(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,143 @@
+use super::{deserialize_optional_int, non_empty, plain_text_message, split_tags, ModUpdateData};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use reqwest::{Client, RequestBuilder, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const DEFAULT_PRIORITY: i64 = 3;
+
+/// The `config` of an ntfy webhook. The webhook URL is the topic URL, e.g.
+/// `https://ntfy.sh/my-topic`. Either an access token or a username and password
+/// can be given for protected topics.
+#[derive(Debug, Default, Deserialize)]
+pub struct NtfyConfig {
+    #[serde(default, deserialize_with = "deserialize_optional_int")]
+    pub priority: Option<i64>,
+    #[serde(default)]
+    pub tags: String,
+    pub token: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl NtfyConfig {
+    pub fn from_webhook(webhook: &Webhook) -> Result<Self, TrackerError> {
+        let config: NtfyConfig = if webhook.config.is_null() {
+            NtfyConfig::default()
+        } else {
+            serde_json::from_value(webhook.config.clone())
+                .map_err(|e| TrackerError::InvalidInput(format!("Invalid ntfy settings: {}", e)))?
+        };
+
+        if let Some(priority) = config.priority {
+            if !(1..=5).contains(&priority) {
+                return Err(TrackerError::InvalidInput(
+                    "ntfy priorities range from 1 (min) to 5 (max)".into(),
+                ));
+            }
+        }
+        Ok(config)
+    }
+}
+
+// ntfy's JSON publishing goes to the server root and names the topic in the body
+fn split_topic_url(webhook: &Webhook) -> Result<(Url, String), TrackerError> {
+    let invalid = || {
+        TrackerError::InvalidInput(format!(
+            "\"{}\" is not an ntfy topic URL like https://ntfy.sh/my-topic",
+            webhook.url
+        ))
+    };
+
+    let mut url = Url::parse(webhook.url.trim()).map_err(|_| invalid())?;
+    let topic = url
+        .path_segments()
+        .and_then(|segments| segments.filter(|s| !s.is_empty()).last())
+        .map(str::to_string)
+        .ok_or_else(invalid)?;
+    url.path_segments_mut()
+        .map_err(|_| invalid())?
+        .pop_if_empty()
+        .pop();
+    Ok((url, topic))
+}
+
+fn message(webhook: &Webhook, title: String, text: String) -> Result<Value, TrackerError> {
+    let config = NtfyConfig::from_webhook(webhook)?;
+    let (_, topic) = split_topic_url(webhook)?;
+
+    Ok(json!({
+        "topic": topic,
+        "title": title,
+        "message": text,
+        "priority": config.priority.unwrap_or(DEFAULT_PRIORITY),
+        "tags": split_tags(&config.tags),
+    }))
+}
+
+pub fn build_test_payload(webhook: &Webhook) -> Result<Value, TrackerError> {
+    message(
+        webhook,
+        "🧪 Test Message".to_string(),
+        "This is a test message from Arti's CurseForge Mod Tracker!".to_string(),
+    )
+}
+
+/// Renders the template as a push notification. The mod logo becomes the
+/// notification icon and tapping it opens the mod page.
+pub fn build_update_payload(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let (title, text) = plain_text_message(template, data, WebhookKind::Ntfy)?;
+    let mut payload = message(webhook, title, text)?;
+
+    if let Some(logo_url) = non_empty(&data.logo_url) {
+        payload["icon"] = json!(logo_url);
+    }
+    if let Some(page_url) = non_empty(&data.page_url) {
+        payload["click"] = json!(page_url);
+    }
+
+    Ok(payload)
+}
+
+pub fn build_request(
+    client: &Client,
+    webhook: &Webhook,
+    payload: &Value,
+) -> Result<RequestBuilder, TrackerError> {
+    let config = NtfyConfig::from_webhook(webhook)?;
+    let (server_url, _) = split_topic_url(webhook)?;
+
+    let request = client.post(server_url).json(payload);
+    Ok(
+        match (non_empty(&config.token), non_empty(&config.username)) {
+            (Some(token), _) => request.bearer_auth(token),
+            (None, Some(username)) => request.basic_auth(username, config.password.as_deref()),
+            (None, None) => request,
+        },
+    )
+}
+
+/// Turns a failed ntfy response into a typed error. ntfy answers with
+/// `{"code": ..., "http": ..., "error": "..."}`.
+pub async fn error_from_response(response: reqwest::Response) -> TrackerError {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let error = TrackerError::rate_limited("ntfy", response.headers());
+        println!("ntfy rate limited the webhook: {}", error);
+        return error;
+    }
+
+    let body: Option<Value> = response.json().await.ok();
+    let message = body
+        .as_ref()
+        .and_then(|b| b["error"].as_str())
+        .unwrap_or("Unknown error")
+        .to_string();
+    println!("ntfy error: {}", message);
+    TrackerError::webhook_delivery("ntfy", status, message)
+}
@@ -0,0 +1,156 @@
+use crate::error::TrackerError;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Longer waits are left to the outbox, which retries after Discord's `retry_after`
+const MAX_WAIT: Duration = Duration::from_secs(60);
+const MAX_RETRIES: u32 = 3;
+// Used when a 429 response says nothing about how long to wait
+const DEFAULT_RETRY_AFTER_SECS: f64 = 1.0;
+
+/// What Discord last reported about a webhook's rate limit bucket.
+#[derive(Debug, Default)]
+struct Bucket {
+    /// The `X-RateLimit-Bucket` hash, kept for logging
+    id: Option<String>,
+    remaining: Option<u64>,
+    reset_at: Option<Instant>,
+}
+
+impl Bucket {
+    fn update(&mut self, headers: &HeaderMap) {
+        if let Some(id) = header_str(headers, "x-ratelimit-bucket") {
+            self.id = Some(id.to_string());
+        }
+        if let Some(remaining) =
+            header_str(headers, "x-ratelimit-remaining").and_then(|value| value.parse().ok())
+        {
+            self.remaining = Some(remaining);
+        }
+        if let Some(reset_after) =
+            header_str(headers, "x-ratelimit-reset-after").and_then(|value| value.parse().ok())
+        {
+            self.reset_at = Some(Instant::now() + seconds(reset_after));
+        }
+    }
+
+    // How long until the bucket allows another request
+    fn wait_time(&self, now: Instant) -> Duration {
+        match (self.remaining, self.reset_at) {
+            (Some(0), Some(reset_at)) => reset_at.saturating_duration_since(now),
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+/// Paces Discord webhook sends so bursts of updates don't get rejected. Sends to the
+/// same webhook URL are queued one after another and wait for the route's bucket to
+/// refill; a global rate limit holds back every webhook until it resets.
+#[derive(Debug, Default)]
+pub struct DiscordRateLimiter {
+    routes: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Bucket>>>>,
+    global_reset_at: Mutex<Option<Instant>>,
+}
+
+impl DiscordRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn route(&self, url: &str) -> Arc<tokio::sync::Mutex<Bucket>> {
+        // The query string (e.g. `?wait=true`) doesn't change the route
+        let key = url.split('?').next().unwrap_or(url).to_string();
+        self.routes.lock().unwrap().entry(key).or_default().clone()
+    }
+
+    fn global_wait_time(&self, now: Instant) -> Duration {
+        self.global_reset_at
+            .lock()
+            .unwrap()
+            .map(|reset_at| reset_at.saturating_duration_since(now))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Sends the request built by `build` once the webhook's bucket and the global
+    /// limit allow it, retrying up to three times on a 429 answer. Returns the
+    /// response together with how long the send was held back. Gives up with
+    /// `RateLimited` when waiting would take longer than a minute.
+    pub async fn send<F>(&self, url: &str, build: F) -> Result<(Response, Duration), TrackerError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let route = self.route(url);
+        // Holding the route's lock queues concurrent sends to the same webhook
+        let mut bucket = route.lock().await;
+        let mut waited = Duration::ZERO;
+        let mut retries = 0;
+
+        loop {
+            let now = Instant::now();
+            let wait = bucket.wait_time(now).max(self.global_wait_time(now));
+            if !wait.is_zero() {
+                if waited + wait > MAX_WAIT {
+                    return Err(TrackerError::RateLimited {
+                        service: "Discord".to_string(),
+                        retry_after: Some(wait.as_secs_f64()),
+                    });
+                }
+                println!(
+                    "Waiting {:.1}s for Discord rate limit (bucket {})",
+                    wait.as_secs_f64(),
+                    bucket.id.as_deref().unwrap_or("unknown")
+                );
+                tokio::time::sleep(wait).await;
+                waited += wait;
+            }
+
+            let response = build().send().await?;
+            bucket.update(response.headers());
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS || retries >= MAX_RETRIES {
+                return Ok((response, waited));
+            }
+            retries += 1;
+
+            let headers = response.headers().clone();
+            let body: Option<Value> = response.json().await.ok();
+            let retry_after = body
+                .as_ref()
+                .and_then(|b| b["retry_after"].as_f64())
+                .or_else(|| header_str(&headers, RETRY_AFTER.as_str()).and_then(|v| v.parse().ok()))
+                .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+            let global = body
+                .as_ref()
+                .and_then(|b| b["global"].as_bool())
+                .unwrap_or_else(|| header_str(&headers, "x-ratelimit-global").is_some());
+
+            let reset_at = Instant::now() + seconds(retry_after);
+            println!(
+                "Discord answered 429 ({} limit), retrying in {:.1}s",
+                if global { "global" } else { "route" },
+                retry_after
+            );
+            if global {
+                *self.global_reset_at.lock().unwrap() = Some(reset_at);
+            } else {
+                bucket.remaining = Some(0);
+                bucket.reset_at = Some(reset_at);
+            }
+        }
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+}
+
+fn seconds(value: f64) -> Duration {
+    Duration::from_secs_f64(value.max(0.0))
+}
@@ -0,0 +1,240 @@
+use super::{
+    fields_show_changelog, non_empty, replace_id_placeholders, replace_template_variables,
+    truncate_text, ModUpdateData,
+};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+// Block Kit limits, see https://api.slack.com/reference/block-kit/blocks
+pub const SECTION_TEXT_LIMIT: usize = 3000;
+const HEADER_TEXT_LIMIT: usize = 150;
+const SECTION_FIELD_LIMIT: usize = 2000;
+const SECTION_MAX_FIELDS: usize = 10;
+
+/// Escapes the three characters Slack treats as control sequences in message text.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn is_slack_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Renders `{everyone}`, `{here}`, `{&group}` and `{#channel}` as Slack mentions.
+pub fn replace_mentions(text: &str) -> String {
+    let result = text
+        .replace("{everyone}", "<!channel>")
+        .replace("{here}", "<!here>");
+    let result = replace_id_placeholders(&result, "&", is_slack_id, &|id| {
+        format!("<!subteam^{}>", id)
+    });
+    replace_id_placeholders(&result, "#", is_slack_id, &|id| format!("<#{}>", id))
+}
+
+fn color_hex(color: i32) -> String {
+    format!("#{:06X}", color & 0xFF_FFFF)
+}
+
+fn mrkdwn(text: &str) -> Value {
+    json!({ "type": "mrkdwn", "text": text })
+}
+
+fn base_payload(webhook: &Webhook, text: &str) -> Value {
+    let mut payload = json!({
+        "text": text,
+        "username": non_empty(&webhook.username).unwrap_or("Mod Tracker"),
+    });
+
+    if let Some(avatar_url) = non_empty(&webhook.avatar_url) {
+        payload["icon_url"] = json!(avatar_url);
+    }
+
+    payload
+}
+
+pub fn build_test_payload(webhook: &Webhook) -> Value {
+    let mut payload = base_payload(webhook, "🧪 Test Message");
+    payload["attachments"] = json!([{
+        "color": color_hex(5814783),
+        "blocks": [
+            {
+                "type": "header",
+                "text": { "type": "plain_text", "text": "🧪 Test Message", "emoji": true }
+            },
+            {
+                "type": "section",
+                "text": mrkdwn("This is a test message from Arti's CurseForge Mod Tracker!")
+            },
+            {
+                "type": "context",
+                "elements": [mrkdwn("Test completed successfully")]
+            }
+        ]
+    }]);
+    payload
+}
+
+/// Renders the template as Block Kit: a header for the title, an author context,
+/// field sections with the mod logo as accessory and a footer context. The
+/// template color becomes the attachment's side bar.
+pub fn build_update_payload(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let render = |text: &str| replace_template_variables(text, data, WebhookKind::Slack);
+
+    if !template.use_embed {
+        let content = template
+            .content
+            .as_deref()
+            .unwrap_or("🔄 Mod Update Available!");
+        return Ok(base_payload(
+            webhook,
+            &truncate_text(&render(content), SECTION_TEXT_LIMIT),
+        ));
+    }
+
+    let title = render(&template.title);
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": {
+            "type": "plain_text",
+            "text": truncate_text(&title, HEADER_TEXT_LIMIT),
+            "emoji": true
+        }
+    })];
+
+    if let Some(author_name) = non_empty(&template.author_name) {
+        let mut elements = Vec::new();
+        if let Some(icon_url) = non_empty(&template.author_icon_url) {
+            elements.push(json!({
+                "type": "image",
+                "image_url": render(icon_url),
+                "alt_text": "author"
+            }));
+        }
+        elements.push(mrkdwn(&format!("*{}*", render(author_name))));
+        blocks.push(json!({ "type": "context", "elements": elements }));
+    }
+
+    // Inline fields share a two-column section, other fields get a full-width one
+    let mut sections: Vec<Value> = Vec::new();
+    let mut inline_fields: Vec<Value> = Vec::new();
+    for field in serde_json::from_str::<Vec<Value>>(&template.embed_fields)? {
+        let name = render(field["name"].as_str().unwrap_or(""));
+        let value = render(field["value"].as_str().unwrap_or(""));
+        if value.trim().is_empty() {
+            continue;
+        }
+        let text = format!("*{}*\n{}", name, value);
+
+        if field["inline"].as_bool().unwrap_or(false) {
+            inline_fields.push(mrkdwn(&truncate_text(&text, SECTION_FIELD_LIMIT)));
+            if inline_fields.len() == SECTION_MAX_FIELDS {
+                sections.push(json!({ "type": "section", "fields": inline_fields }));
+                inline_fields = Vec::new();
+            }
+        } else {
+            if !inline_fields.is_empty() {
+                sections.push(json!({ "type": "section", "fields": inline_fields }));
+                inline_fields = Vec::new();
+            }
+            sections.push(json!({
+                "type": "section",
+                "text": mrkdwn(&truncate_text(&text, SECTION_TEXT_LIMIT))
+            }));
+        }
+    }
+    if !inline_fields.is_empty() {
+        sections.push(json!({ "type": "section", "fields": inline_fields }));
+    }
+
+    // Add thumbnail if enabled and URL is available
+    if template.use_thumbnail {
+        if let Some(url) = &data.logo_url {
+            if sections.is_empty() {
+                sections.push(json!({
+                    "type": "section",
+                    "text": mrkdwn(&format!("*{}*", escape(&data.mod_name)))
+                }));
+            }
+            sections[0]["accessory"] = json!({
+                "type": "image",
+                "image_url": url,
+                "alt_text": data.mod_name
+            });
+        }
+    }
+    blocks.extend(sections);
+
+    // Only post the changelog on its own when no field already shows it
+    let changelog_in_fields = fields_show_changelog(template);
+    if let Some(changelog) = data.changelog_for(WebhookKind::Slack).as_deref() {
+        if !changelog_in_fields && !changelog.trim().is_empty() {
+            let text = format!("*Changelog*\n{}", escape(changelog.trim()));
+            blocks.push(json!({
+                "type": "section",
+                "text": mrkdwn(&truncate_text(&text, SECTION_TEXT_LIMIT))
+            }));
+        }
+    }
+
+    let mut footer = Vec::new();
+    if let Some(icon_url) = non_empty(&template.footer_icon_url) {
+        footer.push(json!({
+            "type": "image",
+            "image_url": render(icon_url),
+            "alt_text": "footer"
+        }));
+    }
+    let mut footer_text = non_empty(&template.footer_text)
+        .map(render)
+        .unwrap_or_default();
+    if template.include_timestamp {
+        let now = chrono::Utc::now();
+        if !footer_text.is_empty() {
+            footer_text.push_str(" • ");
+        }
+        footer_text.push_str(&format!(
+            "<!date^{}^{{date_short_pretty}} at {{time}}|{}>",
+            now.timestamp(),
+            now.format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+    if !footer_text.is_empty() {
+        footer.push(mrkdwn(&footer_text));
+    }
+    if !footer.is_empty() {
+        blocks.push(json!({ "type": "context", "elements": footer }));
+    }
+
+    let mut payload = base_payload(webhook, &title);
+    payload["attachments"] = json!([{
+        "color": color_hex(template.color),
+        "blocks": blocks
+    }]);
+    Ok(payload)
+}
+
+/// Turns a failed Slack response into a typed error. Slack answers errors with a
+/// short plain-text code such as `invalid_payload` or `channel_is_archived`.
+pub async fn error_from_response(response: reqwest::Response) -> TrackerError {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let error = TrackerError::rate_limited("Slack", response.headers());
+        println!("Slack rate limited the webhook: {}", error);
+        return error;
+    }
+
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    println!("Slack API error: {}", error_text);
+    TrackerError::webhook_delivery("Slack", status, error_text)
+}
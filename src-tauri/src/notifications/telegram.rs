@@ -0,0 +1,328 @@
+use super::{
+    escape_html, fields_show_changelog, non_empty, replace_template_variables, truncate_text,
+    ModUpdateData,
+};
+use crate::database::{webhook_templates::WebhookTemplate, Webhook, WebhookKind};
+use crate::error::TrackerError;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Used when a Telegram webhook's URL is left empty. Pointing the URL somewhere
+/// else lets the channel be exercised against a local Bot API stand-in.
+pub const DEFAULT_API_BASE_URL: &str = "https://api.telegram.org";
+
+pub const CHANGELOG_LIMIT: usize = 3000;
+const MESSAGE_LIMIT: usize = 4096; // sendMessage rejects longer texts
+const CAPTION_LIMIT: usize = 1024; // sendPhoto caption limit
+
+const MARKDOWN_V2_SPECIAL_CHARS: &[char] = &[
+    '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!', '\\',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ParseMode {
+    #[serde(rename = "HTML", alias = "html")]
+    Html,
+    #[serde(rename = "MarkdownV2", alias = "markdownv2")]
+    MarkdownV2,
+}
+
+impl Default for ParseMode {
+    fn default() -> Self {
+        ParseMode::Html
+    }
+}
+
+impl ParseMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParseMode::Html => "HTML",
+            ParseMode::MarkdownV2 => "MarkdownV2",
+        }
+    }
+
+    fn escape(&self, text: &str) -> String {
+        match self {
+            ParseMode::Html => escape_html(text),
+            ParseMode::MarkdownV2 => escape_markdown_v2(text),
+        }
+    }
+
+    // `bold` and `italic` expect text that has already been escaped
+    fn bold(&self, text: &str) -> String {
+        match self {
+            ParseMode::Html => format!("<b>{}</b>", text),
+            ParseMode::MarkdownV2 => format!("*{}*", text),
+        }
+    }
+
+    fn italic(&self, text: &str) -> String {
+        match self {
+            ParseMode::Html => format!("<i>{}</i>", text),
+            ParseMode::MarkdownV2 => format!("_{}_", text),
+        }
+    }
+}
+
+/// The `config` of a Telegram webhook.
+#[derive(Debug, Deserialize)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+    #[serde(default)]
+    pub parse_mode: ParseMode,
+}
+
+impl TelegramConfig {
+    pub fn from_webhook(webhook: &Webhook) -> Result<Self, TrackerError> {
+        let missing =
+            || TrackerError::InvalidInput("Telegram webhooks need a bot token and chat ID".into());
+        let config: TelegramConfig =
+            serde_json::from_value(webhook.config.clone()).map_err(|_| missing())?;
+        if config.bot_token.trim().is_empty() || config.chat_id.trim().is_empty() {
+            return Err(missing());
+        }
+        Ok(config)
+    }
+}
+
+pub fn escape_markdown_v2(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if MARKDOWN_V2_SPECIAL_CHARS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Builds the Bot API method URL for a payload: `sendPhoto` when it carries a
+/// photo, `sendMessage` otherwise.
+pub fn request_url(webhook: &Webhook, payload: &Value) -> Result<String, TrackerError> {
+    let config = TelegramConfig::from_webhook(webhook)?;
+    let base_url = match webhook.url.trim().trim_end_matches('/') {
+        "" => DEFAULT_API_BASE_URL,
+        url => url,
+    };
+    let method = if payload.get("photo").is_some() {
+        "sendPhoto"
+    } else {
+        "sendMessage"
+    };
+    Ok(format!(
+        "{}/bot{}/{}",
+        base_url,
+        config.bot_token.trim(),
+        method
+    ))
+}
+
+// Escapes `text`, shortening it first as far as needed for the escaped text to fit
+// in `max_chars`. Escaping only ever adds characters, so the text is cut before.
+fn escape_within(mode: ParseMode, text: &str, max_chars: usize) -> String {
+    let mut limit = max_chars;
+    loop {
+        let escaped = mode.escape(&truncate_text(text, limit));
+        let length = escaped.chars().count();
+        if length <= max_chars {
+            return escaped;
+        }
+        if limit == 0 {
+            return String::new();
+        }
+        limit = limit.saturating_sub(length - max_chars);
+    }
+}
+
+fn excess(text: &str) -> usize {
+    text.chars().count().saturating_sub(MESSAGE_LIMIT)
+}
+
+pub fn build_test_payload(webhook: &Webhook) -> Result<Value, TrackerError> {
+    let config = TelegramConfig::from_webhook(webhook)?;
+    let mode = config.parse_mode;
+    let text = format!(
+        "{}\n{}",
+        mode.bold(&mode.escape("🧪 Test Message")),
+        mode.escape("This is a test message from Arti's CurseForge Mod Tracker!")
+    );
+
+    Ok(json!({
+        "chat_id": config.chat_id.trim(),
+        "parse_mode": mode.as_str(),
+        "text": text,
+    }))
+}
+
+/// Renders the template as a single Telegram message: the title in bold, one line
+/// per field and the changelog below. With thumbnails enabled the message becomes
+/// the caption of the mod logo, as long as it fits Telegram's caption limit.
+pub fn build_update_payload(
+    webhook: &Webhook,
+    template: &WebhookTemplate,
+    data: &ModUpdateData,
+) -> Result<Value, TrackerError> {
+    let config = TelegramConfig::from_webhook(webhook)?;
+    let mode = config.parse_mode;
+    let render = |text: &str| replace_template_variables(text, data, WebhookKind::Telegram);
+
+    let text = if template.use_embed {
+        let title = render(&template.title);
+        let author = non_empty(&template.author_name)
+            .map(|author| mode.italic(&mode.escape(&render(author))));
+
+        // (escaped name, separator, value) of every field that shows something
+        let mut fields = Vec::new();
+        for field in serde_json::from_str::<Vec<Value>>(&template.embed_fields)? {
+            let value = render(field["value"].as_str().unwrap_or(""));
+            if value.trim().is_empty() {
+                continue;
+            }
+            let separator = if field["inline"].as_bool().unwrap_or(false) {
+                ": "
+            } else {
+                "\n"
+            };
+            let name = mode.bold(&mode.escape(&render(field["name"].as_str().unwrap_or(""))));
+            fields.push((name, separator, value));
+        }
+
+        // Only add the changelog on its own when no field already shows it
+        let changelog = data
+            .changelog_for(WebhookKind::Telegram)
+            .filter(|changelog| !fields_show_changelog(template) && !changelog.trim().is_empty())
+            .map(|changelog| truncate_text(changelog.trim(), CHANGELOG_LIMIT));
+
+        let mut footer = non_empty(&template.footer_text)
+            .map(|footer| mode.escape(&render(footer)))
+            .unwrap_or_default();
+        if template.include_timestamp {
+            if !footer.is_empty() {
+                footer.push_str(&mode.escape(" • "));
+            }
+            footer.push_str(
+                &mode.escape(&chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string()),
+            );
+        }
+
+        let assemble = |title: &str, values: &[String], changelog: Option<&str>| {
+            let mut lines = vec![mode.bold(title)];
+            if let Some(author) = &author {
+                lines.push(author.clone());
+            }
+            lines.push(String::new());
+            for ((name, separator, _), value) in fields.iter().zip(values) {
+                if !value.is_empty() {
+                    lines.push(format!("{}{}{}", name, separator, value));
+                }
+            }
+            if let Some(changelog) = changelog {
+                lines.push(String::new());
+                lines.push(mode.bold(&mode.escape("Changelog")));
+                lines.push(changelog.to_string());
+            }
+            if !footer.is_empty() {
+                lines.push(String::new());
+                lines.push(mode.italic(&footer));
+            }
+            lines.join("\n").trim().to_string()
+        };
+
+        let mut escaped_title = mode.escape(&title);
+        let mut values: Vec<String> = fields
+            .iter()
+            .map(|(_, _, value)| mode.escape(value))
+            .collect();
+        let mut escaped_changelog = changelog.as_deref().map(|changelog| mode.escape(changelog));
+        let mut text = assemble(&escaped_title, &values, escaped_changelog.as_deref());
+
+        // Telegram rejects longer messages outright. The changelog gives way first,
+        // then the longest of the title and field values.
+        if let (Some(changelog), Some(escaped)) = (changelog.as_deref(), escaped_changelog.as_mut())
+        {
+            if excess(&text) > 0 {
+                let length = escaped.chars().count().saturating_sub(excess(&text));
+                *escaped = escape_within(mode, changelog, length);
+                text = assemble(&escaped_title, &values, escaped_changelog.as_deref());
+            }
+        }
+        while excess(&text) > 0 {
+            let (index, length) = std::iter::once(&escaped_title)
+                .chain(values.iter())
+                .map(|text| text.chars().count())
+                .enumerate()
+                .max_by_key(|&(_, length)| length)
+                .unwrap_or((0, 0));
+            if length == 0 {
+                break;
+            }
+            let length = length.saturating_sub(excess(&text));
+            if index == 0 {
+                escaped_title = escape_within(mode, &title, length);
+            } else {
+                values[index - 1] = escape_within(mode, &fields[index - 1].2, length);
+            }
+            text = assemble(&escaped_title, &values, escaped_changelog.as_deref());
+        }
+
+        text
+    } else {
+        let content = template
+            .content
+            .as_deref()
+            .unwrap_or("🔄 Mod Update Available!");
+        escape_within(mode, &render(content), MESSAGE_LIMIT)
+    };
+
+    let mut payload = json!({
+        "chat_id": config.chat_id.trim(),
+        "parse_mode": mode.as_str(),
+    });
+
+    let logo_url = if template.use_embed && template.use_thumbnail {
+        data.logo_url.as_deref()
+    } else {
+        None
+    };
+    match logo_url {
+        Some(url) if text.chars().count() <= CAPTION_LIMIT => {
+            payload["photo"] = json!(url);
+            payload["caption"] = json!(text);
+        }
+        _ => payload["text"] = json!(text),
+    }
+
+    Ok(payload)
+}
+
+/// Turns a failed Bot API response into a typed error. Telegram reports the
+/// reason in `description` and the rate-limit wait in `parameters.retry_after`.
+pub async fn error_from_response(response: reqwest::Response) -> TrackerError {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body: Option<Value> = response.json().await.ok();
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let mut error = TrackerError::rate_limited("Telegram", &headers);
+        if let TrackerError::RateLimited { retry_after, .. } = &mut error {
+            if let Some(seconds) = body
+                .as_ref()
+                .and_then(|b| b["parameters"]["retry_after"].as_f64())
+            {
+                *retry_after = Some(seconds);
+            }
+        }
+        println!("Telegram rate limited the bot: {}", error);
+        return error;
+    }
+
+    let description = body
+        .as_ref()
+        .and_then(|b| b["description"].as_str())
+        .unwrap_or("Unknown error")
+        .to_string();
+    println!("Telegram API error: {}", description);
+    TrackerError::webhook_delivery("Telegram", status, description)
+}
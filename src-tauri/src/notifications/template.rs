@@ -0,0 +1,319 @@
+use super::{truncate_text, ModUpdateData};
+use crate::database::webhook_templates::WebhookTemplate;
+use crate::error::TrackerError;
+use chrono::format::{Item, StrftimeItems};
+use chrono::DateTime;
+use handlebars::{handlebars_helper, Handlebars, Template};
+use serde_json::{json, Value};
+
+// Renders a helper argument the way `{{value}}` would: strings as they are,
+// nothing for null and numbers or booleans as text
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+handlebars_helper!(upper: |value: Json| text(value).to_uppercase());
+handlebars_helper!(lower: |value: Json| text(value).to_lowercase());
+handlebars_helper!(truncate: |value: Json, max_chars: u64| {
+    truncate_text(&text(value), max_chars as usize)
+});
+handlebars_helper!(join: |value: Json, separator: str| match value {
+    Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(separator),
+    other => text(other),
+});
+handlebars_helper!(default: |value: Json, fallback: str| {
+    let value = text(value);
+    if value.trim().is_empty() {
+        fallback.to_string()
+    } else {
+        value
+    }
+});
+// Formats an RFC 3339 date with a chrono format string, e.g. `{{date releasedAt "%Y-%m-%d"}}`
+handlebars_helper!(date: |value: Json, format: str| {
+    let value = text(value);
+    // chrono panics when asked to print an invalid format, so those are left alone
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    match DateTime::parse_from_rfc3339(&value) {
+        Ok(parsed) if !items.contains(&Item::Error) => {
+            parsed.format_with_items(items.into_iter()).to_string()
+        }
+        _ => value,
+    }
+});
+
+fn registry(escape: fn(&str) -> String) -> Handlebars<'static> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(escape);
+    handlebars.register_helper("upper", Box::new(upper));
+    handlebars.register_helper("lower", Box::new(lower));
+    handlebars.register_helper("truncate", Box::new(truncate));
+    handlebars.register_helper("join", Box::new(join));
+    handlebars.register_helper("default", Box::new(default));
+    handlebars.register_helper("date", Box::new(date));
+    handlebars
+}
+
+/// Whether `text` uses the template engine at all. Plain `{modName}` templates skip it.
+pub fn is_template(text: &str) -> bool {
+    text.contains("{{")
+}
+
+// `changelog` comes converted and shortened for the service the text is sent to, and
+// is `None` when there is none so `{{#if changelog}}` can test for it
+fn context(data: &ModUpdateData, changelog: Option<&str>) -> Value {
+    json!({
+        "modID": data.curseforge_id,
+        "modDatabaseID": data.mod_id,
+        "modName": data.mod_name,
+        "modAuthorName": data.mod_author,
+        "authors": data.authors,
+        "newReleaseDate": data.new_release_date,
+        "oldPreviousDate": data.old_release_date,
+        "releasedAt": data.released_at,
+        "latestModFileName": data.latest_file_name,
+        "lastestModFileName": data.latest_file_name,
+        "logoUrl": data.logo_url,
+        "modURL": data.page_url,
+        "changelog": changelog,
+        "fileID": data.file_id,
+        "fileDisplayName": data.file_display_name,
+        "fileDate": data.file_date,
+        "fileReleaseType": data.release_type,
+        "fileGameVersions": data.game_versions.join(", "),
+        "gameVersions": data.game_versions,
+        "files": data.files.iter().map(|file| json!({
+            "id": file.id,
+            "displayName": file.display_name,
+            "fileName": file.file_name,
+            "fileDate": file.file_date,
+            "releaseType": file.release_type,
+            "gameVersions": file.game_versions,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn sample_context() -> Value {
+    let sample = ModUpdateData::sample();
+    context(&sample, sample.changelog.as_deref())
+}
+
+/// Runs the template engine over `text`, escaping every inserted value with `escape`.
+pub fn render(
+    text: &str,
+    data: &ModUpdateData,
+    escape: fn(&str) -> String,
+    changelog: Option<&str>,
+) -> Result<String, TrackerError> {
+    if !is_template(text) {
+        return Ok(text.to_string());
+    }
+
+    registry(escape)
+        .render_template(text, &context(data, changelog))
+        .map_err(|e| TrackerError::InvalidInput(format!("Template error: {}", e)))
+}
+
+/// Checks a single template string; `label` names it in the error message.
+pub fn validate_text(label: &str, text: &str) -> Result<(), TrackerError> {
+    if !is_template(text) {
+        return Ok(());
+    }
+
+    let invalid =
+        |e: String| TrackerError::InvalidInput(format!("Invalid template in {}: {}", label, e));
+    Template::compile(text).map_err(|e| invalid(e.to_string()))?;
+    // Rendering sample data also catches helpers called with the wrong arguments
+    registry(str::to_string)
+        .render_template(text, &sample_context())
+        .map(|_| ())
+        .map_err(|e| invalid(e.to_string()))
+}
+
+// Every templated string of `template`, with the label its messages name it by
+fn texts(template: &WebhookTemplate) -> Result<Vec<(String, String)>, TrackerError> {
+    let mut texts = vec![("the title".to_string(), template.title.clone())];
+
+    let optional = [
+        ("the content", &template.content),
+        ("the author name", &template.author_name),
+        ("the author icon URL", &template.author_icon_url),
+        ("the footer text", &template.footer_text),
+        ("the footer icon URL", &template.footer_icon_url),
+    ];
+    for (label, text) in optional.iter() {
+        if let Some(text) = text {
+            texts.push((label.to_string(), text.clone()));
+        }
+    }
+
+    let fields: Vec<Value> = serde_json::from_str(&template.embed_fields)?;
+    for (index, field) in fields.iter().enumerate() {
+        texts.push((
+            format!("the name of field {}", index + 1),
+            field["name"].as_str().unwrap_or("").to_string(),
+        ));
+        texts.push((
+            format!("the value of field {}", index + 1),
+            field["value"].as_str().unwrap_or("").to_string(),
+        ));
+    }
+
+    Ok(texts)
+}
+
+/// Compiles every templated string of `template`, so syntax errors are reported
+/// when the template is saved rather than when an update is sent.
+pub fn validate(template: &WebhookTemplate) -> Result<(), TrackerError> {
+    for (label, text) in texts(template)? {
+        validate_text(&label, &text)?;
+    }
+
+    Ok(())
+}
+
+// The `{placeholder}` names `replace_template_variables` fills in, plus the mentions
+const PLACEHOLDERS: &[&str] = &[
+    "modID",
+    "modDatabaseID",
+    "modName",
+    "newReleaseDate",
+    "oldPreviousDate",
+    "lastestModFileName",
+    "modAuthorName",
+    "logoUrl",
+    "modURL",
+    "changelog",
+    "fileID",
+    "fileDisplayName",
+    "fileDate",
+    "fileReleaseType",
+    "fileGameVersions",
+    "everyone",
+    "here",
+];
+
+const KEYWORDS: &[&str] = &[
+    "if", "unless", "each", "with", "else", "this", "lookup", "log", "true", "false", "null",
+    "upper", "lower", "truncate", "join", "default", "date",
+];
+
+// The variables an expression reads, leaving out helpers, literals and `@index`-style
+// data variables
+fn expression_names(expression: &str) -> Vec<String> {
+    let body = expression.trim_start_matches(|c: char| c == '{' || c == '~');
+    if body.starts_with('!') {
+        return Vec::new();
+    }
+    let body = body.trim_start_matches(|c: char| matches!(c, '#' | '/' | '^' | '&' | '>'));
+    // Block parameters (`as |file|`) only name locals
+    let body = body.split('|').next().unwrap_or("");
+
+    let mut unquoted = String::with_capacity(body.len());
+    let mut quote = None;
+    for c in body.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None => unquoted.push(c),
+        }
+    }
+
+    unquoted
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '~' || c == '}')
+        .map(|token| token.rsplit('=').next().unwrap_or(token))
+        .map(|token| token.trim_start_matches("../").trim_start_matches("./"))
+        .map(|token| {
+            token
+                .split(|c: char| c == '.' || c == '/')
+                .next()
+                .unwrap_or("")
+        })
+        .filter(|name| !name.is_empty() && !KEYWORDS.contains(name))
+        .filter(|name| name.chars().all(|c| c.is_alphanumeric() || c == '_'))
+        .filter(|name| !name.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lists the variables and `{placeholders}` in `template` that nothing fills in,
+/// most likely typos, as one warning each.
+pub fn unknown_variables(template: &WebhookTemplate) -> Result<Vec<String>, TrackerError> {
+    let sample = sample_context();
+    let mut variables: Vec<&str> = sample
+        .as_object()
+        .map(|object| object.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    // Inside `{{#each files}}` the names refer to a file's properties
+    if let Some(file) = sample["files"][0].as_object() {
+        variables.extend(file.keys().map(String::as_str));
+    }
+
+    let mut warnings = Vec::new();
+    for (label, text) in texts(template)? {
+        let mut unknown = Vec::new();
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find('{') {
+            let after = &rest[start + 1..];
+            if after.starts_with('{') {
+                let end = after.find("}}").unwrap_or(after.len());
+                for name in expression_names(&after[1..end]) {
+                    if !variables.contains(&name.as_str()) {
+                        unknown.push(format!("{{{{{}}}}}", name));
+                    }
+                }
+                rest = after.get(end + 2..).unwrap_or("");
+                continue;
+            }
+
+            if let Some(end) = after.find('}') {
+                let name = &after[..end];
+                let is_placeholder =
+                    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+                if is_placeholder && !PLACEHOLDERS.contains(&name) {
+                    unknown.push(format!("{{{}}}", name));
+                }
+            }
+            rest = after;
+        }
+
+        unknown.sort();
+        unknown.dedup();
+        warnings.extend(
+            unknown
+                .into_iter()
+                .map(|name| format!("Unknown variable {} in {}", name, label)),
+        );
+    }
+
+    Ok(warnings)
+}
+
+/// Whether a template string shows the changelog, through `{changelog}` or any
+/// template expression that references it.
+pub fn references(text: &str, variable: &str) -> bool {
+    if text.contains(&format!("{{{}}}", variable)) {
+        return true;
+    }
+
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after.find("}}").unwrap_or(after.len());
+        let is_reference = after[..end]
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .any(|token| token == variable);
+        if is_reference {
+            return true;
+        }
+        rest = &after[end..];
+    }
+
+    false
+}
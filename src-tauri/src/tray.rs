@@ -1,5 +1,4 @@
-use crate::database::{get_database_path, get_minimize_to_tray};
-use rusqlite::Connection;
+use crate::database::{get_connection, get_minimize_to_tray};
 use tauri::{
     AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
     SystemTrayMenuItem, WindowEvent,
@@ -50,8 +49,7 @@ pub fn handle_window_event(app: &AppHandle, event: WindowEvent) {
         WindowEvent::Resized(position) => {
             // Check if window is minimized
             if position.width == 0 && position.height == 0 {
-                let db_path = get_database_path(app);
-                let conn = Connection::open(&db_path).unwrap();
+                let conn = get_connection(app).unwrap();
 
                 if let Ok(minimize_to_tray) = get_minimize_to_tray(&conn) {
                     if minimize_to_tray {
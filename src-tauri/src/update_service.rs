@@ -0,0 +1,417 @@
+use crate::commands::mod_commands::check_mods_for_updates;
+use crate::commands::webhook_commands::{deliver_due_digest, deliver_outbox_entry};
+use crate::curseforge::CurseForgeClient;
+use crate::database::{get_api_key, get_connection, get_update_interval, mods, outbox, webhooks};
+use crate::error::TrackerError;
+use crate::notifications;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+// Longest the outbox worker sleeps before looking for due deliveries again
+const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+// How often webhooks in digest mode are checked for a due digest. Discord digest
+// windows can be as short as a few minutes, so this runs every minute
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateServiceStatus {
+    pub running: bool,
+    pub paused: bool,
+    pub is_checking: bool,
+    pub interval: i64,
+    pub last_checked: Option<DateTime<Utc>>,
+    pub next_check_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheckStarted {
+    pub timestamp: DateTime<Utc>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheckProgress {
+    pub checked: usize,
+    pub total: usize,
+    pub mod_id: i64,
+    pub mod_name: String,
+    pub updated: bool,
+    pub error: Option<TrackerError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheckCompleted {
+    pub timestamp: DateTime<Utc>,
+    pub interval: i64,
+    pub checked: usize,
+    pub updates_found: usize,
+    pub failed: usize,
+    pub error: Option<TrackerError>,
+}
+
+#[derive(Debug, Default)]
+struct Schedule {
+    interval: i64,
+    last_checked: Option<DateTime<Utc>>,
+    next_check_time: Option<DateTime<Utc>>,
+}
+
+/// Background scheduler that periodically checks every tracked mod for updates
+/// and dispatches notifications to the assigned webhooks. Managed as Tauri state.
+pub struct UpdateService {
+    running: AtomicBool,
+    paused: AtomicBool,
+    checking: AtomicBool,
+    check_now: Notify,
+    reschedule: Notify,
+    outbox_wakeup: Notify,
+    schedule: Mutex<Schedule>,
+}
+
+impl UpdateService {
+    pub fn new() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            checking: AtomicBool::new(false),
+            check_now: Notify::new(),
+            reschedule: Notify::new(),
+            outbox_wakeup: Notify::new(),
+            schedule: Mutex::new(Schedule::default()),
+        }
+    }
+
+    pub fn status(&self) -> UpdateServiceStatus {
+        let schedule = self.schedule.lock().unwrap();
+        UpdateServiceStatus {
+            running: self.running.load(Ordering::SeqCst),
+            paused: self.paused.load(Ordering::SeqCst),
+            is_checking: self.checking.load(Ordering::SeqCst),
+            interval: schedule.interval,
+            last_checked: schedule.last_checked,
+            next_check_time: schedule.next_check_time,
+        }
+    }
+
+    pub fn is_checking(&self) -> bool {
+        self.checking.load(Ordering::SeqCst)
+    }
+}
+
+/// Spawns the scheduler loop, the outbox worker and the digest loop. Calling
+/// this again while they are running is a no-op.
+pub fn start(app_handle: &AppHandle) {
+    let service = app_handle.state::<UpdateService>();
+    if service.running.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        run_scheduler(handle).await;
+    });
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        run_outbox_worker(handle).await;
+    });
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        run_digest_scheduler(handle).await;
+    });
+}
+
+pub fn pause(app_handle: &AppHandle) {
+    let service = app_handle.state::<UpdateService>();
+    service.paused.store(true, Ordering::SeqCst);
+    service.reschedule.notify_one();
+}
+
+pub fn resume(app_handle: &AppHandle) {
+    let service = app_handle.state::<UpdateService>();
+    service.paused.store(false, Ordering::SeqCst);
+    service.reschedule.notify_one();
+}
+
+/// Wakes the outbox worker to deliver newly queued or re-sent notifications and
+/// tells the frontend the outbox changed.
+pub fn wake_outbox(app_handle: &AppHandle) {
+    app_handle.state::<UpdateService>().outbox_wakeup.notify_one();
+    emit_outbox_updated(app_handle);
+}
+
+pub fn emit_outbox_updated(app_handle: &AppHandle) {
+    app_handle
+        .emit_all("outbox_updated", ())
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to emit outbox updated event: {}", e);
+        });
+}
+
+/// Wakes the scheduler so it re-reads the update interval and restarts its timer.
+pub fn reschedule(app_handle: &AppHandle) {
+    app_handle.state::<UpdateService>().reschedule.notify_one();
+}
+
+/// Asks the scheduler to run a check immediately, even while paused.
+pub fn check_now(app_handle: &AppHandle) -> Result<(), TrackerError> {
+    let service = app_handle.state::<UpdateService>();
+    if service.is_checking() {
+        return Err(TrackerError::UpdateCheckInProgress);
+    }
+    service.check_now.notify_one();
+    Ok(())
+}
+
+fn emit_status(app_handle: &AppHandle) {
+    let status = app_handle.state::<UpdateService>().status();
+    app_handle
+        .emit_all("update_service_status", &status)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to emit update service status event: {}", e);
+        });
+}
+
+fn read_update_interval(app_handle: &AppHandle) -> i64 {
+    get_connection(app_handle)
+        .and_then(|conn| Ok(get_update_interval(&conn)?))
+        .unwrap_or_else(|e| {
+            println!("Failed to read update interval, using default: {}", e);
+            30
+        })
+        .max(1)
+}
+
+async fn run_scheduler(app_handle: AppHandle) {
+    let service = app_handle.state::<UpdateService>();
+    println!("Update service started");
+
+    // Check once at startup, then on every interval tick
+    let mut run_check = !service.paused.load(Ordering::SeqCst);
+
+    loop {
+        if run_check {
+            service.checking.store(true, Ordering::SeqCst);
+            emit_status(&app_handle);
+
+            let summary = run_update_check(&app_handle).await;
+
+            service.checking.store(false, Ordering::SeqCst);
+            service.schedule.lock().unwrap().last_checked = Some(summary.timestamp);
+            app_handle
+                .emit_all("update_check_completed", &summary)
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to emit update check completed event: {}", e);
+                });
+        }
+
+        let interval = read_update_interval(&app_handle);
+        let paused = service.paused.load(Ordering::SeqCst);
+        {
+            let mut schedule = service.schedule.lock().unwrap();
+            schedule.interval = interval;
+            schedule.next_check_time = if paused {
+                None
+            } else {
+                Some(Utc::now() + chrono::Duration::minutes(interval))
+            };
+        }
+        emit_status(&app_handle);
+
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(interval as u64 * 60)) => {
+                run_check = !service.paused.load(Ordering::SeqCst);
+            }
+            _ = service.check_now.notified() => {
+                run_check = true;
+            }
+            _ = service.reschedule.notified() => {
+                run_check = false;
+            }
+        }
+    }
+}
+
+/// Runs one full update check over all tracked mods, queueing notifications for the
+/// enabled webhooks of every mod that changed and emitting progress events.
+pub async fn run_update_check(app_handle: &AppHandle) -> UpdateCheckCompleted {
+    let started = Utc::now();
+    let interval = read_update_interval(app_handle);
+
+    let mut summary = UpdateCheckCompleted {
+        timestamp: started,
+        interval,
+        checked: 0,
+        updates_found: 0,
+        failed: 0,
+        error: None,
+    };
+
+    let loaded = get_connection(app_handle).and_then(|conn| {
+        let api_key = get_api_key(&conn)?;
+        let tracked_mods = mods::get_all_mods(&conn)?;
+        Ok((api_key, tracked_mods))
+    });
+
+    let (api_key, tracked_mods) = match loaded {
+        Ok((Some(api_key), tracked_mods)) if !api_key.trim().is_empty() => (api_key, tracked_mods),
+        Ok(_) => {
+            println!("Skipping update check: no API key configured");
+            summary.error = Some(TrackerError::MissingApiKey);
+            return summary;
+        }
+        Err(e) => {
+            println!("Skipping update check: {}", e);
+            summary.error = Some(e);
+            return summary;
+        }
+    };
+
+    let total = tracked_mods.len();
+    println!("Starting update check for {} mods", total);
+    app_handle
+        .emit_all(
+            "update_check_started",
+            &UpdateCheckStarted {
+                timestamp: started,
+                total,
+            },
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to emit update check started event: {}", e);
+        });
+
+    let client = app_handle.state::<CurseForgeClient>().with_api_key(&api_key);
+    let tracked_mods = tracked_mods.into_iter().map(|m| m.mod_info).collect();
+    let results = check_mods_for_updates(app_handle, &client, tracked_mods).await;
+
+    for check in results {
+        summary.checked += 1;
+
+        let (updated, error) = match check.result {
+            // Notifications were queued in the outbox when the update was recorded
+            Ok(Some(update)) => {
+                println!("Mod \"{}\" updated", update.name);
+                summary.updates_found += 1;
+                (true, None)
+            }
+            Ok(None) => (false, None),
+            Err(e) => {
+                println!("Error checking mod {}: {}", check.mod_id, e);
+                summary.failed += 1;
+                (false, Some(e))
+            }
+        };
+
+        app_handle
+            .emit_all(
+                "update_check_progress",
+                &UpdateCheckProgress {
+                    checked: summary.checked,
+                    total,
+                    mod_id: check.mod_id,
+                    mod_name: check.mod_name,
+                    updated,
+                    error,
+                },
+            )
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to emit update check progress event: {}", e);
+            });
+    }
+
+    summary.timestamp = Utc::now();
+    println!(
+        "Update check finished: {} checked, {} updated, {} failed",
+        summary.checked, summary.updates_found, summary.failed
+    );
+    summary
+}
+
+// Delivers whatever is due in the outbox, then sleeps until the next retry is due,
+// new notifications are queued or a failed delivery is re-sent
+async fn run_outbox_worker(app_handle: AppHandle) {
+    let service = app_handle.state::<UpdateService>();
+
+    loop {
+        deliver_due_outbox_entries(&app_handle).await;
+
+        let next_attempt_at = get_connection(&app_handle)
+            .and_then(|conn| Ok(outbox::get_next_attempt_at(&conn)?))
+            .unwrap_or_else(|e| {
+                println!("Failed to read the outbox: {}", e);
+                None
+            });
+        let wait = next_attempt_at
+            .and_then(|next| (next - Utc::now()).to_std().ok())
+            .map_or(OUTBOX_POLL_INTERVAL, |wait| wait.min(OUTBOX_POLL_INTERVAL))
+            .max(Duration::from_secs(1));
+
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = service.outbox_wakeup.notified() => {}
+        }
+    }
+}
+
+async fn deliver_due_outbox_entries(app_handle: &AppHandle) {
+    let due = match get_connection(app_handle)
+        .and_then(|conn| Ok(outbox::get_due_entries(&conn, Utc::now())?))
+    {
+        Ok(due) => due,
+        Err(e) => {
+            println!("Failed to load due outbox entries: {}", e);
+            return;
+        }
+    };
+
+    // Discord posts are paced by the shared rate limiter, and other services that
+    // answer 429 get their delivery rescheduled for when they allow it again
+    for entry in due {
+        if let Err(e) = deliver_outbox_entry(app_handle, &entry).await {
+            println!(
+                "Failed to send notification for \"{}\" to \"{}\" (attempt {}): {}",
+                entry.mod_name,
+                entry.webhook_name,
+                entry.attempts + 1,
+                e
+            );
+        }
+    }
+}
+
+// Digests go out even while update checks are paused, since the queued updates
+// were already found
+async fn run_digest_scheduler(app_handle: AppHandle) {
+    loop {
+        send_due_digests(&app_handle).await;
+        tokio::time::sleep(DIGEST_CHECK_INTERVAL).await;
+    }
+}
+
+async fn send_due_digests(app_handle: &AppHandle) {
+    let all_webhooks =
+        match get_connection(app_handle).and_then(|conn| Ok(webhooks::get_all_webhooks(&conn)?)) {
+            Ok(all_webhooks) => all_webhooks,
+            Err(e) => {
+                println!("Failed to load webhooks for digests: {}", e);
+                return;
+            }
+        };
+
+    for webhook in all_webhooks
+        .iter()
+        .filter(|w| w.enabled && notifications::uses_digest(w))
+    {
+        if let Err(e) = deliver_due_digest(app_handle, webhook).await {
+            println!("Failed to send digest to \"{}\": {}", webhook.name, e);
+        }
+    }
+}
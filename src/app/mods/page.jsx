@@ -3,14 +3,15 @@
 import { useState, useEffect, useMemo } from "react";
 import { Button, Tooltip, CircularProgress, ScrollShadow, Card, CardHeader, Chip, Input } from "@nextui-org/react";
 import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
 import { Plus, Settings as SettingsIcon, AlertTriangle, RefreshCw, Gamepad2, Search } from "lucide-react";
 import { AnimatePresence, motion } from "framer-motion";
 
 import ModCard from "@/components/ModCard";
 import AddModModal from "@/components/AddModModal";
 import WebhookAssignModal from "@/components/WebhookAssignModal";
+import ModFiltersModal from "@/components/ModFiltersModal";
 import SettingsModal from "@/components/Settings";
-import { useModUpdateChecker } from "@/hooks/useModUpdateChecker";
 
 const fadeInUp = {
   initial: { opacity: 0, y: 20 },
@@ -26,8 +27,8 @@ export default function ModsPage() {
   const [isSettingsOpen, setIsSettingsOpen] = useState(false);
   const [isWebhookModalOpen, setIsWebhookModalOpen] = useState(false);
   const [selectedModId, setSelectedModId] = useState(null);
+  const [filterMod, setFilterMod] = useState(null);
   const [searchQuery, setSearchQuery] = useState("");
-  const { isChecking, checkForUpdates } = useModUpdateChecker();
 
   // Filter and group mods based on search query
   const groupedMods = useMemo(() => {
@@ -62,6 +63,16 @@ export default function ModsPage() {
     loadMods();
   }, []);
 
+  // Reload when a backup is restored from the settings
+  useEffect(() => {
+    const unlisten = listen("backup_imported", () => {
+      loadMods();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const loadMods = async () => {
     try {
       setIsLoading(true);
@@ -91,21 +102,20 @@ export default function ModsPage() {
     }
   };
 
-  const handleModUpdate = async (modId, curseforgeId, lastUpdated) => {
+  const handleModUpdate = async (modId) => {
     try {
       const apiKey = await invoke("get_api_key");
-      await invoke("check_mod_update", {
-        modId,
-        curseforgeId,
-        currentLastUpdated: lastUpdated,
-        apiKey,
-      });
+      await invoke("check_mod_update", { modId, apiKey });
       await loadMods();
     } catch (error) {
       console.error("Failed to check for updates:", error);
     }
   };
 
+  const handleFiltersSaved = (modId, filters) => {
+    setMods((prevMods) => prevMods.map((mod) => ((mod.mod_info ? mod.mod_info.id : mod.id) === modId ? { ...mod, filters } : mod)));
+  };
+
   const openWebhookModal = (modId) => {
     setSelectedModId(modId);
     setIsWebhookModalOpen(true);
@@ -232,7 +242,7 @@ export default function ModsPage() {
                         <div className="px-4 pb-4 space-y-4">
                           {gameMods.map((mod, index) => (
                             <motion.div key={mod.id} initial={{ opacity: 0, y: 20 }} animate={{ opacity: 1, y: 0 }} transition={{ duration: 0.2, delay: index * 0.05 }}>
-                              <ModCard mod={mod} onDelete={handleDeleteMod} onUpdate={() => handleModUpdate(mod.id, mod.curseforge_id, mod.last_updated)} onManageWebhooks={openWebhookModal} />
+                              <ModCard mod={mod} onDelete={handleDeleteMod} onUpdate={() => handleModUpdate(mod.id)} onManageWebhooks={openWebhookModal} onManageFilters={setFilterMod} />
                             </motion.div>
                           ))}
                         </div>
@@ -267,6 +277,8 @@ export default function ModsPage() {
         }}
         modId={selectedModId}
       />
+
+      <ModFiltersModal isOpen={!!filterMod} onClose={() => setFilterMod(null)} mod={filterMod} onSaved={handleFiltersSaved} />
     </div>
   );
 }
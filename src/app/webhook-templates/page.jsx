@@ -1,11 +1,14 @@
 "use client";
 
-import { useState, useEffect, useCallback, memo } from "react";
-import { Card, CardBody, Button, CircularProgress, Tabs, Tab, Select, SelectItem, ScrollShadow } from "@nextui-org/react";
+import { useState, useEffect, useCallback, useMemo, memo } from "react";
+import { Card, CardBody, Button, CircularProgress, Select, SelectItem, ScrollShadow, Tooltip, Modal, ModalContent, ModalHeader, ModalBody, ModalFooter, Input } from "@nextui-org/react";
 import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
 import { motion, AnimatePresence } from "framer-motion";
 import WebhookEditor from "@/components/WebhookEditor";
-import { MessageSquare, AlertTriangle, RefreshCw, FileCode, Bot } from "lucide-react";
+import DeleteConfirmationModal from "@/components/DeleteConfirmationModal";
+import { MessageSquare, AlertTriangle, RefreshCw, FileCode, Plus, Copy, Pencil, Trash2 } from "lucide-react";
+import { getErrorCode, getErrorMessage } from "@/utils/errors";
 
 const fadeInUp = {
   initial: { opacity: 0, y: 20 },
@@ -13,56 +16,128 @@ const fadeInUp = {
   exit: { opacity: 0, y: -20 },
 };
 
-const getTemplateForWebhook = async (webhookId) => {
-  try {
-    const template = await invoke("get_webhook_template", { webhookId });
-    return template;
-  } catch (error) {
-    console.error("Failed to get webhook template:", error);
-    return null;
-  }
+// Asks for the name of a new, duplicated or renamed template
+const TemplateNameModal = ({ action, onClose, onSubmit }) => {
+  const [name, setName] = useState("");
+  const [error, setError] = useState("");
+  const [isSaving, setIsSaving] = useState(false);
+
+  useEffect(() => {
+    setName(action?.name || "");
+    setError("");
+  }, [action]);
+
+  const handleSubmit = async () => {
+    if (!name.trim()) {
+      setError("Template name cannot be empty");
+      return;
+    }
+
+    try {
+      setIsSaving(true);
+      await onSubmit(name.trim());
+      onClose();
+    } catch (error) {
+      console.error("Failed to save template name:", error);
+      setError(getErrorCode(error) === "template_name_taken" ? "A template with this name already exists" : getErrorMessage(error));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Modal isOpen={!!action} onClose={onClose} size="sm">
+      <ModalContent>
+        <ModalHeader>{action?.title}</ModalHeader>
+        <ModalBody>
+          <Input
+            label="Template Name"
+            value={name}
+            onChange={(e) => {
+              setName(e.target.value);
+              setError("");
+            }}
+            onKeyDown={(e) => e.key === "Enter" && handleSubmit()}
+            isInvalid={!!error}
+            errorMessage={error}
+            autoFocus
+          />
+        </ModalBody>
+        <ModalFooter>
+          <Button variant="light" onPress={onClose}>
+            Cancel
+          </Button>
+          <Button color="primary" onPress={handleSubmit} isLoading={isSaving}>
+            Save
+          </Button>
+        </ModalFooter>
+      </ModalContent>
+    </Modal>
+  );
 };
 
-// Memoize the WebhookSelector component
-const WebhookSelector = memo(({ webhooks, selectedWebhook, onSelect }) => (
+// Memoize the TemplateSelector component
+const TemplateSelector = memo(({ templates, usage, selectedTemplate, onSelect, onDuplicate, onRename, onDelete }) => (
   <Card className="bg-content1/50 backdrop-blur-md">
-    <CardBody>
+    <CardBody className="flex flex-row items-center gap-2">
       <Select
-        label="Select Webhook"
-        placeholder="Choose a webhook to customize"
-        selectedKeys={selectedWebhook ? [selectedWebhook.id.toString()] : []}
-        onChange={(e) => onSelect(e.target.value)}
+        label="Select Template"
+        placeholder="Choose a template to edit"
+        selectedKeys={selectedTemplate ? [selectedTemplate.id.toString()] : []}
+        onChange={(e) => e.target.value && onSelect(e.target.value)}
         classNames={{
           trigger: "bg-content1",
         }}
       >
-        {webhooks.map((webhook) => (
-          <SelectItem key={webhook.id.toString()} value={webhook.id} textValue={webhook.name}>
+        {templates.map((template) => (
+          <SelectItem key={template.id.toString()} value={template.id} textValue={template.name}>
             <div className="flex justify-between items-center gap-2">
-              <span>{webhook.name}</span>
+              <span>{template.name}</span>
               <div className="flex items-center gap-2 text-default-500">
                 <MessageSquare size={14} />
-                <span className="text-xs">{webhook.use_custom_template ? "Custom" : "Default"}</span>
+                <span className="text-xs">{template.is_default ? "Default" : `${(usage[template.id] || []).length} webhooks`}</span>
               </div>
             </div>
           </SelectItem>
         ))}
       </Select>
+      <Tooltip content="Duplicate Template">
+        <Button isIconOnly variant="flat" onPress={onDuplicate} isDisabled={!selectedTemplate}>
+          <Copy size={18} />
+        </Button>
+      </Tooltip>
+      <Tooltip content="Rename Template">
+        <Button isIconOnly variant="flat" onPress={onRename} isDisabled={!selectedTemplate}>
+          <Pencil size={18} />
+        </Button>
+      </Tooltip>
+      <Tooltip content="Delete Template">
+        <Button isIconOnly variant="flat" color="danger" onPress={onDelete} isDisabled={!selectedTemplate || selectedTemplate.is_default}>
+          <Trash2 size={18} />
+        </Button>
+      </Tooltip>
     </CardBody>
   </Card>
 ));
 
-WebhookSelector.displayName = "WebhookSelector";
+TemplateSelector.displayName = "TemplateSelector";
 
 // Memoize the WebhookEditorWrapper component
-const WebhookEditorWrapper = memo(({ webhook, template, onSave, isDefault }) => (
+const WebhookEditorWrapper = memo(({ template, webhooks, onSave }) => (
   <Card className="bg-content1/50 backdrop-blur-md">
     <CardBody className="gap-4">
       <div className="space-y-2">
-        <h2 className="text-xl font-bold">{isDefault ? "Default Template" : `Template for ${webhook.name}`}</h2>
-        <p className="text-sm text-default-500">{isDefault ? "This template will be used for all webhooks unless they have a custom template enabled." : "Customize how notifications appear for this specific webhook."}</p>
+        <h2 className="text-xl font-bold">{template.name}</h2>
+        <p className="text-sm text-default-500">
+          {template.is_default
+            ? "Used by every webhook that has no other template selected."
+            : webhooks.length > 0
+            ? `Used by ${webhooks.map((webhook) => webhook.name).join(", ")}. Changes apply to all of them.`
+            : "Not used by any webhook yet. Choose it for a webhook on the Webhooks page."}
+        </p>
       </div>
-      <WebhookEditor key={isDefault ? "default-template" : `webhook-${webhook?.id}`} webhook={webhook} template={template} onSave={onSave} isDefault={isDefault} />
+      {/* The preview renders for the first webhook using the template */}
+      <WebhookEditor key={`template-${template.id}`} webhook={webhooks[0] || null} template={template} onSave={onSave} />
     </CardBody>
   </Card>
 ));
@@ -70,21 +145,22 @@ const WebhookEditorWrapper = memo(({ webhook, template, onSave, isDefault }) =>
 WebhookEditorWrapper.displayName = "WebhookEditorWrapper";
 
 export default function WebhookTemplatesPage() {
+  const [templates, setTemplates] = useState([]);
   const [webhooks, setWebhooks] = useState([]);
-  const [selectedWebhook, setSelectedWebhook] = useState(null);
-  const [defaultTemplate, setDefaultTemplate] = useState(null);
+  const [selectedId, setSelectedId] = useState(null);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState(null);
-  const [selectedTab, setSelectedTab] = useState("default");
-  const [selectedWebhookTemplate, setSelectedWebhookTemplate] = useState(null);
+  const [nameAction, setNameAction] = useState(null);
+  const [isDeleteModalOpen, setIsDeleteModalOpen] = useState(false);
 
   const loadData = useCallback(async () => {
     try {
       setIsLoading(true);
       setError(null);
-      const [webhooksData, defaultTemplateData] = await Promise.all([invoke("get_webhooks"), invoke("get_webhook_template", { webhookId: -1 })]);
+      const [templatesData, webhooksData] = await Promise.all([invoke("get_webhook_templates"), invoke("get_webhooks")]);
+      setTemplates(templatesData || []);
       setWebhooks(webhooksData || []);
-      setDefaultTemplate(defaultTemplateData);
+      setSelectedId((id) => (templatesData || []).find((template) => template.id === id)?.id ?? templatesData?.[0]?.id ?? null);
     } catch (error) {
       console.error("Failed to load data:", error);
       setError("Failed to load templates. Please try again.");
@@ -97,36 +173,62 @@ export default function WebhookTemplatesPage() {
     loadData();
   }, [loadData]);
 
-  const handleWebhookSelect = useCallback(
-    async (value) => {
-      const webhook = webhooks.find((w) => w.id.toString() === value);
-      if (webhook) {
-        setSelectedWebhook(webhook);
-        if (webhook.use_custom_template) {
-          const template = await getTemplateForWebhook(webhook.id);
-          setSelectedWebhookTemplate(template);
-        } else {
-          setSelectedWebhookTemplate(null);
-        }
-      } else {
-        setSelectedWebhook(null);
-        setSelectedWebhookTemplate(null);
-      }
-    },
-    [webhooks]
-  );
+  // Reload when a backup is restored from the settings
+  useEffect(() => {
+    const unlisten = listen("backup_imported", () => {
+      loadData();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [loadData]);
 
-  const handleSaveTemplate = useCallback(
-    async (updatedTemplate) => {
-      if (selectedTab === "default") {
-        setDefaultTemplate(updatedTemplate);
-      } else if (selectedWebhook) {
-        setSelectedWebhookTemplate(updatedTemplate);
-        setWebhooks((prevWebhooks) => prevWebhooks.map((webhook) => (webhook.id === selectedWebhook.id ? { ...webhook, use_custom_template: true } : webhook)));
-      }
-    },
-    [selectedTab, selectedWebhook]
-  );
+  const defaultTemplate = templates.find((template) => template.is_default);
+  const selectedTemplate = templates.find((template) => template.id === selectedId) || null;
+
+  // The webhooks using each template, by template ID
+  const usage = useMemo(() => {
+    const byTemplate = {};
+    for (const webhook of webhooks) {
+      const templateId = webhook.template_id ?? defaultTemplate?.id;
+      byTemplate[templateId] = [...(byTemplate[templateId] || []), webhook];
+    }
+    return byTemplate;
+  }, [webhooks, defaultTemplate]);
+
+  const handleSelect = useCallback((value) => setSelectedId(Number(value)), []);
+
+  const addTemplate = (template) => {
+    setTemplates((prevTemplates) => [...prevTemplates, template]);
+    setSelectedId(template.id);
+  };
+
+  const handleCreate = async (name) => {
+    const template = await invoke("create_webhook_template", { template: { ...defaultTemplate, id: null, is_default: false, name } });
+    addTemplate(template);
+  };
+
+  const handleDuplicate = async (name) => {
+    const template = await invoke("duplicate_webhook_template", { templateId: selectedTemplate.id, name });
+    addTemplate(template);
+  };
+
+  const handleRename = async (name) => {
+    await invoke("rename_webhook_template", { templateId: selectedTemplate.id, name });
+    setTemplates((prevTemplates) => prevTemplates.map((template) => (template.id === selectedTemplate.id ? { ...template, name } : template)));
+  };
+
+  const handleConfirmDelete = async () => {
+    await invoke("delete_webhook_template", { templateId: selectedTemplate.id });
+    // Its webhooks now use the default template
+    setWebhooks((prevWebhooks) => prevWebhooks.map((webhook) => (webhook.template_id === selectedTemplate.id ? { ...webhook, template_id: null } : webhook)));
+    setTemplates((prevTemplates) => prevTemplates.filter((template) => template.id !== selectedTemplate.id));
+    setSelectedId(defaultTemplate?.id ?? null);
+  };
+
+  const handleSaveTemplate = useCallback((updatedTemplate) => {
+    setTemplates((prevTemplates) => prevTemplates.map((template) => (template.id === updatedTemplate.id ? updatedTemplate : template)));
+  }, []);
 
   if (isLoading) {
     return (
@@ -161,85 +263,53 @@ export default function WebhookTemplatesPage() {
           <div className="flex justify-between items-center shrink-0">
             <div className="flex items-center gap-3">
               <h1 className="text-2xl font-bold bg-gradient-to-r from-primary to-secondary bg-clip-text text-transparent">Webhook Templates</h1>
-              {webhooks.length > 0 && (
+              {templates.length > 0 && (
                 <div className="px-3 py-1 rounded-full bg-primary/10">
                   <span className="text-sm text-primary font-medium">
-                    {webhooks.length} {webhooks.length === 1 ? "Template" : "Templates"}
+                    {templates.length} {templates.length === 1 ? "Template" : "Templates"}
                   </span>
                 </div>
               )}
             </div>
+            <Button color="primary" variant="shadow" startContent={<Plus size={18} />} onPress={() => setNameAction({ title: "New Template", name: "", submit: handleCreate })} isDisabled={!defaultTemplate}>
+              New Template
+            </Button>
           </div>
 
-          {/* Tabs */}
-          <Tabs
-            selectedKey={selectedTab}
-            onSelectionChange={setSelectedTab}
-            variant="underlined"
-            classNames={{
-              tabList: "gap-6",
-              cursor: "bg-primary",
-              tab: "max-w-fit px-0 h-12",
-              tabContent: "group-data-[selected=true]:text-primary",
-            }}
-          >
-            <Tab
-              key="default"
-              title={
-                <div className="flex items-center gap-2">
-                  <FileCode size={18} />
-                  <span>Default Template</span>
-                </div>
-              }
-            />
-            <Tab
-              key="custom"
-              title={
-                <div className="flex items-center gap-2">
-                  <Bot size={18} />
-                  <span>Custom Templates</span>
-                </div>
-              }
-            />
-          </Tabs>
+          <TemplateSelector
+            templates={templates}
+            usage={usage}
+            selectedTemplate={selectedTemplate}
+            onSelect={handleSelect}
+            onDuplicate={() => setNameAction({ title: "Duplicate Template", name: `${selectedTemplate.name} (copy)`, submit: handleDuplicate })}
+            onRename={() => setNameAction({ title: "Rename Template", name: selectedTemplate.name, submit: handleRename })}
+            onDelete={() => setIsDeleteModalOpen(true)}
+          />
 
           {/* Content */}
           <ScrollShadow className="flex-1 min-h-0" hideScrollBar>
             <AnimatePresence mode="wait">
-              {selectedTab === "default" && defaultTemplate ? (
-                <motion.div {...fadeInUp}>
-                  <WebhookEditorWrapper template={defaultTemplate} webhook={null} onSave={handleSaveTemplate} isDefault={true} />
+              {selectedTemplate ? (
+                <motion.div key={selectedTemplate.id} {...fadeInUp}>
+                  <WebhookEditorWrapper template={selectedTemplate} webhooks={usage[selectedTemplate.id] || []} onSave={handleSaveTemplate} />
                 </motion.div>
               ) : (
-                selectedTab === "custom" && (
-                  <motion.div className="space-y-6" {...fadeInUp}>
-                    {webhooks.length === 0 ? (
-                      <div className="flex flex-col items-center justify-center p-8 border border-dashed rounded-lg">
-                        <div className="p-4 rounded-full bg-primary/10 mb-4">
-                          <Bot size={24} className="text-primary" />
-                        </div>
-                        <p className="text-default-600">No webhooks found. Add webhooks first to customize their templates!</p>
-                      </div>
-                    ) : (
-                      <>
-                        <WebhookSelector webhooks={webhooks} selectedWebhook={selectedWebhook} onSelect={handleWebhookSelect} />
-
-                        {selectedWebhook ? (
-                          <WebhookEditorWrapper webhook={selectedWebhook} template={selectedWebhookTemplate || defaultTemplate} onSave={handleSaveTemplate} isDefault={false} />
-                        ) : (
-                          <div className="flex flex-col items-center justify-center p-8 border border-dashed rounded-lg">
-                            <p className="text-default-600">Select a webhook above to customize its template.</p>
-                          </div>
-                        )}
-                      </>
-                    )}
-                  </motion.div>
-                )
+                <motion.div className="flex flex-col items-center justify-center p-8 border border-dashed rounded-lg" {...fadeInUp}>
+                  <div className="p-4 rounded-full bg-primary/10 mb-4">
+                    <FileCode size={24} className="text-primary" />
+                  </div>
+                  <p className="text-default-600">Select a template above to edit it.</p>
+                </motion.div>
               )}
             </AnimatePresence>
           </ScrollShadow>
         </div>
       </motion.div>
+
+      <TemplateNameModal action={nameAction} onClose={() => setNameAction(null)} onSubmit={(name) => nameAction.submit(name)} />
+
+      {/* Delete Confirmation Modal */}
+      <DeleteConfirmationModal isOpen={isDeleteModalOpen} onClose={() => setIsDeleteModalOpen(false)} onConfirm={handleConfirmDelete} title="Delete Template" message={`This will permanently delete the template "${selectedTemplate?.name}". ${(usage[selectedTemplate?.id] || []).length} webhooks use it and will switch to the default template.`} itemType="template" icon={Trash2} iconColor="danger" />
     </div>
   );
 }
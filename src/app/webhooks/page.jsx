@@ -4,9 +4,11 @@ import { useState, useEffect, useMemo } from "react";
 import { Button, CircularProgress, ScrollShadow, Input } from "@nextui-org/react";
 import { Plus, AlertTriangle, RefreshCw, Webhook as WebhookIcon, Search } from "lucide-react";
 import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
 import { motion, AnimatePresence } from "framer-motion";
 import WebhookCard from "@/components/WebhookCard";
 import AddWebhookModal from "@/components/AddWebhookModal";
+import FailedDeliveries from "@/components/FailedDeliveries";
 
 const fadeInUp = {
   initial: { opacity: 0, y: 20 },
@@ -16,6 +18,7 @@ const fadeInUp = {
 
 export default function WebhooksPage() {
   const [webhooks, setWebhooks] = useState([]);
+  const [templates, setTemplates] = useState([]);
   const [isAddModalOpen, setIsAddModalOpen] = useState(false);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState(null);
@@ -33,12 +36,23 @@ export default function WebhooksPage() {
     loadWebhooks();
   }, []);
 
+  // Reload when a backup is restored from the settings
+  useEffect(() => {
+    const unlisten = listen("backup_imported", () => {
+      loadWebhooks();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const loadWebhooks = async () => {
     try {
       setIsLoading(true);
       setError(null);
-      const webhooksData = await invoke("get_webhooks");
+      const [webhooksData, templatesData] = await Promise.all([invoke("get_webhooks"), invoke("get_webhook_templates")]);
       setWebhooks(webhooksData || []);
+      setTemplates(templatesData || []);
     } catch (error) {
       console.error("Failed to load webhooks:", error);
       setError("Failed to load webhooks. Please try again.");
@@ -121,6 +135,9 @@ export default function WebhooksPage() {
             </Button>
           </div>
 
+          {/* Deliveries that are being retried or ran out of attempts */}
+          <FailedDeliveries />
+
           {/* Search Bar - Only show if there are webhooks */}
           {webhooks.length > 0 && (
             <div className="flex items-center gap-4">
@@ -172,7 +189,7 @@ export default function WebhooksPage() {
                 <div className="space-y-4 pr-2">
                   {filteredWebhooks.map((webhook, index) => (
                     <motion.div key={webhook.id} initial={{ opacity: 0, y: 20 }} animate={{ opacity: 1, y: 0 }} exit={{ opacity: 0, y: -20 }} transition={{ duration: 0.2, delay: index * 0.05 }}>
-                      <WebhookCard key={webhook.id} webhook={webhook} onDelete={handleDeleteWebhook} onUpdate={handleUpdateWebhook} existingWebhooks={webhooks} />
+                      <WebhookCard key={webhook.id} webhook={webhook} templates={templates} onDelete={handleDeleteWebhook} onUpdate={handleUpdateWebhook} existingWebhooks={webhooks} />
                     </motion.div>
                   ))}
                 </div>
@@ -6,6 +6,7 @@ import { Package2, ExternalLink, AlertCircle, Key, Plus, FileText } from "lucide
 import { invoke } from "@tauri-apps/api/tauri";
 import { motion } from "framer-motion";
 import { open } from "@tauri-apps/api/shell";
+import { getErrorCode, getRetryAfter } from "@/utils/errors";
 
 export default function AddModModal({ isOpen, onClose, onAdd, onOpenSettings }) {
   const [curseforgeId, setCurseforgeId] = useState("");
@@ -60,14 +61,31 @@ export default function AddModModal({ isOpen, onClose, onAdd, onOpenSettings })
         return;
       }
 
-      if (error.toString().includes("already exists")) {
-        setError("This mod is already being tracked.");
-      } else if (error.toString().includes("not found")) {
-        setError("Mod not found on CurseForge. Please check the ID.");
-      } else if (error.toString().includes("Failed to fetch")) {
-        setError("Failed to connect to CurseForge. Please check your API key.");
-      } else {
-        setError("Failed to add mod. Please try again.");
+      switch (getErrorCode(error)) {
+        case "mod_already_tracked":
+          setError("This mod is already being tracked.");
+          break;
+        case "mod_not_found":
+          setError("Mod not found on CurseForge. Please check the ID.");
+          break;
+        case "invalid_api_key":
+        case "missing_api_key":
+          setError("CurseForge rejected the request. Please check your API key.");
+          break;
+        case "rate_limited": {
+          const retryAfter = getRetryAfter(error);
+          setError(
+            retryAfter
+              ? `CurseForge is rate limiting requests. Please try again in ${Math.ceil(retryAfter)} seconds.`
+              : "CurseForge is rate limiting requests. Please try again later."
+          );
+          break;
+        }
+        case "network_error":
+          setError("Failed to connect to CurseForge. Please check your internet connection.");
+          break;
+        default:
+          setError("Failed to add mod. Please try again.");
       }
     } finally {
       setIsLoading(false);
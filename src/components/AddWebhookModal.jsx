@@ -1,15 +1,20 @@
 "use client";
 
-import { Modal, ModalContent, ModalHeader, ModalBody, ModalFooter, Button, Input, Divider } from "@nextui-org/react";
+import { Modal, ModalContent, ModalHeader, ModalBody, ModalFooter, Button, Input, Divider, Select, SelectItem } from "@nextui-org/react";
 import { Webhook as WebhookIcon, Bot, Image, Plus, Link as LinkIcon } from "lucide-react";
 import { useState } from "react";
 import { motion } from "framer-motion";
+import { getErrorCode, getErrorMessage } from "@/utils/errors";
+import HttpWebhookConfig from "./HttpWebhookConfig";
+import WebhookConfigFields from "./WebhookConfigFields";
+import { WEBHOOK_KINDS, getMissingConfigFields, getWebhookKind, isValidWebhookUrl } from "@/utils/webhookKinds";
 
 export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhooks = [] }) {
   const [isLoading, setIsLoading] = useState(false);
   const [errors, setErrors] = useState({
     name: "",
     url: "",
+    config: "",
     general: "",
   });
   const [newWebhook, setNewWebhook] = useState({
@@ -18,13 +23,17 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
     username: "",
     avatar_url: "",
     enabled: true,
-    use_custom_template: false,
+    template_id: null,
+    kind: "discord",
+    config: null,
   });
+  const kind = getWebhookKind(newWebhook.kind);
 
   const validateWebhook = () => {
     const newErrors = {
       name: "",
       url: "",
+      config: "",
       general: "",
     };
 
@@ -44,14 +53,28 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
     }
 
     // Check for duplicate URL
-    const urlExists = existingWebhooks.some((webhook) => webhook.url === newWebhook.url.trim());
+    const urlExists = !kind.sharedUrl && existingWebhooks.some((webhook) => webhook.url === newWebhook.url.trim());
     if (urlExists) {
       newErrors.url = "This webhook URL is already in use";
     }
 
-    // Validate Discord webhook URL format
-    if (newWebhook.url && !newWebhook.url.startsWith("https://discord.com/api/webhooks/")) {
-      newErrors.url = "Please enter a valid Discord webhook URL";
+    // Validate the webhook URL format for the selected service
+    if (newWebhook.url && !isValidWebhookUrl(newWebhook.kind, newWebhook.url.trim())) {
+      newErrors.url = `Please enter a valid ${kind.label} webhook URL`;
+    }
+
+    const missingFields = getMissingConfigFields(newWebhook.kind, newWebhook.config);
+    if (missingFields.length > 0) {
+      newErrors.config = `${missingFields.map((field) => field.label).join(" and ")} ${missingFields.length > 1 ? "are" : "is"} required`;
+    }
+
+    if (newWebhook.kind === "http") {
+      try {
+        // Variables are replaced with sample text so only the template's structure is checked
+        JSON.parse((newWebhook.config?.body_template || "").replace(/\{[A-Za-z&#0-9]+\}/g, "0"));
+      } catch {
+        newErrors.config = "The body template must be valid JSON";
+      }
     }
 
     setErrors(newErrors);
@@ -65,11 +88,14 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
       username: "",
       avatar_url: "",
       enabled: true,
-      use_custom_template: false,
+      template_id: null,
+      kind: "discord",
+      config: null,
     });
     setErrors({
       name: "",
       url: "",
+      config: "",
       general: "",
     });
   };
@@ -81,7 +107,7 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
 
     try {
       setIsLoading(true);
-      setErrors({ name: "", url: "", general: "" });
+      setErrors({ name: "", url: "", config: "", general: "" });
 
       // Trim all string fields
       const webhookToAdd = {
@@ -90,6 +116,7 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
         url: newWebhook.url.trim(),
         username: newWebhook.username.trim(),
         avatar_url: newWebhook.avatar_url.trim(),
+        config: newWebhook.config ? Object.fromEntries(Object.entries(newWebhook.config).map(([key, value]) => [key, typeof value === "string" && key !== "body_template" ? value.trim() : value])) : null,
       };
 
       await onAdd(webhookToAdd);
@@ -99,11 +126,16 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
       console.error("Failed to add webhook:", error);
 
       // Handle specific error cases
-      if (error.toString().includes("already exists")) {
+      if (getErrorCode(error) === "webhook_name_taken") {
         setErrors((prev) => ({
           ...prev,
           name: "A webhook with this name already exists",
         }));
+      } else if (getErrorCode(error) === "invalid_input") {
+        setErrors((prev) => ({
+          ...prev,
+          config: getErrorMessage(error),
+        }));
       } else {
         setErrors((prev) => ({
           ...prev,
@@ -141,7 +173,7 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
             </div>
             <div>
               <h2 className="text-xl font-bold">Add New Webhook</h2>
-              <p className="text-sm text-default-500">Configure Discord webhook integration</p>
+              <p className="text-sm text-default-500">Configure {kind.label} webhook integration</p>
             </div>
           </motion.div>
         </ModalHeader>
@@ -174,15 +206,38 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
                   isInvalid={!!errors.name}
                   errorMessage={errors.name}
                 />
+                <Select
+                  label="Service"
+                  selectedKeys={[newWebhook.kind]}
+                  onChange={(e) => {
+                    if (!e.target.value) return;
+                    const nextKind = getWebhookKind(e.target.value);
+                    setNewWebhook({
+                      ...newWebhook,
+                      kind: e.target.value,
+                      url: !newWebhook.url || newWebhook.url === kind.defaultUrl ? nextKind.defaultUrl || "" : newWebhook.url,
+                      config: nextKind.defaultConfig ? { ...nextKind.defaultConfig } : null,
+                    });
+                    setErrors({ ...errors, url: "", config: "" });
+                  }}
+                  description="Where notifications for this webhook are posted"
+                  disallowEmptySelection
+                >
+                  {Object.entries(WEBHOOK_KINDS).map(([value, { label }]) => (
+                    <SelectItem key={value} value={value}>
+                      {label}
+                    </SelectItem>
+                  ))}
+                </Select>
                 <Input
-                  label="Discord Webhook URL"
-                  placeholder="https://discord.com/api/webhooks/..."
+                  label={kind.urlLabel}
+                  placeholder={kind.urlPlaceholder}
                   value={newWebhook.url}
                   onChange={(e) => {
                     setNewWebhook({ ...newWebhook, url: e.target.value });
                     if (errors.url) setErrors({ ...errors, url: "" });
                   }}
-                  description="The Discord webhook URL for sending notifications"
+                  description={kind.urlDescription}
                   isRequired
                   isInvalid={!!errors.url}
                   errorMessage={errors.url}
@@ -190,24 +245,49 @@ export default function AddWebhookModal({ isOpen, onClose, onAdd, existingWebhoo
                     input: "font-mono text-small",
                   }}
                 />
+                {kind.configFields && (
+                  <WebhookConfigFields
+                    fields={kind.configFields}
+                    config={newWebhook.config}
+                    onChange={(config) => {
+                      setNewWebhook({ ...newWebhook, config });
+                      if (errors.config) setErrors({ ...errors, config: "" });
+                    }}
+                    error={errors.config}
+                  />
+                )}
+                {newWebhook.kind === "http" && (
+                  <HttpWebhookConfig
+                    config={newWebhook.config}
+                    onChange={(config) => {
+                      setNewWebhook({ ...newWebhook, config });
+                      if (errors.config) setErrors({ ...errors, config: "" });
+                    }}
+                    error={errors.config}
+                  />
+                )}
               </div>
 
-              <Divider />
+              {kind.supportsIdentity && (
+                <>
+                  <Divider />
 
-              {/* Optional Settings */}
-              <div className="space-y-4">
-                <div className="flex items-center justify-between">
-                  <div className="flex items-center gap-2">
-                    <Bot size={16} className="text-primary" />
-                    <h3 className="text-sm font-medium">Optional Settings</h3>
-                  </div>
-                  <span className="text-xs text-default-400">Customize how notifications appear</span>
-                </div>
+                  {/* Optional Settings */}
+                  <div className="space-y-4">
+                    <div className="flex items-center justify-between">
+                      <div className="flex items-center gap-2">
+                        <Bot size={16} className="text-primary" />
+                        <h3 className="text-sm font-medium">Optional Settings</h3>
+                      </div>
+                      <span className="text-xs text-default-400">Customize how notifications appear</span>
+                    </div>
 
-                <Input label="Bot Username" placeholder="Custom username for the webhook" value={newWebhook.username} onChange={(e) => setNewWebhook({ ...newWebhook, username: e.target.value })} startContent={<Bot size={16} className="text-default-400 shrink-0" />} description="Override the default webhook bot name" />
+                    <Input label="Bot Username" placeholder="Custom username for the webhook" value={newWebhook.username} onChange={(e) => setNewWebhook({ ...newWebhook, username: e.target.value })} startContent={<Bot size={16} className="text-default-400 shrink-0" />} description="Override the default webhook bot name" />
 
-                <Input label="Avatar URL" placeholder="https://example.com/avatar.png" value={newWebhook.avatar_url} onChange={(e) => setNewWebhook({ ...newWebhook, avatar_url: e.target.value })} startContent={<Image size={16} className="text-default-400 shrink-0" />} description="Custom avatar image URL for the webhook" />
-              </div>
+                    <Input label="Avatar URL" placeholder="https://example.com/avatar.png" value={newWebhook.avatar_url} onChange={(e) => setNewWebhook({ ...newWebhook, avatar_url: e.target.value })} startContent={<Image size={16} className="text-default-400 shrink-0" />} description="Custom avatar image URL for the webhook" />
+                  </div>
+                </>
+              )}
             </div>
           </motion.div>
         </ModalBody>
@@ -1,84 +1,70 @@
 "use client";
 
-import { ScrollShadow } from "@nextui-org/react";
-import { MessageSquare } from "lucide-react";
+import { useState, useEffect } from "react";
+import { ScrollShadow, Select, SelectItem } from "@nextui-org/react";
+import { MessageSquare, AlertTriangle } from "lucide-react";
+import { invoke } from "@tauri-apps/api/tauri";
+import { getErrorMessage } from "@/utils/errors";
 
-const PreviewEmbed = ({ template, fields }) => {
-  const colorHex = `#${template.color.toString(16).padStart(6, "0")}`;
+const SAMPLE_DATA = "sample";
+
+// Renders one embed of the payload the backend built
+const PreviewEmbed = ({ embed }) => {
+  const colorHex = `#${(embed.color || 0).toString(16).padStart(6, "0")}`;
 
   return (
-    <div className="rounded-lg p-4" style={{ backgroundColor: "#313338" }}>
-      <div className="flex gap-4">
-        {/* Bot Avatar Placeholder */}
-        <div className="w-10 h-10 rounded-full bg-default-300 shrink-0" />
-
-        <div className="space-y-1 w-full">
-          {/* Bot Name */}
-          <div className="flex items-center gap-2">
-            <span className="font-medium text-white">{template.username || "Mod Tracker"}</span>
-            <span className="text-xs text-[#989AA2]">Today at {new Date().toLocaleTimeString()}</span>
-          </div>
+    <div className="rounded-lg overflow-hidden max-w-[520px]">
+      {/* Left Border Accent */}
+      <div className="flex" style={{ backgroundColor: "#2B2D31" }}>
+        <div className="w-1 shrink-0" style={{ backgroundColor: colorHex }} />
+        <div className="p-3 w-full relative">
+          <div className={`${embed.thumbnail ? "pr-24" : ""}`}>
+            {/* Author Section */}
+            {embed.author?.name && (
+              <div className="flex items-center gap-2 mb-2">
+                {embed.author.icon_url && <img src={embed.author.icon_url} alt="" className="w-6 h-6 rounded-full shrink-0" />}
+                <span className="text-sm font-medium text-white">{embed.author.name}</span>
+              </div>
+            )}
 
-          {/* Embed Content */}
-          {template.use_embed ? (
-            <div className="rounded-lg overflow-hidden max-w-[520px]">
-              {/* Left Border Accent */}
-              <div className="flex" style={{ backgroundColor: "#2B2D31" }}>
-                <div className="w-1 shrink-0" style={{ backgroundColor: colorHex }} />
-                <div className="p-3 w-full relative">
-                  <div className={`${template.use_thumbnail ? "pr-24" : ""}`}>
-                    {/* Author Section */}
-                    {template.author_name && (
-                      <div className="flex items-center gap-2 mb-2">
-                        {template.author_icon_url && <div className="w-6 h-6 rounded-full bg-default-300 shrink-0" />}
-                        <span className="text-sm font-medium text-white">{template.author_name}</span>
-                      </div>
-                    )}
-
-                    {/* Title */}
-                    {template.title && <div className="font-semibold text-white mb-2">{template.title}</div>}
-
-                    {/* Description if any */}
-                    {template.content && <div className="text-[#D1D3D7] text-sm mb-2">{template.content}</div>}
-
-                    {/* Fields Grid */}
-                    <div className="grid grid-cols-2 gap-2">
-                      {fields.map((field, index) => (
-                        <div key={index} className={`${field.inline ? "" : "col-span-2"} min-w-0`}>
-                          <div className="text-[#D1D3D7] text-xs font-medium mb-0.5">{field.name || "Field Name"}</div>
-                          <div className="text-[#989AA2] text-sm break-words">{field.value || "Field Value"}</div>
-                        </div>
-                      ))}
-                    </div>
-
-                    {/* Footer Section */}
-                    {(template.footer_text || template.include_timestamp) && (
-                      <div className="flex items-center gap-2 mt-2 pt-2 border-t border-[#393B40]">
-                        {template.footer_icon_url && <div className="w-5 h-5 rounded-full bg-default-300 shrink-0" />}
-                        <span className="text-xs text-[#989AA2] flex items-center gap-2">
-                          {template.footer_text}
-                          {template.include_timestamp && (
-                            <>
-                              {template.footer_text && "•"}
-                              {new Date().toLocaleString()}
-                            </>
-                          )}
-                        </span>
-                      </div>
-                    )}
-                  </div>
+            {/* Title */}
+            {embed.title && <div className="font-semibold text-white mb-2 break-words">{embed.title}</div>}
 
-                  {/* Thumbnail */}
-                  {template.use_thumbnail && (
-                    <div className="absolute top-3 right-3">
-                      <div className="w-16 h-16 rounded bg-[#1E1F22] flex items-center justify-center text-xs text-[#989AA2]">Mod Icon</div>
-                    </div>
-                  )}
+            {/* Description if any */}
+            {embed.description && <div className="text-[#D1D3D7] text-sm mb-2 whitespace-pre-wrap break-words">{embed.description}</div>}
+
+            {/* Fields Grid */}
+            <div className="grid grid-cols-2 gap-2">
+              {(embed.fields || []).map((field, index) => (
+                <div key={index} className={`${field.inline ? "" : "col-span-2"} min-w-0`}>
+                  <div className="text-[#D1D3D7] text-xs font-medium mb-0.5 break-words">{field.name}</div>
+                  <div className="text-[#989AA2] text-sm whitespace-pre-wrap break-words">{field.value}</div>
                 </div>
+              ))}
+            </div>
+
+            {/* Footer Section */}
+            {(embed.footer?.text || embed.timestamp) && (
+              <div className="flex items-center gap-2 mt-2 pt-2 border-t border-[#393B40]">
+                {embed.footer?.icon_url && <img src={embed.footer.icon_url} alt="" className="w-5 h-5 rounded-full shrink-0" />}
+                <span className="text-xs text-[#989AA2] flex items-center gap-2">
+                  {embed.footer?.text}
+                  {embed.timestamp && (
+                    <>
+                      {embed.footer?.text && "•"}
+                      {new Date(embed.timestamp).toLocaleString()}
+                    </>
+                  )}
+                </span>
               </div>
+            )}
+          </div>
+
+          {/* Thumbnail */}
+          {embed.thumbnail && (
+            <div className="absolute top-3 right-3">
+              <img src={embed.thumbnail.url} alt="" className="w-16 h-16 rounded object-cover" />
             </div>
-          ) : (
-            <p className="text-[#D1D3D7] whitespace-pre-wrap">{template.content || "No content set"}</p>
           )}
         </div>
       </div>
@@ -86,7 +72,63 @@ const PreviewEmbed = ({ template, fields }) => {
   );
 };
 
-export default function DiscordPreview({ template, fields }) {
+const PreviewMessage = ({ payload }) => (
+  <div className="rounded-lg p-4" style={{ backgroundColor: "#313338" }}>
+    <div className="flex gap-4">
+      {/* Bot Avatar */}
+      {payload.avatar_url ? <img src={payload.avatar_url} alt="" className="w-10 h-10 rounded-full shrink-0" /> : <div className="w-10 h-10 rounded-full bg-default-300 shrink-0" />}
+
+      <div className="space-y-1 w-full min-w-0">
+        {/* Bot Name */}
+        <div className="flex items-center gap-2">
+          <span className="font-medium text-white">{payload.username}</span>
+          <span className="text-xs text-[#989AA2]">Today at {new Date().toLocaleTimeString()}</span>
+        </div>
+
+        {payload.content && <p className="text-[#D1D3D7] whitespace-pre-wrap break-words">{payload.content}</p>}
+        {(payload.embeds || []).map((embed, index) => (
+          <PreviewEmbed key={index} embed={embed} />
+        ))}
+      </div>
+    </div>
+  </div>
+);
+
+// Shows the messages exactly as the backend renders them, so the preview matches what
+// gets posted. Webhooks of other services see the raw JSON body.
+export default function DiscordPreview({ template, fields, webhook }) {
+  const [mods, setMods] = useState([]);
+  const [modKey, setModKey] = useState(SAMPLE_DATA);
+  const [rendered, setRendered] = useState(null);
+  const [error, setError] = useState(null);
+
+  useEffect(() => {
+    invoke("get_mods")
+      .then((mods) => setMods(mods || []))
+      .catch((error) => console.error("Failed to load mods:", error));
+  }, []);
+
+  useEffect(() => {
+    // Wait for typing to pause before rendering again
+    const timer = setTimeout(async () => {
+      try {
+        const result = await invoke("render_webhook_payload", {
+          template: { ...template, embed_fields: JSON.stringify(fields) },
+          webhook: webhook || null,
+          modId: modKey === SAMPLE_DATA ? null : Number(modKey),
+        });
+        setRendered(result);
+        setError(null);
+      } catch (error) {
+        console.error("Failed to render preview:", error);
+        setError(getErrorMessage(error));
+      }
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [template, fields, webhook, modKey]);
+
+  const isDiscord = !webhook || (webhook.kind || "discord") === "discord";
+
   return (
     <div className="h-full">
       <div className="sticky top-4 space-y-4">
@@ -94,10 +136,44 @@ export default function DiscordPreview({ template, fields }) {
           <MessageSquare size={16} className="text-primary" />
           <h3 className="text-sm font-medium">Live Preview</h3>
         </div>
+        <Select
+          size="sm"
+          label="Preview with"
+          selectedKeys={[modKey]}
+          onChange={(e) => e.target.value && setModKey(e.target.value)}
+          disallowEmptySelection
+        >
+          {[{ key: SAMPLE_DATA, label: "Sample data" }, ...mods.map((mod) => ({ key: String(mod.id), label: `${mod.name} (last update)` }))].map(({ key, label }) => (
+            <SelectItem key={key} value={key}>
+              {label}
+            </SelectItem>
+          ))}
+        </Select>
+        {error && <p className="text-danger text-xs">{error}</p>}
+        {rendered?.warnings?.length > 0 && (
+          <div className="space-y-1 p-3 rounded-lg bg-warning-50 text-warning-700 text-xs">
+            {rendered.warnings.map((warning, index) => (
+              <p key={index} className="flex items-start gap-2">
+                <AlertTriangle size={14} className="shrink-0 mt-0.5" />
+                {warning}
+              </p>
+            ))}
+          </div>
+        )}
         <div className="relative max-h-[calc(100vh-8rem)] overflow-y-auto">
           <ScrollShadow className="h-full">
-            <div className="bg-[#313338] rounded-lg">
-              <PreviewEmbed template={template} fields={fields} />
+            <div className="space-y-2">
+              {rendered?.payloads.map((payload, index) =>
+                isDiscord ? (
+                  <div key={index} className="bg-[#313338] rounded-lg">
+                    <PreviewMessage payload={payload} />
+                  </div>
+                ) : (
+                  <pre key={index} className="p-3 rounded-lg bg-default-100 text-xs whitespace-pre-wrap break-all">
+                    {JSON.stringify(payload, null, 2)}
+                  </pre>
+                )
+              )}
             </div>
           </ScrollShadow>
         </div>
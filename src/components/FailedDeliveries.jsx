@@ -0,0 +1,110 @@
+"use client";
+
+import { useState, useEffect, useCallback } from "react";
+import { Card, CardHeader, CardBody, Button, Tooltip, ScrollShadow } from "@nextui-org/react";
+import { AlertTriangle, RotateCw, Trash2, Clock } from "lucide-react";
+import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
+import { getErrorMessage } from "@/utils/errors";
+
+const formatDateTime = (timestamp) => new Date(timestamp).toLocaleString();
+
+// Notifications that ran out of retries, with the option to send them again.
+// Hidden while nothing has failed.
+export default function FailedDeliveries() {
+  const [entries, setEntries] = useState([]);
+  const [busyId, setBusyId] = useState(null);
+  const [error, setError] = useState(null);
+
+  const loadEntries = useCallback(async () => {
+    try {
+      setEntries((await invoke("get_outbox_entries")) || []);
+    } catch (error) {
+      console.error("Failed to load outbox:", error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadEntries();
+    const unlisten = listen("outbox_updated", () => {
+      loadEntries();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [loadEntries]);
+
+  const runAction = async (id, command, args) => {
+    try {
+      setBusyId(id);
+      setError(null);
+      await invoke(command, args);
+      await loadEntries();
+    } catch (error) {
+      console.error(`Failed to run ${command}:`, error);
+      setError(getErrorMessage(error));
+    } finally {
+      setBusyId(null);
+    }
+  };
+
+  const failed = entries.filter((entry) => entry.status === "failed");
+  const retrying = entries.filter((entry) => entry.status === "pending" && entry.attempts > 0);
+
+  if (failed.length === 0 && retrying.length === 0) {
+    return null;
+  }
+
+  return (
+    <Card className="shrink-0 border border-danger/20 bg-danger-50/30">
+      <CardHeader className="flex justify-between items-center px-4 pt-3 pb-0">
+        <div className="flex items-center gap-2">
+          <AlertTriangle size={18} className="text-danger" />
+          <h2 className="text-sm font-semibold">Failed Deliveries</h2>
+          {failed.length > 0 && <span className="px-2 py-0.5 rounded-full bg-danger/10 text-xs text-danger">{failed.length}</span>}
+          {retrying.length > 0 && (
+            <span className="flex items-center gap-1 px-2 py-0.5 rounded-full bg-warning/10 text-xs text-warning">
+              <Clock size={12} />
+              {retrying.length} retrying
+            </span>
+          )}
+        </div>
+        {failed.length > 1 && (
+          <Button size="sm" variant="flat" color="primary" startContent={<RotateCw size={14} />} isLoading={busyId === "all"} onPress={() => runAction("all", "resend_all_failed_deliveries", {})}>
+            Resend All
+          </Button>
+        )}
+      </CardHeader>
+      <CardBody className="px-4 pb-3">
+        {error && <p className="text-danger text-xs mb-2">{error}</p>}
+        <ScrollShadow className="max-h-48 space-y-2" hideScrollBar>
+          {[...failed, ...retrying].map((entry) => (
+            <div key={entry.id} className="flex items-start justify-between gap-4 px-3 py-2 rounded-lg bg-content1">
+              <div className="min-w-0">
+                <p className="text-sm font-medium truncate">
+                  {entry.mod_name} → {entry.webhook_name}
+                </p>
+                <p className="text-xs text-default-500">
+                  {entry.status === "failed" ? `Failed after ${entry.attempts} ${entry.attempts === 1 ? "attempt" : "attempts"}` : `Attempt ${entry.attempts} failed, retrying at ${formatDateTime(entry.next_attempt_at)}`} · queued {formatDateTime(entry.created_at)}
+                </p>
+                {entry.last_error && <p className="text-xs text-danger break-all">{entry.last_error}</p>}
+              </div>
+              <div className="flex items-center gap-1 shrink-0">
+                <Tooltip content={entry.status === "failed" ? "Resend" : "Retry now"}>
+                  <Button isIconOnly size="sm" variant="light" color="primary" isLoading={busyId === entry.id} onPress={() => runAction(entry.id, "resend_failed_delivery", { entryId: entry.id })}>
+                    <RotateCw size={16} />
+                  </Button>
+                </Tooltip>
+                <Tooltip content="Discard">
+                  <Button isIconOnly size="sm" variant="light" color="danger" isDisabled={busyId === entry.id} onPress={() => runAction(entry.id, "discard_failed_delivery", { entryId: entry.id })}>
+                    <Trash2 size={16} />
+                  </Button>
+                </Tooltip>
+              </div>
+            </div>
+          ))}
+        </ScrollShadow>
+      </CardBody>
+    </Card>
+  );
+}
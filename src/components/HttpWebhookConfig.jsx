@@ -0,0 +1,57 @@
+"use client";
+
+import { Button, Input, Select, SelectItem, Textarea } from "@nextui-org/react";
+import { Plus, Trash2 } from "lucide-react";
+import { HTTP_METHODS } from "@/utils/webhookKinds";
+
+export default function HttpWebhookConfig({ config, onChange, error }) {
+  const headers = config?.headers || [];
+
+  const updateHeader = (index, key, value) => {
+    onChange({ ...config, headers: headers.map((header, i) => (i === index ? { ...header, [key]: value } : header)) });
+  };
+
+  return (
+    <div className="space-y-4">
+      <Select label="Method" selectedKeys={[config?.method || "POST"]} onChange={(e) => e.target.value && onChange({ ...config, method: e.target.value })} disallowEmptySelection>
+        {HTTP_METHODS.map((method) => (
+          <SelectItem key={method} value={method}>
+            {method}
+          </SelectItem>
+        ))}
+      </Select>
+
+      <div className="space-y-2">
+        <div className="flex items-center justify-between">
+          <span className="text-sm font-medium">Headers</span>
+          <Button size="sm" variant="flat" startContent={<Plus size={14} />} onPress={() => onChange({ ...config, headers: [...headers, { name: "", value: "" }] })}>
+            Add Header
+          </Button>
+        </div>
+        {headers.map((header, index) => (
+          <div key={index} className="flex items-center gap-2">
+            <Input size="sm" placeholder="Authorization" value={header.name} onChange={(e) => updateHeader(index, "name", e.target.value)} />
+            <Input size="sm" placeholder="Bearer ..." type="password" value={header.value} onChange={(e) => updateHeader(index, "value", e.target.value)} />
+            <Button isIconOnly size="sm" variant="light" color="danger" onPress={() => onChange({ ...config, headers: headers.filter((_, i) => i !== index) })}>
+              <Trash2 size={14} />
+            </Button>
+          </div>
+        ))}
+      </div>
+
+      <Textarea
+        label="JSON Body Template"
+        value={config?.body_template || ""}
+        onChange={(e) => onChange({ ...config, body_template: e.target.value })}
+        description="Uses the same variables as message templates, e.g. {modName}, {modID} and {changelog}. Values are JSON-escaped, so keep string variables inside quotes."
+        minRows={8}
+        isRequired
+        isInvalid={!!error}
+        errorMessage={error}
+        classNames={{
+          input: "font-mono text-small",
+        }}
+      />
+    </div>
+  );
+}
@@ -1,7 +1,7 @@
 "use client";
 
 import { Card, CardBody, Button, Chip, Tooltip, Link } from "@nextui-org/react";
-import { Trash2, Gamepad2, Clock, ExternalLink, Webhook } from "lucide-react";
+import { Trash2, Gamepad2, Clock, ExternalLink, Webhook, Filter } from "lucide-react";
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/tauri";
 
@@ -41,7 +41,7 @@ function formatDate(dateString) {
   }
 }
 
-export default function ModCard({ mod, onDelete, onManageWebhooks }) {
+export default function ModCard({ mod, onDelete, onManageWebhooks, onManageFilters }) {
   const [isDeleting, setIsDeleting] = useState(false);
   const [assignedWebhooks, setAssignedWebhooks] = useState([]);
   const [isLoadingWebhooks, setIsLoadingWebhooks] = useState(false);
@@ -69,7 +69,8 @@ export default function ModCard({ mod, onDelete, onManageWebhooks }) {
     return null;
   }
 
-  const { id, name, game_name, last_updated, page_url } = mod;
+  const { id, name, game_name, last_updated, page_url, filters } = mod;
+  const activeFilters = filters ? [filters.game_version, filters.mod_loader, filters.release_type].filter(Boolean) : [];
 
   const handleDelete = async () => {
     if (!id) return;
@@ -160,6 +161,14 @@ export default function ModCard({ mod, onDelete, onManageWebhooks }) {
                 </Button>
               </Tooltip>
 
+              {onManageFilters && (
+                <Tooltip content={activeFilters.length > 0 ? activeFilters.join(" · ") : "No filters set"}>
+                  <Button size="sm" variant="light" color="secondary" startContent={<Filter size={14} />} onPress={() => onManageFilters(mod)} className="px-2 font-normal">
+                    Filters {activeFilters.length > 0 && `(${activeFilters.length})`}
+                  </Button>
+                </Tooltip>
+              )}
+
               <Button size="sm" variant="light" color="danger" startContent={<Trash2 size={14} />} onPress={handleDelete} isLoading={isDeleting} isDisabled={isDeleting} className="px-2 font-normal opacity-0 group-hover:opacity-100 transition-opacity">
                 Delete
               </Button>
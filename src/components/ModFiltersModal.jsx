@@ -0,0 +1,106 @@
+import { useState, useEffect } from "react";
+import { Modal, ModalContent, ModalHeader, ModalBody, ModalFooter, Button, Input, Select, SelectItem } from "@nextui-org/react";
+import { invoke } from "@tauri-apps/api/tauri";
+import { Filter, AlertCircle, CheckCircle2 } from "lucide-react";
+import { getErrorMessage } from "@/utils/errors";
+
+const RELEASE_TYPES = [
+  { value: "", label: "Any release type" },
+  { value: "release", label: "Release only" },
+  { value: "beta", label: "Release and beta" },
+  { value: "alpha", label: "Release, beta and alpha" },
+];
+
+export default function ModFiltersModal({ isOpen, onClose, mod, onSaved }) {
+  const [gameVersion, setGameVersion] = useState("");
+  const [modLoader, setModLoader] = useState("");
+  const [releaseType, setReleaseType] = useState("");
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState(null);
+
+  useEffect(() => {
+    if (isOpen && mod) {
+      const filters = mod.filters || {};
+      setGameVersion(filters.game_version || "");
+      setModLoader(filters.mod_loader || "");
+      setReleaseType(filters.release_type || "");
+      setError(null);
+    }
+  }, [isOpen, mod]);
+
+  const handleSave = async () => {
+    try {
+      setIsSaving(true);
+      setError(null);
+      const filters = await invoke("set_mod_filters", {
+        modId: mod.id,
+        filters: {
+          game_version: gameVersion || null,
+          mod_loader: modLoader || null,
+          release_type: releaseType || null,
+        },
+      });
+      if (onSaved) onSaved(mod.id, filters);
+      onClose();
+    } catch (error) {
+      console.error("Failed to save mod filters:", error);
+      setError(getErrorMessage(error));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Modal
+      isOpen={isOpen}
+      onClose={onClose}
+      size="lg"
+      backdrop="blur"
+      classNames={{
+        backdrop: "bg-background/50 backdrop-blur-sm",
+        base: "border border-default-100 bg-content1",
+      }}
+    >
+      <ModalContent>
+        <ModalHeader className="flex items-center gap-2">
+          <div className="p-2 rounded-lg bg-primary/10">
+            <Filter size={20} className="text-primary" />
+          </div>
+          <div className="flex flex-col">
+            <span>Update Filters</span>
+            {mod && <span className="text-sm font-normal text-default-500">{mod.name}</span>}
+          </div>
+        </ModalHeader>
+
+        <ModalBody className="space-y-2">
+          <p className="text-sm text-default-500">Only new files matching these filters will trigger update notifications. Leave a field empty to accept any value.</p>
+          <Input label="Game version" placeholder="e.g. 1.20.1" value={gameVersion} onValueChange={setGameVersion} />
+          <Input label="Mod loader" placeholder="e.g. Forge, Fabric, NeoForge" value={modLoader} onValueChange={setModLoader} />
+          <Select label="Release type" selectedKeys={[releaseType]} onChange={(e) => setReleaseType(e.target.value)}>
+            {RELEASE_TYPES.map((type) => (
+              <SelectItem key={type.value} value={type.value}>
+                {type.label}
+              </SelectItem>
+            ))}
+          </Select>
+
+          {error && (
+            <div className="flex items-center gap-3 p-3 rounded-lg bg-danger-50/10 border border-danger-200 text-danger">
+              <AlertCircle size={18} />
+              <p className="text-sm">{error}</p>
+            </div>
+          )}
+        </ModalBody>
+
+        <ModalFooter>
+          <Button variant="light" onPress={onClose}>
+            Cancel
+          </Button>
+          <Button color="primary" onPress={handleSave} isLoading={isSaving} startContent={!isSaving && <CheckCircle2 size={18} />}>
+            Save Filters
+          </Button>
+        </ModalFooter>
+      </ModalContent>
+    </Modal>
+  );
+}
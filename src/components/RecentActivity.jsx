@@ -2,7 +2,7 @@
 
 import { useState, useEffect, useCallback, useRef } from "react";
 import { Card, CardHeader, CardBody, Button, Tooltip, ScrollShadow, CircularProgress } from "@nextui-org/react";
-import { Clock, Package2, Trash2, AlertTriangle, RefreshCw, Plus } from "lucide-react";
+import { Clock, Package2, Trash2, AlertTriangle, RefreshCw, Plus, Download, Upload } from "lucide-react";
 import { invoke } from "@tauri-apps/api/tauri";
 import { listen } from "@tauri-apps/api/event";
 import { motion, AnimatePresence } from "framer-motion";
@@ -42,6 +42,7 @@ const ActivityIcon = ({ type }) => {
   const iconMap = {
     mod_added: { icon: Plus, color: "success" },
     mod_updated: { icon: Clock, color: "primary" },
+    mod_metadata_updated: { icon: Package2, color: "default" },
     mod_removed: { icon: Trash2, color: "danger" },
     webhook_error: { icon: AlertTriangle, color: "warning" },
     webhook_added: { icon: Plus, color: "success" },
@@ -50,6 +51,10 @@ const ActivityIcon = ({ type }) => {
     webhook_assigned: { icon: Plus, color: "success" },
     webhook_unassigned: { icon: Trash2, color: "danger" },
     notification_sent: { icon: Clock, color: "primary" },
+    notification_failed: { icon: AlertTriangle, color: "danger" },
+    notification_delayed: { icon: Clock, color: "warning" },
+    backup_exported: { icon: Download, color: "primary" },
+    backup_imported: { icon: Upload, color: "success" },
   };
 
   const { icon: Icon, color } = iconMap[type] || { icon: Clock, color: "default" };
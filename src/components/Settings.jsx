@@ -24,9 +24,17 @@ import {
   Save,
   ExternalLink,
   MinimizeIcon,
+  Archive,
+  Download,
+  Upload,
+  CheckCircle2,
 } from "lucide-react";
 import { motion } from "framer-motion";
 import { open } from "@tauri-apps/api/shell";
+import { open as openFileDialog, save as saveFileDialog } from "@tauri-apps/api/dialog";
+import { getErrorMessage } from "@/utils/errors";
+
+const BACKUP_FILTERS = [{ name: "Mod Tracker Backup", extensions: ["json"] }];
 
 const UPDATE_INTERVALS = [
   { value: 1, label: "Every minute" },
@@ -49,6 +57,10 @@ export default function Settings({ isOpen, onClose }) {
   const [error, setError] = useState(null);
   const [minimizeToTray, setMinimizeToTray] = useState(false);
   const [closeToTray, setCloseToTray] = useState(false);
+  const [includeApiKey, setIncludeApiKey] = useState(false);
+  const [importMode, setImportMode] = useState("merge");
+  const [backupStatus, setBackupStatus] = useState(null);
+  const [isBackingUp, setIsBackingUp] = useState(false);
 
   useEffect(() => {
     if (isOpen) {
@@ -98,6 +110,54 @@ export default function Settings({ isOpen, onClose }) {
     }
   };
 
+  const handleExportBackup = async () => {
+    try {
+      setBackupStatus(null);
+      const date = new Date().toISOString().slice(0, 10);
+      const path = await saveFileDialog({
+        defaultPath: `mod-tracker-backup-${date}.json`,
+        filters: BACKUP_FILTERS,
+      });
+      if (!path) return;
+
+      setIsBackingUp(true);
+      const summary = await invoke("export_backup", { path, includeApiKey });
+      setBackupStatus({
+        type: "success",
+        message: `Exported ${summary.mods} mods and ${summary.webhooks} webhooks.`,
+      });
+    } catch (error) {
+      console.error("Failed to export backup:", error);
+      setBackupStatus({ type: "error", message: getErrorMessage(error) });
+    } finally {
+      setIsBackingUp(false);
+    }
+  };
+
+  const handleImportBackup = async () => {
+    try {
+      setBackupStatus(null);
+      const path = await openFileDialog({ multiple: false, filters: BACKUP_FILTERS });
+      if (!path) return;
+
+      setIsBackingUp(true);
+      const summary = await invoke("import_backup", { path, mode: importMode });
+      setBackupStatus({
+        type: "success",
+        message: `Restored ${summary.mods_imported} mods and ${summary.webhooks_imported} webhooks` +
+          (summary.mods_skipped || summary.webhooks_skipped
+            ? ` (skipped ${summary.mods_skipped} mods and ${summary.webhooks_skipped} webhooks already tracked).`
+            : "."),
+      });
+      await loadSettings();
+    } catch (error) {
+      console.error("Failed to import backup:", error);
+      setBackupStatus({ type: "error", message: getErrorMessage(error) });
+    } finally {
+      setIsBackingUp(false);
+    }
+  };
+
   const openApiKeyPage = async () => {
     try {
       await open("https://console.curseforge.com/#/api-keys");
@@ -281,6 +341,92 @@ export default function Settings({ isOpen, onClose }) {
                     </Checkbox>
                   </div>
                 </div>
+
+                <Divider />
+
+                {/* Backup Section */}
+                <div className="space-y-3">
+                  <div className="flex items-center gap-2 mb-2">
+                    <Archive size={16} className="text-primary" />
+                    <h3 className="text-sm font-medium">Backup & Restore</h3>
+                  </div>
+                  <div className="space-y-4">
+                    <div className="flex items-center justify-between gap-4">
+                      <Checkbox
+                        isSelected={includeApiKey}
+                        onValueChange={setIncludeApiKey}
+                        classNames={{
+                          wrapper: "inline-flex",
+                        }}
+                      >
+                        <div className="flex flex-col gap-1">
+                          <span className="text-sm">Include API key</span>
+                          <span className="text-xs text-default-400">
+                            Only enable this if the backup file will be kept
+                            somewhere private
+                          </span>
+                        </div>
+                      </Checkbox>
+                      <Button
+                        variant="flat"
+                        onPress={handleExportBackup}
+                        isDisabled={isBackingUp}
+                        startContent={<Download size={16} />}
+                        className="font-medium bg-primary/10 text-primary hover:bg-primary/20"
+                      >
+                        Export
+                      </Button>
+                    </div>
+                    <div className="flex items-center justify-between gap-4">
+                      <Select
+                        label="Import Mode"
+                        selectedKeys={[importMode]}
+                        onChange={(e) => e.target.value && setImportMode(e.target.value)}
+                        description={
+                          importMode === "replace"
+                            ? "Replaces all mods, webhooks, templates and activity with the backup"
+                            : "Adds mods and webhooks from the backup that aren't tracked yet"
+                        }
+                        classNames={{
+                          base: "max-w-xs",
+                          value: "text-small",
+                          label: "text-default-600 font-medium",
+                          description: "text-tiny text-default-400",
+                        }}
+                      >
+                        <SelectItem key="merge" value="merge" className="text-small">
+                          Merge
+                        </SelectItem>
+                        <SelectItem key="replace" value="replace" className="text-small">
+                          Replace
+                        </SelectItem>
+                      </Select>
+                      <Button
+                        variant="flat"
+                        onPress={handleImportBackup}
+                        isDisabled={isBackingUp}
+                        startContent={<Upload size={16} />}
+                        className="font-medium bg-primary/10 text-primary hover:bg-primary/20"
+                      >
+                        Import
+                      </Button>
+                    </div>
+                    {backupStatus && (
+                      <div
+                        className={`flex items-center gap-2 text-sm ${
+                          backupStatus.type === "success" ? "text-success" : "text-danger"
+                        }`}
+                      >
+                        {backupStatus.type === "success" ? (
+                          <CheckCircle2 size={16} />
+                        ) : (
+                          <AlertCircle size={16} />
+                        )}
+                        <p>{backupStatus.message}</p>
+                      </div>
+                    )}
+                  </div>
+                </div>
               </div>
             </motion.div>
           )}
@@ -1,14 +1,16 @@
 import { useState, useRef, useEffect } from "react";
 import { Button, Tooltip } from "@nextui-org/react";
 import { RefreshCw } from "lucide-react";
-import { useModUpdateChecker } from "@/hooks/useModUpdateChecker";
+import { invoke } from "@tauri-apps/api/tauri";
+import { listen } from "@tauri-apps/api/event";
+import { useUpdateService } from "@/contexts/UpdateServiceContext";
 
 // Constants
 const COOLDOWN_DURATION = 30; // 30 seconds cooldown
 const COOLDOWN_STORAGE_KEY = "update_check_cooldown_timestamp";
 
 export default function UpdateCheckButton({ onSuccess, disabled }) {
-  const { checkForUpdates, isChecking: isCheckingMods } = useModUpdateChecker();
+  const { performCheck, isChecking: isCheckingMods, progress } = useUpdateService();
   const cooldownTimerRef = useRef(null);
   const [cooldownRemaining, setCooldownRemaining] = useState(() => {
     // Initialize state with current remaining time
@@ -72,26 +74,46 @@ export default function UpdateCheckButton({ onSuccess, disabled }) {
     return () => clearCooldownTimer();
   }, []);
 
+  // Refresh the caller once the backend finishes the check we triggered
+  useEffect(() => {
+    let unlisten;
+
+    const setup = async () => {
+      unlisten = await listen("update_check_completed", async (event) => {
+        if (!onSuccess) return;
+        try {
+          const mods = await invoke("get_mods");
+          onSuccess(mods, new Date(event.payload.timestamp));
+        } catch (error) {
+          console.error("Failed to refresh mods after update check:", error);
+        }
+      });
+    };
+    setup();
+
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, [onSuccess]);
+
   const handleCheck = async () => {
     // Double-check cooldown before proceeding
     const remaining = calculateRemainingTime();
     if (remaining > 0 || isCheckingMods) return;
 
     try {
-      const result = await checkForUpdates(null, onSuccess);
-      if (result.success) {
-        const endTimestamp = Date.now() + COOLDOWN_DURATION * 1000;
-        localStorage.setItem(COOLDOWN_STORAGE_KEY, endTimestamp.toString());
-        setCooldownRemaining(COOLDOWN_DURATION);
-        startCooldownTimer(COOLDOWN_DURATION);
-      }
+      await performCheck();
+      const endTimestamp = Date.now() + COOLDOWN_DURATION * 1000;
+      localStorage.setItem(COOLDOWN_STORAGE_KEY, endTimestamp.toString());
+      setCooldownRemaining(COOLDOWN_DURATION);
+      startCooldownTimer(COOLDOWN_DURATION);
     } catch (error) {
       console.error("Failed to check for updates:", error);
     }
   };
 
   const getButtonText = () => {
-    if (isCheckingMods) return "Checking...";
+    if (isCheckingMods) return progress ? `Checking ${progress.checked}/${progress.total}...` : "Checking...";
     if (cooldownRemaining > 0) return `Wait ${cooldownRemaining}s`;
     return "Check Updates";
   };
@@ -1,19 +1,22 @@
 "use client";
 
-import { Card, CardBody, Button, Switch, Tooltip, Input } from "@nextui-org/react";
-import { Trash2, TestTubes, Bot, Webhook, CheckCircle2, AlertCircle, FileText, Pencil, X } from "lucide-react";
+import { Card, CardBody, Button, Switch, Tooltip, Input, Select, SelectItem } from "@nextui-org/react";
+import { Trash2, TestTubes, Bot, Webhook, CheckCircle2, AlertCircle, Pencil, X } from "lucide-react";
 import { useState } from "react";
 import { invoke } from "@tauri-apps/api/tauri";
 import DeleteConfirmationModal from "./DeleteConfirmationModal";
+import { getErrorCode, getErrorMessage } from "@/utils/errors";
+import { getWebhookKind } from "@/utils/webhookKinds";
 
-export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebhooks }) {
+// Webhooks without a template of their own use the default template
+const DEFAULT_TEMPLATE = "default";
+
+export default function WebhookCard({ webhook, templates = [], onDelete, onUpdate, existingWebhooks }) {
   const [isTesting, setIsTesting] = useState(false);
   const [isEnabled, setIsEnabled] = useState(webhook.enabled);
-  const [useCustomTemplate, setUseCustomTemplate] = useState(webhook.use_custom_template);
   const [testError, setTestError] = useState(null);
   const [testSuccess, setTestSuccess] = useState(false);
   const [isDeleteModalOpen, setIsDeleteModalOpen] = useState(false);
-  const [isTemplateModalOpen, setIsTemplateModalOpen] = useState(false);
   const [isRenaming, setIsRenaming] = useState(false);
   const [newName, setNewName] = useState(webhook.name);
   const [renameError, setRenameError] = useState("");
@@ -46,7 +49,7 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
       setTimeout(() => setTestSuccess(false), 3000); // Clear success after 3 seconds
     } catch (error) {
       console.error("Failed to test webhook:", error);
-      setTestError(error.toString());
+      setTestError(getErrorMessage(error));
       setTimeout(() => setTestError(null), 5000); // Clear error after 5 seconds
     } finally {
       setIsTesting(false);
@@ -67,24 +70,15 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
     }
   };
 
-  const handleTemplateToggle = async (useCustom) => {
-    // If enabling custom template, no confirmation needed
-    if (useCustom) {
-      try {
-        const updatedWebhook = {
-          ...webhook,
-          use_custom_template: true,
-        };
-        await onUpdate(updatedWebhook);
-        setUseCustomTemplate(true);
-      } catch (error) {
-        console.error("Failed to update template settings:", error);
-      }
-      return;
+  const handleTemplateChange = async (key) => {
+    try {
+      await onUpdate({
+        ...webhook,
+        template_id: key === DEFAULT_TEMPLATE ? null : Number(key),
+      });
+    } catch (error) {
+      console.error("Failed to update template settings:", error);
     }
-
-    // If disabling custom template, show confirmation
-    setIsTemplateModalOpen(true);
   };
 
   const handleStartRename = () => {
@@ -121,7 +115,7 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
       setRenameError("");
     } catch (error) {
       console.error("Failed to rename webhook:", error);
-      if (error.toString().includes("already exists")) {
+      if (getErrorCode(error) === "webhook_name_taken") {
         setRenameError("A webhook with this name already exists");
       } else {
         setRenameError("Failed to rename webhook");
@@ -130,21 +124,6 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
     }
   };
 
-  const handleConfirmTemplateDisable = async () => {
-    try {
-      const updatedWebhook = {
-        ...webhook,
-        use_custom_template: false,
-      };
-      await onUpdate(updatedWebhook);
-      await invoke("delete_custom_template", { webhookId: webhook.id });
-      setUseCustomTemplate(false);
-    } catch (error) {
-      console.error("Failed to update template settings:", error);
-      throw error;
-    }
-  };
-
   const handleDelete = () => {
     setIsDeleteModalOpen(true);
   };
@@ -211,6 +190,7 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
                         </Tooltip>
                       </>
                     )}
+                    <span className="px-2 py-0.5 bg-primary/10 rounded-full text-xs text-primary">{getWebhookKind(webhook.kind).label}</span>
                     {webhook.username && (
                       <div className="flex items-center gap-1 px-2 py-0.5 bg-default-100 rounded-full">
                         <Bot size={14} className="text-default-500" />
@@ -244,6 +224,13 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
             {/* URL Preview */}
             <div className="px-3 py-2 bg-default-50 rounded-lg">
               <p className="text-small text-default-500 font-mono break-all">{webhook.url}</p>
+              {(getWebhookKind(webhook.kind).configFields || [])
+                .filter((field) => !field.secret && !field.options && webhook.config?.[field.key])
+                .map((field) => (
+                  <p key={field.key} className="text-small text-default-400 font-mono break-all">
+                    {field.label}: {webhook.config[field.key]}
+                  </p>
+                ))}
             </div>
 
             {/* Actions Section */}
@@ -262,20 +249,20 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
               </div>
 
               <div className="flex items-center gap-3">
-                <Tooltip content={useCustomTemplate ? "Using custom template" : "Using default template"}>
-                  <div className="flex items-center gap-2">
-                    <Switch
-                      size="sm"
-                      isSelected={useCustomTemplate}
-                      onValueChange={handleTemplateToggle}
-                      classNames={{
-                        wrapper: "group-data-[selected=true]:bg-secondary",
-                      }}
-                    >
-                      Custom Template
-                    </Switch>
-                  </div>
-                </Tooltip>
+                <Select
+                  size="sm"
+                  label="Template"
+                  className="w-48"
+                  selectedKeys={[webhook.template_id ? String(webhook.template_id) : DEFAULT_TEMPLATE]}
+                  onChange={(e) => e.target.value && handleTemplateChange(e.target.value)}
+                  disallowEmptySelection
+                >
+                  {templates.map((template) => (
+                    <SelectItem key={template.is_default ? DEFAULT_TEMPLATE : String(template.id)} value={template.id}>
+                      {template.name}
+                    </SelectItem>
+                  ))}
+                </Select>
               </div>
             </div>
           </div>
@@ -283,10 +270,7 @@ export default function WebhookCard({ webhook, onDelete, onUpdate, existingWebho
       </Card>
 
       {/* Delete Confirmation Modal */}
-      <DeleteConfirmationModal isOpen={isDeleteModalOpen} onClose={() => setIsDeleteModalOpen(false)} onConfirm={handleConfirmDelete} title="Delete Webhook" message="This will permanently delete this webhook and remove all mod assignments associated with it as well the custom template if one is created for this webhook. Any future updates for assigned mods will no longer be sent to this webhook." itemType="webhook" icon={Trash2} iconColor="danger" />
-
-      {/* Template Disable Confirmation Modal */}
-      <DeleteConfirmationModal isOpen={isTemplateModalOpen} onClose={() => setIsTemplateModalOpen(false)} onConfirm={handleConfirmTemplateDisable} title="Disable Custom Template" message="This will permanently delete the custom template for this webhook. The webhook will revert to using the default template for all notifications. This action cannot be reversed, and you'll need to recreate the template if you want to use it again." itemType="template" icon={FileText} iconColor="warning" />
+      <DeleteConfirmationModal isOpen={isDeleteModalOpen} onClose={() => setIsDeleteModalOpen(false)} onConfirm={handleConfirmDelete} title="Delete Webhook" message="This will permanently delete this webhook and remove all mod assignments associated with it. Its template stays in the template library. Any future updates for assigned mods will no longer be sent to this webhook." itemType="webhook" icon={Trash2} iconColor="danger" />
     </>
   );
 }
@@ -0,0 +1,37 @@
+"use client";
+
+import { Input, Select, SelectItem } from "@nextui-org/react";
+import { KeyRound } from "lucide-react";
+
+// Renders the service-specific settings declared in a webhook kind's `configFields`
+export default function WebhookConfigFields({ fields, config, onChange, error }) {
+  return (
+    <>
+      {fields.map((field) =>
+        field.options ? (
+          <Select key={field.key} label={field.label} selectedKeys={[config?.[field.key] || field.options[0].value]} onChange={(e) => e.target.value && onChange({ ...config, [field.key]: e.target.value })} description={field.description} disallowEmptySelection>
+            {field.options.map((option) => (
+              <SelectItem key={option.value} value={option.value}>
+                {option.label}
+              </SelectItem>
+            ))}
+          </Select>
+        ) : (
+          <Input
+            key={field.key}
+            label={field.label}
+            placeholder={field.placeholder}
+            type={field.secret ? "password" : "text"}
+            value={config?.[field.key] ?? ""}
+            onChange={(e) => onChange({ ...config, [field.key]: e.target.value })}
+            startContent={field.secret && <KeyRound size={16} className="text-default-400 shrink-0" />}
+            description={field.description}
+            isRequired={field.required}
+            isInvalid={field.required && !!error && !String(config?.[field.key] ?? "").trim()}
+          />
+        )
+      )}
+      {error && <p className="text-danger text-xs">{error}</p>}
+    </>
+  );
+}
@@ -29,7 +29,6 @@ import {
   MessageSquare,
   Eye,
   FileText,
-  Crown,
   Bot,
   Image as ImageIcon,
   Calendar,
@@ -42,8 +41,9 @@ import { invoke } from "@tauri-apps/api/tauri";
 import { motion, AnimatePresence } from "framer-motion";
 import DiscordPreview from "./DiscordPreview";
 import ColorPicker from "./ColorPicker";
+import { getErrorMessage } from "@/utils/errors";
 
-const SaveNotification = ({ status, onClose }) => {
+const SaveNotification = ({ status, message, onClose }) => {
   useEffect(() => {
     const timer = setTimeout(onClose, 3000);
     return () => clearTimeout(timer);
@@ -64,7 +64,7 @@ const SaveNotification = ({ status, onClose }) => {
       <span className="text-sm font-medium">
         {status === "success"
           ? "Changes saved successfully"
-          : "Failed to save changes"}
+          : message || "Failed to save changes"}
       </span>
     </motion.div>
   );
@@ -79,7 +79,7 @@ const VariableReference = ({ type, variables }) => (
           key={variable.name}
           className="p-2 bg-default-50 rounded-lg flex items-center gap-2"
         >
-          <code className="text-sm font-mono text-primary bg-primary/10 px-1.5 py-0.5 rounded">{`{${variable.name}}`}</code>
+          <code className="text-sm font-mono text-primary bg-primary/10 px-1.5 py-0.5 rounded">{variable.code || `{${variable.name}}`}</code>
           <span className="text-sm text-default-600">
             {variable.description}
           </span>
@@ -99,6 +99,22 @@ const TemplateVariablesHelp = () => {
     { name: "modAuthorName", description: "Mod author name" },
     { name: "modURL", description: "URL to mod page" },
     { name: "changelog", description: "Latest version changelog" },
+    { name: "fileID", description: "ID of the new file" },
+    { name: "fileDisplayName", description: "Display name of the new file" },
+    { name: "fileDate", description: "Upload time of the new file" },
+    { name: "fileReleaseType", description: "Release type (release, beta, alpha)" },
+    { name: "fileGameVersions", description: "Game versions and loaders of the new file" },
+  ];
+
+  const templateExpressions = [
+    { name: "if", code: "{{#if changelog}}...{{/if}}", description: "Only shown when the value exists" },
+    { name: "authors", code: "{{#each authors}}{{this}}{{/each}}", description: "Loop over all mod authors" },
+    { name: "files", code: "{{#each files}}{{displayName}}{{/each}}", description: "Loop over the mod's latest files" },
+    { name: "truncate", code: "{{truncate changelog 200}}", description: "Cut text to a number of characters" },
+    { name: "upper", code: "{{upper modName}}", description: "Upper case (lower works the same way)" },
+    { name: "date", code: '{{date releasedAt "%Y-%m-%d"}}', description: "Format a date" },
+    { name: "join", code: '{{join gameVersions ", "}}', description: "Join a list with a separator" },
+    { name: "default", code: '{{default fileReleaseType "release"}}', description: "Fallback for empty values" },
   ];
 
   const discordVariables = [
@@ -128,6 +144,11 @@ const TemplateVariablesHelp = () => {
           <ScrollShadow className="max-h-[400px] space-y-6" hideScrollBar>
             <VariableReference type="Mod Variables" variables={modVariables} />
             <Divider />
+            <VariableReference
+              type="Template Expressions"
+              variables={templateExpressions}
+            />
+            <Divider />
             <VariableReference
               type="Discord Variables"
               variables={discordVariables}
@@ -143,13 +164,13 @@ export default function WebhookEditor({
   template: initialTemplate,
   webhook,
   onSave,
-  isDefault = false,
 }) {
   const [template, setTemplate] = useState(initialTemplate || null);
   const [fields, setFields] = useState([]);
   const [isSaving, setIsSaving] = useState(false);
   const [showPreview, setShowPreview] = useState(false);
   const [saveStatus, setSaveStatus] = useState(null);
+  const [saveError, setSaveError] = useState(null);
   const prevTemplateRef = useRef(null);
 
   useEffect(() => {
@@ -171,8 +192,6 @@ export default function WebhookEditor({
       const updatedTemplate = {
         ...template,
         embed_fields: JSON.stringify(fields),
-        is_default: isDefault,
-        webhook_id: webhook?.id || null,
       };
 
       // Ensure we're passing the complete template
@@ -185,6 +204,7 @@ export default function WebhookEditor({
       }
     } catch (error) {
       console.error("Failed to save webhook template:", error);
+      setSaveError(getErrorMessage(error));
       setSaveStatus("error");
     } finally {
       setIsSaving(false);
@@ -224,20 +244,6 @@ export default function WebhookEditor({
 
   if (!template) return null;
 
-  if (!isDefault && !webhook?.use_custom_template) {
-    return (
-      <div className="flex flex-col items-center justify-center p-8 border border-dashed rounded-lg gap-4">
-        <Crown size={24} className="text-primary" />
-        <div className="text-center">
-          <p>This webhook is using the default template</p>
-          <p className="text-sm text-default-500 mt-2">
-            Enable custom template in webhook settings to customize it
-          </p>
-        </div>
-      </div>
-    );
-  }
-
   return (
     <>
       <div className="space-y-6">
@@ -548,7 +554,7 @@ export default function WebhookEditor({
           {/* Preview Section */}
           {showPreview && (
             <ScrollShadow className="h-full">
-              <DiscordPreview template={template} fields={fields} />
+              <DiscordPreview template={template} fields={fields} webhook={webhook} />
             </ScrollShadow>
           )}
         </div>
@@ -559,6 +565,7 @@ export default function WebhookEditor({
         {saveStatus && (
           <SaveNotification
             status={saveStatus}
+            message={saveError}
             onClose={() => setSaveStatus(null)}
           />
         )}
@@ -1,9 +1,8 @@
 "use client";
 
-import { createContext, useContext, useEffect, useRef, useState } from "react";
+import { createContext, useContext, useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/tauri";
-import { emit, listen } from "@tauri-apps/api/event";
-import { useModUpdateChecker } from "@/hooks/useModUpdateChecker";
+import { listen } from "@tauri-apps/api/event";
 
 const UpdateServiceContext = createContext(null);
 
@@ -12,88 +11,66 @@ export function UpdateServiceProvider({ children }) {
   const [nextCheckTime, setNextCheckTime] = useState(null);
   const [updateInterval, setUpdateInterval] = useState(30);
   const [isChecking, setIsChecking] = useState(false);
-  const timeoutRef = useRef(null);
-  const processedEventsRef = useRef(new Set());
-  const { checkForUpdates } = useModUpdateChecker();
+  const [isPaused, setIsPaused] = useState(false);
+  const [progress, setProgress] = useState(null);
+
+  // The scheduler runs in the Rust backend; we only mirror its status here
+  const applyStatus = (status) => {
+    setIsChecking(status.is_checking);
+    setIsPaused(status.paused);
+    if (status.interval) setUpdateInterval(status.interval);
+    setLastChecked(status.last_checked ? new Date(status.last_checked) : null);
+    setNextCheckTime(status.next_check_time ? new Date(status.next_check_time) : null);
+  };
 
   const performCheck = async () => {
-    if (isChecking) return;
-
-    setIsChecking(true);
     try {
-      const interval = await invoke("get_update_interval");
-      await checkForUpdates(interval);
-      setLastChecked(new Date());
-      scheduleNextCheck(interval);
+      await invoke("check_now");
     } catch (error) {
-      console.error("Failed to perform update check:", error);
-    } finally {
-      setIsChecking(false);
+      console.error("Failed to start update check:", error);
+      throw error;
     }
   };
 
-  const scheduleNextCheck = (interval) => {
-    if (timeoutRef.current) {
-      clearTimeout(timeoutRef.current);
-    }
-
-    const nextCheck = new Date(Date.now() + interval * 60 * 1000);
-    setNextCheckTime(nextCheck);
-    localStorage.setItem("nextCheckTime", nextCheck.toISOString());
+  const pause = async () => {
+    const status = await invoke("pause_update_service");
+    applyStatus(status);
+  };
 
-    timeoutRef.current = setTimeout(() => {
-      performCheck();
-    }, interval * 60 * 1000);
+  const resume = async () => {
+    const status = await invoke("resume_update_service");
+    applyStatus(status);
   };
 
   useEffect(() => {
-    let unlistenInterval;
-    let unlistenUpdate;
-    let cleanupInterval;
+    let unlistenStatus;
+    let unlistenStarted;
+    let unlistenProgress;
+    let unlistenCompleted;
 
     const setup = async () => {
       try {
-        // Listen for interval changes
-        unlistenInterval = await listen("update_interval_changed", async (event) => {
-          const newInterval = event.payload.interval;
-          setUpdateInterval(newInterval);
-          scheduleNextCheck(newInterval);
+        unlistenStatus = await listen("update_service_status", (event) => {
+          applyStatus(event.payload);
         });
 
-        // Listen for manual update checks
-        unlistenUpdate = await listen("update_check_completed", (event) => {
-          if (event.payload.eventId && processedEventsRef.current.has(event.payload.eventId)) {
-            return;
-          }
+        unlistenStarted = await listen("update_check_started", (event) => {
+          setIsChecking(true);
+          setProgress({ checked: 0, total: event.payload.total });
+        });
 
-          if (event.payload.eventId) {
-            processedEventsRef.current.add(event.payload.eventId);
-          }
+        unlistenProgress = await listen("update_check_progress", (event) => {
+          setProgress({ checked: event.payload.checked, total: event.payload.total });
+        });
 
+        unlistenCompleted = await listen("update_check_completed", (event) => {
+          setIsChecking(false);
+          setProgress(null);
           setLastChecked(new Date(event.payload.timestamp));
-          scheduleNextCheck(event.payload.interval);
         });
 
-        // Clear processed events periodically
-        cleanupInterval = setInterval(() => {
-          processedEventsRef.current.clear();
-        }, 60000);
-
-        // Initialize the service
-        const interval = await invoke("get_update_interval");
-        setUpdateInterval(interval);
-
-        // Check if we need to perform an immediate check
-        const storedNextCheck = localStorage.getItem("nextCheckTime");
-        if (!storedNextCheck || new Date(storedNextCheck) <= new Date()) {
-          performCheck();
-        } else {
-          setNextCheckTime(new Date(storedNextCheck));
-          const timeUntilNext = new Date(storedNextCheck) - new Date();
-          timeoutRef.current = setTimeout(() => {
-            performCheck();
-          }, timeUntilNext);
-        }
+        const status = await invoke("start_update_service");
+        applyStatus(status);
       } catch (error) {
         console.error("Failed to setup update service:", error);
       }
@@ -102,10 +79,10 @@ export function UpdateServiceProvider({ children }) {
     setup();
 
     return () => {
-      if (unlistenInterval) unlistenInterval();
-      if (unlistenUpdate) unlistenUpdate();
-      if (cleanupInterval) clearInterval(cleanupInterval);
-      if (timeoutRef.current) clearTimeout(timeoutRef.current);
+      if (unlistenStatus) unlistenStatus();
+      if (unlistenStarted) unlistenStarted();
+      if (unlistenProgress) unlistenProgress();
+      if (unlistenCompleted) unlistenCompleted();
     };
   }, []);
 
@@ -113,8 +90,12 @@ export function UpdateServiceProvider({ children }) {
     lastChecked,
     nextCheckTime,
     isChecking,
+    isPaused,
+    progress,
     updateInterval,
     performCheck,
+    pause,
+    resume,
   };
 
   return <UpdateServiceContext.Provider value={value}>{children}</UpdateServiceContext.Provider>;
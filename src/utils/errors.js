@@ -0,0 +1,16 @@
+// Tauri commands reject with a serialized TrackerError: { code, message, details }.
+// These helpers also accept plain strings and Error objects thrown on the frontend.
+
+export function getErrorCode(error) {
+  return error && typeof error === "object" && typeof error.code === "string" ? error.code : null;
+}
+
+export function getErrorMessage(error) {
+  if (!error) return "Unknown error";
+  if (typeof error === "string") return error;
+  return error.message || String(error);
+}
+
+export function getRetryAfter(error) {
+  return error?.details?.retryAfter ?? null;
+}
@@ -0,0 +1,262 @@
+// Per-service metadata for the webhook kinds the backend can deliver to.
+
+export const WEBHOOK_KINDS = {
+  discord: {
+    label: "Discord",
+    urlLabel: "Discord Webhook URL",
+    urlPlaceholder: "https://discord.com/api/webhooks/...",
+    urlDescription: "The Discord webhook URL for sending notifications",
+    urlPrefixes: ["https://discord.com/api/webhooks/"],
+    supportsIdentity: true,
+    defaultConfig: { digest_window: "0", digest_style: "embeds", edit_window: "0" },
+    configFields: [
+      {
+        key: "digest_window",
+        label: "Delivery",
+        description: "A digest collects the updates found within the window into one message",
+        options: [
+          { value: "0", label: "One message per update" },
+          { value: "5", label: "Digest every 5 minutes" },
+          { value: "15", label: "Digest every 15 minutes" },
+          { value: "30", label: "Digest every 30 minutes" },
+          { value: "60", label: "Digest every hour" },
+          { value: "180", label: "Digest every 3 hours" },
+          { value: "360", label: "Digest every 6 hours" },
+          { value: "1440", label: "Digest every day" },
+        ],
+      },
+      {
+        key: "digest_style",
+        label: "Digest Style",
+        description: "How a digest shows its updates; long digests are split over several messages",
+        options: [
+          { value: "embeds", label: "One embed per mod (up to 10 per message)" },
+          { value: "list", label: "A single embed listing the mods" },
+        ],
+      },
+      {
+        key: "edit_window",
+        label: "Quick Re-updates",
+        description: "When a mod updates again shortly after its message, edit that message instead of posting a new one",
+        options: [
+          { value: "0", label: "Always post a new message" },
+          { value: "15", label: "Edit within 15 minutes" },
+          { value: "30", label: "Edit within 30 minutes" },
+          { value: "60", label: "Edit within an hour" },
+          { value: "360", label: "Edit within 6 hours" },
+          { value: "1440", label: "Edit within a day" },
+        ],
+      },
+    ],
+  },
+  slack: {
+    label: "Slack",
+    urlLabel: "Slack Webhook URL",
+    urlPlaceholder: "https://hooks.slack.com/services/...",
+    urlDescription: "The Slack incoming webhook URL for sending notifications",
+    urlPrefixes: ["https://hooks.slack.com/"],
+    supportsIdentity: true,
+  },
+  telegram: {
+    label: "Telegram",
+    urlLabel: "Bot API URL",
+    urlPlaceholder: "https://api.telegram.org",
+    urlDescription: "Telegram Bot API base URL; change it only to use a self-hosted Bot API server",
+    urlPrefixes: ["https://", "http://"],
+    defaultUrl: "https://api.telegram.org",
+    // Several bots and chats can share one Bot API server
+    sharedUrl: true,
+    supportsIdentity: false,
+    defaultConfig: { bot_token: "", chat_id: "", parse_mode: "HTML" },
+    configFields: [
+      { key: "bot_token", label: "Bot Token", placeholder: "123456789:AA...", description: "The token BotFather gave you for the bot", secret: true, required: true },
+      { key: "chat_id", label: "Chat ID", placeholder: "-1001234567890 or @channelname", description: "The chat, group or channel the bot posts to", required: true },
+      {
+        key: "parse_mode",
+        label: "Formatting",
+        description: "How messages are formatted; values are escaped for the chosen mode",
+        options: [
+          { value: "HTML", label: "HTML" },
+          { value: "MarkdownV2", label: "MarkdownV2" },
+        ],
+      },
+    ],
+  },
+  http: {
+    label: "HTTP",
+    urlLabel: "Endpoint URL",
+    urlPlaceholder: "https://example.com/hooks/mod-updates",
+    urlDescription: "The endpoint that receives the JSON body",
+    urlPrefixes: ["https://", "http://"],
+    supportsIdentity: false,
+    defaultConfig: {
+      method: "POST",
+      headers: [],
+      body_template: `{
+  "event": "mod_updated",
+  "mod": {
+    "id": {modID},
+    "name": "{modName}",
+    "author": "{modAuthorName}",
+    "url": "{modURL}"
+  },
+  "file": {
+    "name": "{lastestModFileName}",
+    "releaseType": "{fileReleaseType}",
+    "releasedAt": "{newReleaseDate}"
+  },
+  "changelog": "{changelog}"
+}`,
+    },
+  },
+  matrix: {
+    label: "Matrix",
+    urlLabel: "Homeserver URL",
+    urlPlaceholder: "https://matrix.org",
+    urlDescription: "The homeserver the bot account lives on",
+    urlPrefixes: ["https://", "http://"],
+    sharedUrl: true,
+    supportsIdentity: false,
+    defaultConfig: { access_token: "", room_id: "" },
+    configFields: [
+      { key: "access_token", label: "Access Token", placeholder: "syt_...", description: "Access token of the bot account posting the messages", secret: true, required: true },
+      { key: "room_id", label: "Room ID", placeholder: "!abcdefghijklmnop:matrix.org", description: "The internal ID of the room, found in the room's advanced settings", required: true },
+    ],
+  },
+  ntfy: {
+    label: "ntfy",
+    urlLabel: "Topic URL",
+    urlPlaceholder: "https://ntfy.sh/my-mod-updates",
+    urlDescription: "The full URL of the ntfy topic to publish to",
+    urlPrefixes: ["https://", "http://"],
+    supportsIdentity: false,
+    defaultConfig: { priority: "3", tags: "package", token: "", username: "", password: "" },
+    configFields: [
+      {
+        key: "priority",
+        label: "Priority",
+        description: "Higher priorities can bypass Do Not Disturb on the phone",
+        options: [
+          { value: "1", label: "1 - Min" },
+          { value: "2", label: "2 - Low" },
+          { value: "3", label: "3 - Default" },
+          { value: "4", label: "4 - High" },
+          { value: "5", label: "5 - Urgent" },
+        ],
+      },
+      { key: "tags", label: "Tags", placeholder: "package, warning", description: "Comma-separated tags; tags matching an emoji short code are shown as emojis" },
+      { key: "token", label: "Access Token", placeholder: "tk_...", description: "Optional, for protected topics", secret: true },
+      { key: "username", label: "Username", placeholder: "Optional", description: "Optional, used instead of an access token" },
+      { key: "password", label: "Password", placeholder: "Optional", secret: true },
+    ],
+  },
+  gotify: {
+    label: "Gotify",
+    urlLabel: "Server URL",
+    urlPlaceholder: "https://gotify.example.com",
+    urlDescription: "The URL of your Gotify server",
+    urlPrefixes: ["https://", "http://"],
+    sharedUrl: true,
+    supportsIdentity: false,
+    defaultConfig: { app_token: "", priority: "5", tags: "" },
+    configFields: [
+      { key: "app_token", label: "App Token", placeholder: "A...", description: "Token of the Gotify application the messages are sent as", secret: true, required: true },
+      {
+        key: "priority",
+        label: "Priority",
+        description: "Gotify clients decide how to alert based on the priority",
+        options: [
+          { value: "0", label: "0 - Silent" },
+          { value: "2", label: "2 - Low" },
+          { value: "5", label: "5 - Normal" },
+          { value: "8", label: "8 - High" },
+          { value: "10", label: "10 - Max" },
+        ],
+      },
+      { key: "tags", label: "Tags", placeholder: "mods, updates", description: "Comma-separated tags, sent as a custom extra" },
+    ],
+  },
+  email: {
+    label: "Email",
+    urlLabel: "SMTP Server",
+    urlPlaceholder: "smtp.example.com",
+    urlDescription: "Host name of the SMTP server that sends the emails",
+    // Any host name is accepted, so there is no URL prefix to check
+    urlPrefixes: [],
+    sharedUrl: true,
+    supportsIdentity: false,
+    defaultConfig: { port: "", tls_mode: "starttls", username: "", password: "", from: "", to: "", digest: "off", digest_hour: "8" },
+    configFields: [
+      {
+        key: "tls_mode",
+        label: "Encryption",
+        description: "Use None only for a local test server such as MailHog",
+        options: [
+          { value: "starttls", label: "STARTTLS (port 587)" },
+          { value: "tls", label: "TLS (port 465)" },
+          { value: "none", label: "None (port 25)" },
+        ],
+      },
+      { key: "port", label: "Port", placeholder: "Default for the encryption mode", description: "Optional, e.g. 1025 for MailHog" },
+      { key: "username", label: "Username", placeholder: "Optional", description: "Optional, for servers that require authentication" },
+      { key: "password", label: "Password", placeholder: "Optional", secret: true },
+      { key: "from", label: "From", placeholder: "Mod Tracker <tracker@example.com>", description: "The sender address", required: true },
+      { key: "to", label: "To", placeholder: "admin@example.com, owner@example.com", description: "One or more recipients, separated by commas", required: true },
+      {
+        key: "digest",
+        label: "Delivery",
+        description: "A daily digest collects the day's updates into a single email",
+        options: [
+          { value: "off", label: "One email per update" },
+          { value: "daily", label: "Daily digest" },
+        ],
+      },
+      {
+        key: "digest_hour",
+        label: "Digest Time",
+        description: "When the daily digest is sent, in local time",
+        options: [
+          { value: "0", label: "00:00" },
+          { value: "1", label: "01:00" },
+          { value: "2", label: "02:00" },
+          { value: "3", label: "03:00" },
+          { value: "4", label: "04:00" },
+          { value: "5", label: "05:00" },
+          { value: "6", label: "06:00" },
+          { value: "7", label: "07:00" },
+          { value: "8", label: "08:00" },
+          { value: "9", label: "09:00" },
+          { value: "10", label: "10:00" },
+          { value: "11", label: "11:00" },
+          { value: "12", label: "12:00" },
+          { value: "13", label: "13:00" },
+          { value: "14", label: "14:00" },
+          { value: "15", label: "15:00" },
+          { value: "16", label: "16:00" },
+          { value: "17", label: "17:00" },
+          { value: "18", label: "18:00" },
+          { value: "19", label: "19:00" },
+          { value: "20", label: "20:00" },
+          { value: "21", label: "21:00" },
+          { value: "22", label: "22:00" },
+          { value: "23", label: "23:00" },
+        ],
+      },
+    ],
+  },
+};
+
+export function getWebhookKind(kind) {
+  return WEBHOOK_KINDS[kind] || WEBHOOK_KINDS.discord;
+}
+
+export function getMissingConfigFields(kind, config) {
+  return (getWebhookKind(kind).configFields || []).filter((field) => field.required && !String(config?.[field.key] ?? "").trim());
+}
+
+export function isValidWebhookUrl(kind, url) {
+  const { urlPrefixes } = getWebhookKind(kind);
+  return urlPrefixes.length === 0 || urlPrefixes.some((prefix) => url.startsWith(prefix));
+}
+
+export const HTTP_METHODS = ["POST", "PUT", "PATCH"];